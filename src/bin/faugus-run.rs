@@ -1,27 +1,59 @@
 // faugus-run - Command-line launcher for Faugus games
-// Usage: faugus-run --game <gameid>
+// Usage: faugus-run --game <gameid> [--profile <name>] [--dry-run] | --migrate-library [--dry-run]
 
 use anyhow::{bail, Context, Result};
+use faugus_launcher_rs::config::library_store::LibraryStore;
 use faugus_launcher_rs::config::Game;
 use faugus_launcher_rs::launcher::game_launcher::GameLauncher;
+use faugus_launcher_rs::launcher::process_tree;
 use std::env;
 use std::process::ExitCode;
+use std::time::Duration;
 use tracing::{error, info};
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-/// Parse command-line arguments and extract game ID
+/// How often a game that outlived umu-run is checked for exit
+const FOLLOW_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Action requested on the command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Command {
+    /// Launch the game with the given ID, optionally with a named profile;
+    /// with dry_run, only print what would be run
+    Launch {
+        game_id: String,
+        profile: Option<String>,
+        dry_run: bool,
+    },
+    /// Upgrade games.json to the current schema (or only report with dry_run)
+    MigrateLibrary { dry_run: bool },
+}
+
+/// Parse command-line arguments into a command
 /// Unit-testable: accepts any iterator over strings
-fn parse_args(args: impl Iterator<Item = String>) -> Result<String> {
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Command> {
     let args_vec: Vec<String> = args.collect();
+    let program = args_vec.first().map(|s| s.as_str()).unwrap_or("faugus-run");
+
+    // faugus-run --migrate-library [--dry-run]
+    if args_vec.get(1).map(|s| s.as_str()) == Some("--migrate-library") {
+        return match &args_vec[2..] {
+            [] => Ok(Command::MigrateLibrary { dry_run: false }),
+            [flag] if flag == "--dry-run" => Ok(Command::MigrateLibrary { dry_run: true }),
+            _ => bail!("Usage: {} --migrate-library [--dry-run]", program),
+        };
+    }
 
-    // Expected: faugus-run --game <id>
-    if args_vec.len() != 3 {
+    // Expected: faugus-run --game <id> [--profile <name>] [--dry-run]
+    if args_vec.len() < 3 {
         bail!(
-            "Usage: {} --game <gameid>\n\
-             Example: {} --game my-game-id",
-            args_vec.first().map(|s| s.as_str()).unwrap_or("faugus-run"),
-            args_vec.first().map(|s| s.as_str()).unwrap_or("faugus-run")
+            "Usage: {} --game <gameid> [--profile <name>] [--dry-run]\n\
+             Usage: {} --migrate-library [--dry-run]\n\
+             Example: {} --game my-game-id --profile Benchmark",
+            program,
+            program,
+            program
         );
     }
 
@@ -33,7 +65,29 @@ fn parse_args(args: impl Iterator<Item = String>) -> Result<String> {
         );
     }
 
-    Ok(args_vec[2].clone())
+    let mut profile = None;
+    let mut dry_run = false;
+    let mut flags = args_vec[3..].iter();
+    while let Some(flag) = flags.next() {
+        match flag.as_str() {
+            "--profile" => match flags.next() {
+                Some(name) => profile = Some(name.clone()),
+                None => bail!("Usage: {} --game <gameid> --profile <name>", program),
+            },
+            "--dry-run" => dry_run = true,
+            _ => bail!(
+                "Unknown flag: {}\n\
+                 Expected: --profile or --dry-run",
+                flag
+            ),
+        }
+    }
+
+    Ok(Command::Launch {
+        game_id: args_vec[2].clone(),
+        profile,
+        dry_run,
+    })
 }
 
 /// Find a game by its ID in the loaded games list
@@ -65,7 +119,15 @@ async fn main() -> ExitCode {
 
 async fn run() -> Result<()> {
     // Parse command-line arguments
-    let game_id = parse_args(std::env::args().skip(1)).context("Failed to parse arguments")?;
+    let (game_id, profile, dry_run) =
+        match parse_args(std::env::args()).context("Failed to parse arguments")? {
+            Command::Launch {
+                game_id,
+                profile,
+                dry_run,
+            } => (game_id, profile, dry_run),
+            Command::MigrateLibrary { dry_run } => return migrate_library(dry_run),
+        };
     info!("Launching game: {}", game_id);
 
     // Load all games from config
@@ -80,8 +142,21 @@ async fn run() -> Result<()> {
 
     info!("Found game: {} ({})", game.title, game.gameid);
 
+    // Apply the requested or default launch profile
+    let game = game.resolve_profile(profile.as_deref())?;
+    if let Some(name) = profile.as_deref().or(game.default_profile.as_deref()) {
+        info!("Using profile: {}", name);
+    }
+
+    if dry_run {
+        let plan = GameLauncher::launch_plan(&game).context("Failed to plan launch")?;
+        print!("{}", plan);
+        println!("\n{}", plan.command_line());
+        return Ok(());
+    }
+
     // Launch the game
-    let process = GameLauncher::launch(&game)
+    let (mut process, mut child) = GameLauncher::spawn(&game)
         .await
         .context("Failed to launch game")?;
 
@@ -91,6 +166,32 @@ async fn run() -> Result<()> {
     );
     info!("Game launched successfully");
 
+    // Stay around until the game exits so the session counts towards playtime
+    let status = child.wait().await;
+    // Games started through a launcher stub keep running after umu-run exits
+    while process.refresh(&process_tree::snapshot()) {
+        tokio::time::sleep(FOLLOW_INTERVAL).await;
+    }
+    let _ = GameLauncher::remove_process(&process.gameid);
+    GameLauncher::finish_session(&process);
+    GameLauncher::run_post_exit_hooks(&process.gameid);
+    let status = status.context("Failed to wait for game process")?;
+    info!("Game exited with {}", status);
+
+    Ok(())
+}
+
+/// Upgrade games.json to the current schema and print what changed
+fn migrate_library(dry_run: bool) -> Result<()> {
+    let report = LibraryStore::open()
+        .migrate(dry_run)
+        .context("Failed to migrate games library")?;
+
+    if dry_run && !report.is_empty() {
+        println!("Dry run, no changes written:");
+    }
+    println!("{}", report);
+
     Ok(())
 }
 
@@ -124,10 +225,83 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(
             result.expect("Should have returned Ok"),
-            "test-id".to_string()
+            Command::Launch {
+                game_id: "test-id".to_string(),
+                profile: None,
+                dry_run: false,
+            }
         );
     }
 
+    #[test]
+    fn test_parse_args_profile() {
+        let args = ["faugus-run", "--game", "test-id", "--profile", "DX11 safe"]
+            .into_iter()
+            .map(String::from);
+        assert_eq!(
+            parse_args(args).expect("Should parse"),
+            Command::Launch {
+                game_id: "test-id".to_string(),
+                profile: Some("DX11 safe".to_string()),
+                dry_run: false,
+            }
+        );
+
+        let args = [
+            "faugus-run",
+            "--game",
+            "test-id",
+            "--dry-run",
+            "--profile",
+            "A",
+        ]
+        .into_iter()
+        .map(String::from);
+        assert_eq!(
+            parse_args(args).expect("Should parse"),
+            Command::Launch {
+                game_id: "test-id".to_string(),
+                profile: Some("A".to_string()),
+                dry_run: true,
+            }
+        );
+
+        let args = ["faugus-run", "--game", "test-id", "--profile"]
+            .into_iter()
+            .map(String::from);
+        assert!(parse_args(args).is_err());
+
+        let args = ["faugus-run", "--game", "test-id", "--mode", "safe"]
+            .into_iter()
+            .map(String::from);
+        let error_msg = parse_args(args).expect_err("Should fail").to_string();
+        assert!(error_msg.contains("--profile"));
+    }
+
+    #[test]
+    fn test_parse_args_migrate_library() {
+        let args = ["faugus-run", "--migrate-library", "--dry-run"]
+            .into_iter()
+            .map(String::from);
+        assert_eq!(
+            parse_args(args).expect("Should parse"),
+            Command::MigrateLibrary { dry_run: true }
+        );
+
+        let args = ["faugus-run", "--migrate-library"]
+            .into_iter()
+            .map(String::from);
+        assert_eq!(
+            parse_args(args).expect("Should parse"),
+            Command::MigrateLibrary { dry_run: false }
+        );
+
+        let args = ["faugus-run", "--migrate-library", "--force"]
+            .into_iter()
+            .map(String::from);
+        assert!(parse_args(args).is_err());
+    }
+
     #[test]
     fn test_parse_args_missing_flag() {
         // Simulate: faugus-run --invalid test-id
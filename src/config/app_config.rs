@@ -8,6 +8,7 @@ use std::fs;
 use std::path::PathBuf;
 
 use crate::config::paths::Paths;
+use crate::config::wrappers::{self, Wrapper};
 
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +52,9 @@ pub struct AppConfig {
     /// Interface mode (List, Blocks, Banners)
     pub interface_mode: InterfaceMode,
 
+    /// Order of games in the library
+    pub sort_mode: SortMode,
+
     /// Start maximized
     pub start_maximized: bool,
 
@@ -66,6 +70,9 @@ pub struct AppConfig {
     /// Enable logging
     pub enable_logging: bool,
 
+    /// Session logs kept per game
+    pub log_sessions: usize,
+
     /// Use Wayland driver
     pub wayland_driver: bool,
 
@@ -83,6 +90,27 @@ pub struct AppConfig {
 
     /// Show hidden games
     pub show_hidden: bool,
+
+    /// Command run before every game launch
+    pub pre_launch_hook: String,
+
+    /// Command run after every game exits
+    pub post_exit_hook: String,
+
+    /// Seconds a hook may run before it is killed
+    pub hook_timeout: u64,
+
+    /// Seconds a stopped game gets to exit before it is killed
+    pub stop_grace_period: u64,
+
+    /// Refuse to launch a game whose prefix is used by a running game
+    pub prevent_shared_prefix: bool,
+
+    /// Don't launch a game if the global pre-launch hook fails
+    pub hook_abort_on_failure: bool,
+
+    /// Wrappers applied to every game, around the game's own
+    pub wrappers: Vec<Wrapper>,
 }
 
 /// Interface display modes
@@ -116,6 +144,63 @@ impl fmt::Display for InterfaceMode {
     }
 }
 
+/// Library sort modes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum SortMode {
+    #[default]
+    Title,
+    LastPlayed,
+    Playtime,
+    DateAdded,
+    Runner,
+}
+
+impl SortMode {
+    pub const ALL: [SortMode; 5] = [
+        SortMode::Title,
+        SortMode::LastPlayed,
+        SortMode::Playtime,
+        SortMode::DateAdded,
+        SortMode::Runner,
+    ];
+
+    /// Parse the value stored in config.ini
+    fn from_config(value: &str) -> Self {
+        match value {
+            "LastPlayed" => SortMode::LastPlayed,
+            "Playtime" => SortMode::Playtime,
+            "DateAdded" => SortMode::DateAdded,
+            "Runner" => SortMode::Runner,
+            _ => SortMode::Title,
+        }
+    }
+}
+
+impl fmt::Display for SortMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                SortMode::Title => "Title",
+                SortMode::LastPlayed => "Last played",
+                SortMode::Playtime => "Playtime",
+                SortMode::DateAdded => "Date added",
+                SortMode::Runner => "Runner",
+            }
+        )
+    }
+}
+
+/// Default hook timeout in seconds
+pub const DEFAULT_HOOK_TIMEOUT: u64 = 60;
+
+/// Default grace period for stopping a game in seconds
+pub const DEFAULT_STOP_GRACE_PERIOD: u64 = 10;
+
+/// Default number of session logs kept per game
+pub const DEFAULT_LOG_SESSIONS: usize = 10;
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
@@ -132,17 +217,26 @@ impl Default for AppConfig {
             start_boot: false,
             mono_icon: false,
             interface_mode: InterfaceMode::List,
+            sort_mode: SortMode::Title,
             start_maximized: false,
             start_fullscreen: false,
             show_labels: false,
             smaller_banners: false,
             enable_logging: false,
+            log_sessions: DEFAULT_LOG_SESSIONS,
             wayland_driver: false,
             enable_hdr: false,
             enable_wow64: false,
             language: String::from("en_US"),
             logging_warning: false,
             show_hidden: false,
+            pre_launch_hook: String::new(),
+            post_exit_hook: String::new(),
+            hook_timeout: DEFAULT_HOOK_TIMEOUT,
+            stop_grace_period: DEFAULT_STOP_GRACE_PERIOD,
+            prevent_shared_prefix: false,
+            hook_abort_on_failure: false,
+            wrappers: Vec::new(),
         }
     }
 }
@@ -172,6 +266,12 @@ impl AppConfig {
 
             if let Some((key, value)) = line.split_once('=') {
                 let key = key.trim();
+                // Commands keep their inner quotes; only the surrounding pair is removed
+                let command = value.trim();
+                let command = command
+                    .strip_prefix('"')
+                    .and_then(|c| c.strip_suffix('"'))
+                    .unwrap_or(command);
                 let value = value.trim().trim_matches('"');
 
                 match key {
@@ -194,17 +294,37 @@ impl AppConfig {
                             _ => InterfaceMode::List,
                         };
                     }
+                    "sort-mode" => config.sort_mode = SortMode::from_config(value),
                     "start-maximized" => config.start_maximized = value.parse().unwrap_or(false),
                     "start-fullscreen" => config.start_fullscreen = value.parse().unwrap_or(false),
                     "show-labels" => config.show_labels = value.parse().unwrap_or(false),
                     "smaller-banners" => config.smaller_banners = value.parse().unwrap_or(false),
                     "enable-logging" => config.enable_logging = value.parse().unwrap_or(false),
+                    "log-sessions" => {
+                        config.log_sessions = value.parse().unwrap_or(DEFAULT_LOG_SESSIONS)
+                    }
                     "wayland-driver" => config.wayland_driver = value.parse().unwrap_or(false),
                     "enable-hdr" => config.enable_hdr = value.parse().unwrap_or(false),
                     "enable-wow64" => config.enable_wow64 = value.parse().unwrap_or(false),
                     "language" => config.language = value.to_string(),
                     "logging-warning" => config.logging_warning = value.parse().unwrap_or(false),
                     "show-hidden" => config.show_hidden = value.parse().unwrap_or(false),
+                    "pre-launch-hook" => config.pre_launch_hook = command.to_string(),
+                    "post-exit-hook" => config.post_exit_hook = command.to_string(),
+                    "hook-timeout" => {
+                        config.hook_timeout = value.parse().unwrap_or(DEFAULT_HOOK_TIMEOUT)
+                    }
+                    "prevent-shared-prefix" => {
+                        config.prevent_shared_prefix = value.parse().unwrap_or(false)
+                    }
+                    "stop-grace-period" => {
+                        config.stop_grace_period =
+                            value.parse().unwrap_or(DEFAULT_STOP_GRACE_PERIOD)
+                    }
+                    "hook-abort-on-failure" => {
+                        config.hook_abort_on_failure = value.parse().unwrap_or(false)
+                    }
+                    "wrappers" => config.wrappers = wrappers::wrappers_from_config(command),
                     _ => {
                         tracing::warn!("Unknown config key: {}", key);
                     }
@@ -247,17 +367,35 @@ impl AppConfig {
         content.push_str(&format!("start-boot={}\n", self.start_boot));
         content.push_str(&format!("mono-icon={}\n", self.mono_icon));
         content.push_str(&format!("interface-mode={:?}\n", self.interface_mode));
+        content.push_str(&format!("sort-mode={:?}\n", self.sort_mode));
         content.push_str(&format!("start-maximized={}\n", self.start_maximized));
         content.push_str(&format!("start-fullscreen={}\n", self.start_fullscreen));
         content.push_str(&format!("show-labels={}\n", self.show_labels));
         content.push_str(&format!("smaller-banners={}\n", self.smaller_banners));
         content.push_str(&format!("enable-logging={}\n", self.enable_logging));
+        content.push_str(&format!("log-sessions={}\n", self.log_sessions));
         content.push_str(&format!("wayland-driver={}\n", self.wayland_driver));
         content.push_str(&format!("enable-hdr={}\n", self.enable_hdr));
         content.push_str(&format!("enable-wow64={}\n", self.enable_wow64));
         content.push_str(&format!("language={}\n", self.language));
         content.push_str(&format!("logging-warning={}\n", self.logging_warning));
         content.push_str(&format!("show-hidden={}\n", self.show_hidden));
+        content.push_str(&format!("pre-launch-hook=\"{}\"\n", self.pre_launch_hook));
+        content.push_str(&format!("post-exit-hook=\"{}\"\n", self.post_exit_hook));
+        content.push_str(&format!("hook-timeout={}\n", self.hook_timeout));
+        content.push_str(&format!("stop-grace-period={}\n", self.stop_grace_period));
+        content.push_str(&format!(
+            "prevent-shared-prefix={}\n",
+            self.prevent_shared_prefix
+        ));
+        content.push_str(&format!(
+            "hook-abort-on-failure={}\n",
+            self.hook_abort_on_failure
+        ));
+        content.push_str(&format!(
+            "wrappers={}\n",
+            wrappers::wrappers_to_config(&self.wrappers)
+        ));
 
         fs::write(&config_path, content)
             .with_context(|| format!("Failed to write config file: {:?}", config_path))?;
@@ -308,6 +446,9 @@ impl AppConfig {
         if let Some(interface_mode) = updates.interface_mode {
             self.interface_mode = interface_mode;
         }
+        if let Some(sort_mode) = updates.sort_mode {
+            self.sort_mode = sort_mode;
+        }
         if let Some(start_maximized) = updates.start_maximized {
             self.start_maximized = start_maximized;
         }
@@ -323,6 +464,9 @@ impl AppConfig {
         if let Some(enable_logging) = updates.enable_logging {
             self.enable_logging = enable_logging;
         }
+        if let Some(log_sessions) = updates.log_sessions {
+            self.log_sessions = log_sessions;
+        }
         if let Some(wayland_driver) = updates.wayland_driver {
             self.wayland_driver = wayland_driver;
         }
@@ -341,6 +485,27 @@ impl AppConfig {
         if let Some(show_hidden) = updates.show_hidden {
             self.show_hidden = show_hidden;
         }
+        if let Some(pre_launch_hook) = updates.pre_launch_hook {
+            self.pre_launch_hook = pre_launch_hook;
+        }
+        if let Some(post_exit_hook) = updates.post_exit_hook {
+            self.post_exit_hook = post_exit_hook;
+        }
+        if let Some(hook_timeout) = updates.hook_timeout {
+            self.hook_timeout = hook_timeout;
+        }
+        if let Some(stop_grace_period) = updates.stop_grace_period {
+            self.stop_grace_period = stop_grace_period;
+        }
+        if let Some(prevent_shared_prefix) = updates.prevent_shared_prefix {
+            self.prevent_shared_prefix = prevent_shared_prefix;
+        }
+        if let Some(hook_abort_on_failure) = updates.hook_abort_on_failure {
+            self.hook_abort_on_failure = hook_abort_on_failure;
+        }
+        if let Some(wrappers) = updates.wrappers {
+            self.wrappers = wrappers;
+        }
 
         self.save()
     }
@@ -363,15 +528,24 @@ pub struct ConfigUpdates {
     pub start_boot: Option<bool>,
     pub mono_icon: Option<bool>,
     pub interface_mode: Option<InterfaceMode>,
+    pub sort_mode: Option<SortMode>,
     pub start_maximized: Option<bool>,
     pub start_fullscreen: Option<bool>,
     pub show_labels: Option<bool>,
     pub smaller_banners: Option<bool>,
     pub enable_logging: Option<bool>,
+    pub log_sessions: Option<usize>,
     pub wayland_driver: Option<bool>,
     pub enable_hdr: Option<bool>,
     pub enable_wow64: Option<bool>,
     pub language: Option<String>,
     pub logging_warning: Option<bool>,
     pub show_hidden: Option<bool>,
+    pub pre_launch_hook: Option<String>,
+    pub post_exit_hook: Option<String>,
+    pub hook_timeout: Option<u64>,
+    pub stop_grace_period: Option<u64>,
+    pub prevent_shared_prefix: Option<bool>,
+    pub hook_abort_on_failure: Option<bool>,
+    pub wrappers: Option<Vec<Wrapper>>,
 }
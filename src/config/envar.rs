@@ -60,7 +60,7 @@ pub fn parse_envar_content(content: &str) -> (HashMap<String, String>, Vec<Strin
 
 /// Check if a string is a valid environment variable key
 /// Valid format: [A-Za-z_][A-Za-z0-9_]*
-fn is_valid_env_var_key(key: &str) -> bool {
+pub fn is_valid_env_var_key(key: &str) -> bool {
     if key.is_empty() {
         return false;
     }
@@ -3,10 +3,14 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::fs;
+use std::collections::BTreeMap;
+use std::fmt;
 use std::path::PathBuf;
 
+use crate::config::app_config::AppConfig;
+use crate::config::library_store::LibraryStore;
 use crate::config::paths::Paths;
+use crate::config::wrappers::Wrapper;
 
 /// Format game title for use in filenames/IDs
 /// Converts "Test's Game" -> "tests-game", "My Game" -> "my-game"
@@ -29,7 +33,33 @@ pub fn format_title(title: &str) -> String {
         .join("-")
 }
 
+/// Format a number of seconds as "2h 5m" or "5m"
+pub fn format_duration(seconds: u64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Clean up a user-entered tag: trim and collapse inner whitespace
+pub fn normalize_tag(tag: &str) -> String {
+    tag.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Whether two tags are the same, ignoring case (including non-ASCII letters)
+pub fn same_tag(a: &str, b: &str) -> bool {
+    normalize_tag(a).to_lowercase() == normalize_tag(b).to_lowercase()
+}
+
 /// Legacy compatibility module for deserializing Python format games.json
+///
+/// Stored libraries are normalized by the migrations in `config::schema`;
+/// these deserializers only keep individual entries tolerant of stray
+/// Python-typed values.
 mod legacy_compat {
     use serde::{Deserialize, Deserializer};
     use std::path::PathBuf;
@@ -106,6 +136,330 @@ mod legacy_compat {
     }
 }
 
+/// Per-game override of a global AppConfig toggle
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ToggleOverride {
+    /// Use the global AppConfig value
+    #[default]
+    Inherit,
+    /// Force enabled for this game
+    On,
+    /// Force disabled for this game
+    Off,
+}
+
+impl ToggleOverride {
+    pub const ALL: [ToggleOverride; 3] = [
+        ToggleOverride::Inherit,
+        ToggleOverride::On,
+        ToggleOverride::Off,
+    ];
+
+    /// Resolve against the global value
+    pub fn resolve(self, global: bool) -> bool {
+        match self {
+            ToggleOverride::Inherit => global,
+            ToggleOverride::On => true,
+            ToggleOverride::Off => false,
+        }
+    }
+
+    /// Display label (translation key)
+    pub fn label(self) -> &'static str {
+        match self {
+            ToggleOverride::Inherit => "Inherit",
+            ToggleOverride::On => "On",
+            ToggleOverride::Off => "Off",
+        }
+    }
+}
+
+/// Global AppConfig toggles that a game can override
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverrideField {
+    WaylandDriver,
+    Hdr,
+    Wow64,
+    DiscreteGpu,
+    Logging,
+}
+
+impl OverrideField {
+    pub const ALL: [OverrideField; 5] = [
+        OverrideField::WaylandDriver,
+        OverrideField::Hdr,
+        OverrideField::Wow64,
+        OverrideField::DiscreteGpu,
+        OverrideField::Logging,
+    ];
+
+    /// Display label (translation key)
+    pub fn label(self) -> &'static str {
+        match self {
+            OverrideField::WaylandDriver => "Use Wayland driver",
+            OverrideField::Hdr => "Enable HDR",
+            OverrideField::Wow64 => "Enable WOW64",
+            OverrideField::DiscreteGpu => "Use discrete GPU",
+            OverrideField::Logging => "Enable logging",
+        }
+    }
+
+    /// Global value of this toggle
+    pub fn global(self, config: &AppConfig) -> bool {
+        match self {
+            OverrideField::WaylandDriver => config.wayland_driver,
+            OverrideField::Hdr => config.enable_hdr,
+            OverrideField::Wow64 => config.enable_wow64,
+            OverrideField::DiscreteGpu => config.discrete_gpu,
+            OverrideField::Logging => config.enable_logging,
+        }
+    }
+}
+
+/// Per-game overrides for global AppConfig toggles
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct GameOverrides {
+    pub wayland_driver: ToggleOverride,
+    pub enable_hdr: ToggleOverride,
+    pub enable_wow64: ToggleOverride,
+    pub discrete_gpu: ToggleOverride,
+    pub enable_logging: ToggleOverride,
+}
+
+impl GameOverrides {
+    /// Get the override for a field
+    pub fn get(&self, field: OverrideField) -> ToggleOverride {
+        match field {
+            OverrideField::WaylandDriver => self.wayland_driver,
+            OverrideField::Hdr => self.enable_hdr,
+            OverrideField::Wow64 => self.enable_wow64,
+            OverrideField::DiscreteGpu => self.discrete_gpu,
+            OverrideField::Logging => self.enable_logging,
+        }
+    }
+
+    /// Set the override for a field
+    pub fn set(&mut self, field: OverrideField, value: ToggleOverride) {
+        match field {
+            OverrideField::WaylandDriver => self.wayland_driver = value,
+            OverrideField::Hdr => self.enable_hdr = value,
+            OverrideField::Wow64 => self.enable_wow64 = value,
+            OverrideField::DiscreteGpu => self.discrete_gpu = value,
+            OverrideField::Logging => self.enable_logging = value,
+        }
+    }
+
+    /// Effective value of a toggle for this game
+    pub fn resolve(&self, field: OverrideField, config: &AppConfig) -> bool {
+        self.get(field).resolve(field.global(config))
+    }
+}
+
+/// Gamescope upscaling filter
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum GamescopeUpscaler {
+    #[default]
+    None,
+    Fsr,
+    Nis,
+}
+
+impl GamescopeUpscaler {
+    pub const ALL: [GamescopeUpscaler; 3] = [
+        GamescopeUpscaler::None,
+        GamescopeUpscaler::Fsr,
+        GamescopeUpscaler::Nis,
+    ];
+}
+
+impl fmt::Display for GamescopeUpscaler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                GamescopeUpscaler::None => "None",
+                GamescopeUpscaler::Fsr => "FSR",
+                GamescopeUpscaler::Nis => "NIS",
+            }
+        )
+    }
+}
+
+/// Gamescope window mode
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum GamescopeWindowMode {
+    #[default]
+    Windowed,
+    Fullscreen,
+    Borderless,
+}
+
+impl GamescopeWindowMode {
+    pub const ALL: [GamescopeWindowMode; 3] = [
+        GamescopeWindowMode::Windowed,
+        GamescopeWindowMode::Fullscreen,
+        GamescopeWindowMode::Borderless,
+    ];
+}
+
+impl fmt::Display for GamescopeWindowMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                GamescopeWindowMode::Windowed => "Windowed",
+                GamescopeWindowMode::Fullscreen => "Fullscreen",
+                GamescopeWindowMode::Borderless => "Borderless",
+            }
+        )
+    }
+}
+
+/// Gamescope compositor settings; resolutions and refresh rate of 0 are left to gamescope
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct GamescopeSettings {
+    pub enabled: bool,
+    /// Output (window) resolution
+    pub output_width: u32,
+    pub output_height: u32,
+    /// Resolution the game renders at
+    pub game_width: u32,
+    pub game_height: u32,
+    pub refresh_rate: u32,
+    pub upscaler: GamescopeUpscaler,
+    pub window_mode: GamescopeWindowMode,
+    pub hdr: bool,
+}
+
+impl GamescopeSettings {
+    /// Gamescope options, without the `--` separating them from the game command
+    ///
+    /// With MangoHud enabled the overlay is drawn by gamescope (`--mangoapp`)
+    /// instead of being injected into the game.
+    pub fn args(&self, mangohud: bool) -> Vec<String> {
+        let mut args = Vec::new();
+        let mut push = |flag: &str, value: u32| {
+            if value > 0 {
+                args.push(flag.to_string());
+                args.push(value.to_string());
+            }
+        };
+
+        push("-W", self.output_width);
+        push("-H", self.output_height);
+        push("-w", self.game_width);
+        push("-h", self.game_height);
+        push("-r", self.refresh_rate);
+
+        match self.upscaler {
+            GamescopeUpscaler::None => {}
+            GamescopeUpscaler::Fsr => args.extend(["-F".to_string(), "fsr".to_string()]),
+            GamescopeUpscaler::Nis => args.extend(["-F".to_string(), "nis".to_string()]),
+        }
+        match self.window_mode {
+            GamescopeWindowMode::Windowed => {}
+            GamescopeWindowMode::Fullscreen => args.push("-f".to_string()),
+            GamescopeWindowMode::Borderless => args.push("-b".to_string()),
+        }
+        if self.hdr {
+            args.push("--hdr-enabled".to_string());
+        }
+        if mangohud {
+            args.push("--mangoapp".to_string());
+        }
+
+        args
+    }
+}
+
+/// Named variant of a game's launch settings ("DX11 safe mode", "Benchmark", ...)
+///
+/// A profile replaces the game's runner, arguments, MangoHud/GameMode,
+/// gamescope and lossless settings for a launch; everything else (prefix, playtime,
+/// shortcuts) stays shared with the game.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LaunchProfile {
+    /// Display name, unique within a game
+    pub name: String,
+    pub runner: String,
+    pub launch_arguments: String,
+    pub game_arguments: String,
+    pub mangohud: bool,
+    pub gamemode: bool,
+    pub lossless_enabled: bool,
+    pub lossless_multiplier: u32,
+    pub lossless_flow: bool,
+    pub lossless_performance: bool,
+    pub lossless_hdr: bool,
+    #[serde(default)]
+    pub gamescope: GamescopeSettings,
+}
+
+impl LaunchProfile {
+    /// Snapshot the launch settings of a game under a new name
+    pub fn from_game(name: impl Into<String>, game: &Game) -> Self {
+        Self {
+            name: name.into(),
+            runner: game.runner.clone(),
+            launch_arguments: game.launch_arguments.clone(),
+            game_arguments: game.game_arguments.clone(),
+            mangohud: game.mangohud,
+            gamemode: game.gamemode,
+            lossless_enabled: game.lossless_enabled,
+            lossless_multiplier: game.lossless_multiplier,
+            lossless_flow: game.lossless_flow,
+            lossless_performance: game.lossless_performance,
+            lossless_hdr: game.lossless_hdr,
+            gamescope: game.gamescope,
+        }
+    }
+
+    /// Overwrite the launch settings of a game with this profile
+    pub fn apply_to(&self, game: &mut Game) {
+        game.runner = self.runner.clone();
+        game.launch_arguments = self.launch_arguments.clone();
+        game.game_arguments = self.game_arguments.clone();
+        game.mangohud = self.mangohud;
+        game.gamemode = self.gamemode;
+        game.lossless_enabled = self.lossless_enabled;
+        game.lossless_multiplier = self.lossless_multiplier;
+        game.lossless_flow = self.lossless_flow;
+        game.lossless_performance = self.lossless_performance;
+        game.lossless_hdr = self.lossless_hdr;
+        game.gamescope = self.gamescope;
+    }
+}
+
+/// Commands run before a game starts and after it exits
+///
+/// Hooks run through `sh -c` with the game's launch environment exported.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct GameHooks {
+    /// Run before launching (mount a share, start a remapper, back up saves)
+    pub pre_launch: String,
+    /// Run once the game has exited
+    pub post_exit: String,
+    /// Seconds a hook may run before it is killed (0 = global setting)
+    pub timeout: u64,
+    /// Don't launch the game if the pre-launch hook fails
+    pub abort_on_failure: bool,
+}
+
+impl GameHooks {
+    /// True if no hook command is set
+    pub fn is_empty(&self) -> bool {
+        self.pre_launch.trim().is_empty() && self.post_exit.trim().is_empty()
+    }
+}
+
 /// Represents a single game in the launcher
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Game {
@@ -209,11 +563,47 @@ pub struct Game {
     )]
     pub lossless_hdr: bool,
 
+    /// Run the game inside gamescope
+    #[serde(default)]
+    pub gamescope: GamescopeSettings,
+
+    /// Programs the game is run through, outermost first
+    #[serde(default)]
+    pub wrappers: Vec<Wrapper>,
+
     /// Total playtime in seconds
     pub playtime: u64,
 
     /// Hidden from library
     pub hidden: bool,
+
+    /// Marked as favorite; favorites are listed first
+    #[serde(default)]
+    pub favorite: bool,
+
+    /// User-defined tags, sorted and without duplicates
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Per-game environment variables, applied over envar.txt and AppConfig
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+
+    /// Per-game overrides for global AppConfig toggles
+    #[serde(default)]
+    pub overrides: GameOverrides,
+
+    /// Named launch profiles; the game's own settings are used when none is chosen
+    #[serde(default)]
+    pub profiles: Vec<LaunchProfile>,
+
+    /// Profile used by Play, double-click and shortcuts (None = game settings)
+    #[serde(default)]
+    pub default_profile: Option<String>,
+
+    /// Pre-launch and post-exit commands
+    #[serde(default)]
+    pub hooks: GameHooks,
 }
 
 impl Default for Game {
@@ -239,8 +629,17 @@ impl Default for Game {
             lossless_flow: false,
             lossless_performance: false,
             lossless_hdr: false,
+            gamescope: GamescopeSettings::default(),
+            wrappers: Vec::new(),
             playtime: 0,
             hidden: false,
+            favorite: false,
+            tags: Vec::new(),
+            env: BTreeMap::new(),
+            overrides: GameOverrides::default(),
+            profiles: Vec::new(),
+            default_profile: None,
+            hooks: GameHooks::default(),
         }
     }
 }
@@ -248,73 +647,41 @@ impl Default for Game {
 impl Game {
     /// Load all games from the games.json file
     pub fn load_all() -> Result<Vec<Self>> {
-        let games_path = Paths::games_json();
-
-        if !games_path.exists() {
-            return Ok(Vec::new());
-        }
-
-        let content = fs::read_to_string(&games_path)
-            .with_context(|| format!("Failed to read games file: {:?}", games_path))?;
-
-        let games: Vec<Self> =
-            serde_json::from_str(&content).with_context(|| "Failed to parse games JSON")?;
-
-        Ok(games)
+        LibraryStore::open().load()
     }
 
     /// Save all games to the games.json file
     pub fn save_all(games: &[Self]) -> Result<()> {
-        let games_path = Paths::games_json();
-
-        // Ensure parent directory exists
-        if let Some(parent) = games_path.parent() {
-            fs::create_dir_all(parent)
-                .with_context(|| format!("Failed to create games directory: {:?}", parent))?;
-        }
-
-        let content =
-            serde_json::to_string_pretty(games).with_context(|| "Failed to serialize games")?;
-
-        fs::write(&games_path, content)
-            .with_context(|| format!("Failed to write games file: {:?}", games_path))?;
-
-        Ok(())
+        LibraryStore::open().save(games)
     }
 
     /// Save this game to the games list
+    ///
+    /// Playtime of an existing game is owned by session tracking, so the
+    /// stored value is kept even if this copy is stale.
     pub fn save(&self) -> Result<()> {
-        let mut games = Self::load_all().unwrap_or_default();
-
-        // Find and update existing game or add new one
-        if let Some(existing) = games.iter().position(|g| g.gameid == self.gameid) {
-            games[existing] = self.clone();
-        } else {
-            games.push(self.clone());
-        }
-
-        Self::save_all(&games)
+        LibraryStore::open().update(|games| {
+            // Find and update existing game or add new one
+            if let Some(existing) = games.iter().position(|g| g.gameid == self.gameid) {
+                let playtime = games[existing].playtime;
+                games[existing] = Self {
+                    playtime,
+                    ..self.clone()
+                };
+            } else {
+                games.push(self.clone());
+            }
+        })
     }
 
     /// Delete this game from the games list
     pub fn delete(&self) -> Result<()> {
-        let mut games = Self::load_all().unwrap_or_default();
-
-        games.retain(|g| g.gameid != self.gameid);
-
-        Self::save_all(&games)
+        LibraryStore::open().update(|games| games.retain(|g| g.gameid != self.gameid))
     }
 
     /// Format playtime as human-readable string
     pub fn format_playtime(&self) -> String {
-        let hours = self.playtime / 3600;
-        let minutes = (self.playtime % 3600) / 60;
-
-        if hours > 0 {
-            format!("{}h {}m", hours, minutes)
-        } else {
-            format!("{}m", minutes)
-        }
+        format_duration(self.playtime)
     }
 
     /// Increment playtime by given seconds
@@ -324,165 +691,95 @@ impl Game {
 
     /// Update the hidden state of this game
     pub fn update_hidden(&self, hidden: bool) -> Result<()> {
-        let mut games = Self::load_all().unwrap_or_default();
+        LibraryStore::open().update(|games| {
+            if let Some(existing) = games.iter_mut().find(|g| g.gameid == self.gameid) {
+                existing.hidden = hidden;
+            }
+        })
+    }
 
-        // Find and update existing game
-        if let Some(existing) = games.iter().position(|g| g.gameid == self.gameid) {
-            games[existing].hidden = hidden;
-            Self::save_all(&games)?;
-        }
+    /// Update the favorite state of this game
+    pub fn update_favorite(&self, favorite: bool) -> Result<()> {
+        LibraryStore::open().update(|games| {
+            if let Some(existing) = games.iter_mut().find(|g| g.gameid == self.gameid) {
+                existing.favorite = favorite;
+            }
+        })
+    }
 
-        Ok(())
+    /// Check if the game has a tag (case-insensitive)
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| same_tag(t, tag))
     }
 
-    /// Duplicate this game with a new ID and (Copy) suffix
-    pub fn duplicate(&self) -> Self {
-        Self {
-            gameid: uuid::Uuid::new_v4().to_string(),
-            title: format!("{} (Copy)", self.title),
-            path: self.path.clone(),
-            prefix: self.prefix.clone(),
-            launch_arguments: self.launch_arguments.clone(),
-            game_arguments: self.game_arguments.clone(),
-            mangohud: self.mangohud,
-            gamemode: self.gamemode,
-            disable_hidraw: self.disable_hidraw,
-            protonfix: self.protonfix.clone(),
-            runner: self.runner.clone(),
-            addapp_checkbox: self.addapp_checkbox,
-            addapp: self.addapp.clone(),
-            addapp_bat: self.addapp_bat.clone(),
-            banner: self.banner.clone(),
-            lossless_enabled: self.lossless_enabled,
-            lossless_multiplier: self.lossless_multiplier,
-            lossless_flow: self.lossless_flow,
-            lossless_performance: self.lossless_performance,
-            lossless_hdr: self.lossless_hdr,
-            playtime: 0,
-            hidden: false,
+    /// Add a tag; blank or already present tags are ignored
+    pub fn add_tag(&mut self, tag: &str) {
+        let tag = normalize_tag(tag);
+        if !tag.is_empty() && !self.has_tag(&tag) {
+            self.tags.push(tag);
+            self.tags.sort_by_key(|t| t.to_lowercase());
         }
     }
-}
 
-/// Game configuration for creation/editing
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GameConfig {
-    pub gameid: Option<String>,
-    pub title: String,
-    pub path: PathBuf,
-    pub prefix: PathBuf,
-    pub launch_arguments: String,
-    pub game_arguments: String,
-    #[serde(
-        default,
-        deserialize_with = "legacy_compat::deserialize_bool_from_string_or_bool"
-    )]
-    pub mangohud: bool,
-    #[serde(
-        default,
-        deserialize_with = "legacy_compat::deserialize_bool_from_string_or_bool"
-    )]
-    pub gamemode: bool,
-    #[serde(
-        default,
-        deserialize_with = "legacy_compat::deserialize_bool_from_string_or_bool"
-    )]
-    pub disable_hidraw: bool,
-    pub protonfix: String,
-    pub runner: String,
-    #[serde(
-        default,
-        deserialize_with = "legacy_compat::deserialize_bool_from_string_or_bool"
-    )]
-    pub addapp_checkbox: bool,
-    pub addapp: String,
-    pub addapp_bat: String,
-    #[serde(
-        default,
-        deserialize_with = "legacy_compat::deserialize_optional_path_from_string"
-    )]
-    pub banner: Option<PathBuf>,
-    #[serde(
-        default,
-        deserialize_with = "legacy_compat::deserialize_bool_from_string_or_bool"
-    )]
-    pub lossless_enabled: bool,
-    #[serde(
-        default,
-        deserialize_with = "legacy_compat::deserialize_u32_from_string_or_number"
-    )]
-    pub lossless_multiplier: u32,
-    #[serde(
-        default,
-        deserialize_with = "legacy_compat::deserialize_bool_from_string_or_bool"
-    )]
-    pub lossless_flow: bool,
-    #[serde(
-        default,
-        deserialize_with = "legacy_compat::deserialize_bool_from_string_or_bool"
-    )]
-    pub lossless_performance: bool,
-    #[serde(
-        default,
-        deserialize_with = "legacy_compat::deserialize_bool_from_string_or_bool"
-    )]
-    pub lossless_hdr: bool,
-}
+    /// Remove a tag (case-insensitive)
+    pub fn remove_tag(&mut self, tag: &str) {
+        self.tags.retain(|t| !same_tag(t, tag));
+    }
 
-impl From<GameConfig> for Game {
-    fn from(config: GameConfig) -> Self {
-        Self {
-            gameid: config
-                .gameid
-                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
-            title: config.title,
-            path: config.path,
-            prefix: config.prefix,
-            launch_arguments: config.launch_arguments,
-            game_arguments: config.game_arguments,
-            mangohud: config.mangohud,
-            gamemode: config.gamemode,
-            disable_hidraw: config.disable_hidraw,
-            protonfix: config.protonfix,
-            runner: config.runner,
-            addapp_checkbox: config.addapp_checkbox,
-            addapp: config.addapp,
-            addapp_bat: config.addapp_bat,
-            banner: config.banner,
-            lossless_enabled: config.lossless_enabled,
-            lossless_multiplier: config.lossless_multiplier,
-            lossless_flow: config.lossless_flow,
-            lossless_performance: config.lossless_performance,
-            lossless_hdr: config.lossless_hdr,
-            playtime: 0,
-            hidden: false,
+    /// Add and remove tags on several games at once
+    pub fn retag(gameids: &[String], add: &[String], remove: &[String]) -> Result<()> {
+        LibraryStore::open().update(|games| {
+            for game in games.iter_mut().filter(|g| gameids.contains(&g.gameid)) {
+                for tag in remove {
+                    game.remove_tag(tag);
+                }
+                for tag in add {
+                    game.add_tag(tag);
+                }
+            }
+        })
+    }
+
+    /// Find a profile by name
+    pub fn profile(&self, name: &str) -> Option<&LaunchProfile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+
+    /// Names of all profiles, in display order
+    pub fn profile_names(&self) -> Vec<String> {
+        self.profiles.iter().map(|p| p.name.clone()).collect()
+    }
+
+    /// Game with the given profile (or the default profile) applied
+    ///
+    /// With `None` the default profile is used if one is set and still
+    /// exists; an explicitly requested profile must exist.
+    pub fn resolve_profile(&self, name: Option<&str>) -> Result<Game> {
+        let profile = match name {
+            Some(name) => Some(self.profile(name).with_context(|| {
+                format!("Profile '{}' not found for game '{}'", name, self.title)
+            })?),
+            None => self
+                .default_profile
+                .as_deref()
+                .and_then(|name| self.profile(name)),
+        };
+
+        let mut game = self.clone();
+        if let Some(profile) = profile {
+            profile.apply_to(&mut game);
         }
+        Ok(game)
     }
-}
 
-impl From<Game> for GameConfig {
-    fn from(game: Game) -> Self {
+    /// Duplicate this game with a new ID and (Copy) suffix
+    pub fn duplicate(&self) -> Self {
         Self {
-            gameid: Some(game.gameid),
-            title: game.title,
-            path: game.path,
-            prefix: game.prefix,
-            launch_arguments: game.launch_arguments,
-            game_arguments: game.game_arguments,
-            mangohud: game.mangohud,
-            gamemode: game.gamemode,
-            disable_hidraw: game.disable_hidraw,
-            protonfix: game.protonfix,
-            runner: game.runner,
-            addapp_checkbox: game.addapp_checkbox,
-            addapp: game.addapp,
-            addapp_bat: game.addapp_bat,
-            banner: game.banner,
-            lossless_enabled: game.lossless_enabled,
-            lossless_multiplier: game.lossless_multiplier,
-            lossless_flow: game.lossless_flow,
-            lossless_performance: game.lossless_performance,
-            lossless_hdr: game.lossless_hdr,
+            gameid: uuid::Uuid::new_v4().to_string(),
+            title: format!("{} (Copy)", self.title),
+            playtime: 0,
+            hidden: false,
+            ..self.clone()
         }
     }
 }
@@ -652,8 +949,17 @@ mod tests {
             lossless_flow: false,
             lossless_performance: false,
             lossless_hdr: false,
+            gamescope: GamescopeSettings::default(),
+            wrappers: Vec::new(),
             playtime: 0,
             hidden: false,
+            favorite: false,
+            tags: Vec::new(),
+            env: BTreeMap::new(),
+            overrides: GameOverrides::default(),
+            profiles: Vec::new(),
+            default_profile: None,
+            hooks: GameHooks::default(),
         };
 
         let json = serde_json::to_string(&game).expect("Failed to serialize game to JSON");
@@ -676,4 +982,142 @@ mod tests {
         );
         assert_eq!(parsed["lossless_multiplier"], 2);
     }
+
+    #[test]
+    fn test_toggle_override_resolution() {
+        let config = AppConfig {
+            enable_hdr: true,
+            ..Default::default()
+        };
+        let overrides = GameOverrides {
+            enable_hdr: ToggleOverride::Off,
+            wayland_driver: ToggleOverride::On,
+            ..Default::default()
+        };
+
+        assert!(!overrides.resolve(OverrideField::Hdr, &config));
+        assert!(overrides.resolve(OverrideField::WaylandDriver, &config));
+        assert!(!overrides.resolve(OverrideField::Wow64, &config));
+
+        let json = serde_json::to_value(overrides).expect("serialize");
+        assert_eq!(json["enable_hdr"], "off");
+        assert_eq!(json["enable_wow64"], "inherit");
+    }
+
+    #[test]
+    fn test_tags() {
+        let mut game = Game::default();
+        game.add_tag("  Co-op   games ");
+        game.add_tag("action");
+        game.add_tag("ACTION");
+        game.add_tag("   ");
+        assert_eq!(game.tags, vec!["action", "Co-op games"]);
+        assert!(game.has_tag("co-op GAMES"));
+
+        game.remove_tag("Action");
+        assert_eq!(game.tags, vec!["Co-op games"]);
+
+        // Non-ASCII tags compare the same way as the lowercased tag filter
+        game.add_tag("Ärger");
+        game.add_tag("ärger");
+        assert_eq!(game.tags, vec!["Co-op games", "Ärger"]);
+        assert!(game.has_tag(&"ÄRGER".to_lowercase()));
+        game.add_tag("RPG-Ü");
+        assert!(game.has_tag("rpg-ü"));
+        game.remove_tag("rpg-ü");
+        game.remove_tag("ÄRGER");
+        assert_eq!(game.tags, vec!["Co-op games"]);
+
+        // Libraries written before tags existed load with none
+        let json = serde_json::to_value(Game::default()).expect("serialize");
+        let mut object = json.as_object().cloned().expect("object");
+        object.remove("tags");
+        object.remove("favorite");
+        let game: Game = serde_json::from_value(object.into()).expect("deserialize");
+        assert!(game.tags.is_empty());
+        assert!(!game.favorite);
+    }
+
+    #[test]
+    fn test_resolve_profile() {
+        let mut game = Game {
+            title: "Profiled".to_string(),
+            launch_arguments: "PROTON_LOG=1".to_string(),
+            ..Default::default()
+        };
+        let mut benchmark = LaunchProfile::from_game("Benchmark", &game);
+        benchmark.mangohud = true;
+        benchmark.game_arguments = "-dx11".to_string();
+        game.profiles.push(benchmark);
+
+        // No default profile: the game's own settings are used
+        let base = game.resolve_profile(None).expect("base settings");
+        assert!(!base.mangohud);
+
+        let resolved = game.resolve_profile(Some("Benchmark")).expect("profile");
+        assert!(resolved.mangohud);
+        assert_eq!(resolved.game_arguments, "-dx11");
+        assert_eq!(resolved.launch_arguments, "PROTON_LOG=1");
+        assert_eq!(resolved.gameid, game.gameid);
+
+        game.default_profile = Some("Benchmark".to_string());
+        assert!(game.resolve_profile(None).expect("default").mangohud);
+
+        let err = game
+            .resolve_profile(Some("Missing"))
+            .expect_err("unknown profile must fail");
+        assert!(err.to_string().contains("Missing"));
+
+        // A stale default falls back to the game's settings
+        game.default_profile = Some("Removed".to_string());
+        assert!(!game.resolve_profile(None).expect("fallback").mangohud);
+    }
+    #[test]
+    fn test_gamescope_args() {
+        let mut gamescope = GamescopeSettings {
+            enabled: true,
+            output_width: 2560,
+            output_height: 1440,
+            game_width: 1920,
+            game_height: 1080,
+            refresh_rate: 144,
+            upscaler: GamescopeUpscaler::Fsr,
+            window_mode: GamescopeWindowMode::Fullscreen,
+            hdr: true,
+        };
+        assert_eq!(
+            gamescope.args(true),
+            vec![
+                "-W",
+                "2560",
+                "-H",
+                "1440",
+                "-w",
+                "1920",
+                "-h",
+                "1080",
+                "-r",
+                "144",
+                "-F",
+                "fsr",
+                "-f",
+                "--hdr-enabled",
+                "--mangoapp"
+            ]
+        );
+
+        // Unset values are left to gamescope
+        gamescope = GamescopeSettings {
+            window_mode: GamescopeWindowMode::Borderless,
+            ..Default::default()
+        };
+        assert_eq!(gamescope.args(false), vec!["-b"]);
+
+        // Games stored before gamescope support load with it disabled
+        let json = serde_json::to_value(Game::default()).expect("serialize");
+        let mut object = json.as_object().cloned().expect("object");
+        object.remove("gamescope");
+        let game: Game = serde_json::from_value(object.into()).expect("deserialize");
+        assert!(!game.gamescope.enabled);
+    }
 }
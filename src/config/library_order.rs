@@ -0,0 +1,195 @@
+// Library ordering
+// Sorts the game library and picks recently played games for display
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::config::app_config::SortMode;
+use crate::config::game_config::Game;
+use crate::config::session_history::PlayStats;
+
+/// Play activity used to order the library
+#[derive(Debug, Clone, Copy)]
+pub struct Activity<'a> {
+    /// Play statistics by game ID
+    pub stats: &'a HashMap<String, PlayStats>,
+    /// Game IDs from latest-games.txt, most recent first
+    pub latest_games: &'a [String],
+}
+
+impl Activity<'_> {
+    /// Recency rank of a game; lower is more recent, None if never played
+    ///
+    /// Games with recorded sessions come first, by the end of their last
+    /// session. Games only listed in latest-games.txt (launched before
+    /// session history existed) follow in that file's order.
+    fn recency(&self, game: &Game) -> Option<(u8, u64)> {
+        if let Some(last_played) = self
+            .stats
+            .get(&game.gameid)
+            .and_then(|stats| stats.last_played)
+        {
+            return Some((0, u64::MAX - last_played));
+        }
+
+        self.latest_games
+            .iter()
+            .position(|gameid| gameid == &game.gameid)
+            .map(|position| (1, position as u64))
+    }
+
+    /// Total playtime of a game in seconds
+    fn playtime(&self, game: &Game) -> u64 {
+        self.stats
+            .get(&game.gameid)
+            .map(|stats| stats.total)
+            .unwrap_or(game.playtime)
+    }
+}
+
+/// Case-insensitive title comparison
+fn by_title(a: &Game, b: &Game) -> Ordering {
+    a.title.to_lowercase().cmp(&b.title.to_lowercase())
+}
+
+/// Indices of `games` in display order for the given sort mode
+///
+/// games.json keeps games in the order they were added, so "date added"
+/// lists the newest entry first by position. Favorites always come first.
+pub fn sorted_indices(games: &[Game], mode: SortMode, activity: Activity<'_>) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..games.len()).collect();
+
+    match mode {
+        SortMode::Title => indices.sort_by(|&a, &b| by_title(&games[a], &games[b])),
+        SortMode::LastPlayed => indices.sort_by(|&a, &b| {
+            // Never played games go last
+            let rank = |index: usize| activity.recency(&games[index]).unwrap_or((2, 0));
+            rank(a)
+                .cmp(&rank(b))
+                .then_with(|| by_title(&games[a], &games[b]))
+        }),
+        SortMode::Playtime => indices.sort_by(|&a, &b| {
+            activity
+                .playtime(&games[b])
+                .cmp(&activity.playtime(&games[a]))
+                .then_with(|| by_title(&games[a], &games[b]))
+        }),
+        SortMode::DateAdded => indices.reverse(),
+        SortMode::Runner => indices.sort_by(|&a, &b| {
+            games[a]
+                .runner
+                .to_lowercase()
+                .cmp(&games[b].runner.to_lowercase())
+                .then_with(|| by_title(&games[a], &games[b]))
+        }),
+    }
+
+    // Favorites first, keeping the order within each group
+    indices.sort_by_key(|&index| !games[index].favorite);
+
+    indices
+}
+
+/// Indices of the most recently played games among `candidates`, most recent first
+pub fn recently_played(
+    games: &[Game],
+    candidates: impl IntoIterator<Item = usize>,
+    activity: Activity<'_>,
+    limit: usize,
+) -> Vec<usize> {
+    let mut recent: Vec<((u8, u64), usize)> = candidates
+        .into_iter()
+        .filter_map(|index| Some((activity.recency(games.get(index)?)?, index)))
+        .collect();
+    recent.sort();
+    recent.truncate(limit);
+    recent.into_iter().map(|(_, index)| index).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(id: &str, title: &str, runner: &str) -> Game {
+        Game {
+            gameid: id.to_string(),
+            title: title.to_string(),
+            runner: runner.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn played(total: u64, last_played: Option<u64>) -> PlayStats {
+        PlayStats {
+            total,
+            last_played,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sort_modes() {
+        let games = vec![
+            game("a", "beta", "Proton-9"),
+            game("b", "Alpha", "GE-Proton"),
+            game("c", "gamma", "GE-Proton"),
+        ];
+        let stats = HashMap::from([
+            ("a".to_string(), played(100, Some(50))),
+            ("c".to_string(), played(500, Some(10))),
+        ]);
+        let activity = Activity {
+            stats: &stats,
+            latest_games: &[],
+        };
+
+        assert_eq!(
+            sorted_indices(&games, SortMode::Title, activity),
+            vec![1, 0, 2]
+        );
+        assert_eq!(
+            sorted_indices(&games, SortMode::LastPlayed, activity),
+            vec![0, 2, 1]
+        );
+        assert_eq!(
+            sorted_indices(&games, SortMode::Playtime, activity),
+            vec![2, 0, 1]
+        );
+        assert_eq!(
+            sorted_indices(&games, SortMode::DateAdded, activity),
+            vec![2, 1, 0]
+        );
+        assert_eq!(
+            sorted_indices(&games, SortMode::Runner, activity),
+            vec![1, 2, 0]
+        );
+
+        let mut games = games;
+        games[2].favorite = true;
+        assert_eq!(
+            sorted_indices(&games, SortMode::Title, activity),
+            vec![2, 1, 0]
+        );
+    }
+
+    #[test]
+    fn test_recently_played_uses_latest_games_fallback() {
+        let games = vec![
+            game("a", "Tracked", "GE-Proton"),
+            game("b", "Legacy", "GE-Proton"),
+            game("c", "Never", "GE-Proton"),
+            game("d", "Older legacy", "GE-Proton"),
+        ];
+        let stats = HashMap::from([("a".to_string(), played(60, Some(1000)))]);
+        let latest = ["b".to_string(), "d".to_string()];
+        let activity = Activity {
+            stats: &stats,
+            latest_games: &latest,
+        };
+
+        assert_eq!(recently_played(&games, 0..4, activity, 5), vec![0, 1, 3]);
+        assert_eq!(recently_played(&games, 0..4, activity, 2), vec![0, 1]);
+        // Candidates restrict the result (e.g. hidden games filtered out)
+        assert_eq!(recently_played(&games, [2, 3], activity, 5), vec![3]);
+    }
+}
@@ -0,0 +1,305 @@
+// Library search
+// Parses search box queries into field filters and fuzzy-matches game titles
+
+use crate::config::game_config::Game;
+
+/// Game field a search filter applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchField {
+    Title,
+    Runner,
+    Tag,
+    Prefix,
+    Path,
+    Hidden,
+    Favorite,
+    MangoHud,
+    GameMode,
+}
+
+impl SearchField {
+    /// Field for a `key:` in the query
+    fn from_key(key: &str) -> Option<Self> {
+        match key.to_lowercase().as_str() {
+            "title" => Some(SearchField::Title),
+            "runner" => Some(SearchField::Runner),
+            "tag" => Some(SearchField::Tag),
+            "prefix" => Some(SearchField::Prefix),
+            "path" => Some(SearchField::Path),
+            "hidden" => Some(SearchField::Hidden),
+            "favorite" | "fav" => Some(SearchField::Favorite),
+            "mangohud" => Some(SearchField::MangoHud),
+            "gamemode" => Some(SearchField::GameMode),
+            _ => None,
+        }
+    }
+}
+
+/// A `field:value` filter, optionally negated with a leading `-`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldFilter {
+    pub field: SearchField,
+    pub value: String,
+    pub negate: bool,
+}
+
+impl FieldFilter {
+    /// Check a game against this filter
+    fn matches(&self, game: &Game) -> bool {
+        let value = self.value.to_lowercase();
+        let contains = |haystack: &str| haystack.to_lowercase().contains(&value);
+
+        let matched = match self.field {
+            SearchField::Title => contains(&game.title),
+            SearchField::Runner => contains(&game.runner),
+            SearchField::Tag => game.has_tag(&self.value),
+            SearchField::Prefix => game
+                .prefix
+                .to_string_lossy()
+                .to_lowercase()
+                .starts_with(&value),
+            SearchField::Path => contains(&game.path.to_string_lossy()),
+            SearchField::Hidden => parse_flag(&value) == Some(game.hidden),
+            SearchField::Favorite => parse_flag(&value) == Some(game.favorite),
+            SearchField::MangoHud => parse_flag(&value) == Some(game.mangohud),
+            SearchField::GameMode => parse_flag(&value) == Some(game.gamemode),
+        };
+
+        matched != self.negate
+    }
+}
+
+/// Parse yes/no style filter values
+fn parse_flag(value: &str) -> Option<bool> {
+    match value {
+        "yes" | "y" | "on" | "true" | "1" => Some(true),
+        "no" | "n" | "off" | "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Parsed search box query
+///
+/// `runner:GE-Proton9 tag:"co op" -hidden:yes witcher` keeps games whose
+/// runner contains "GE-Proton9", tagged "co op", not hidden, with a title
+/// fuzzy-matching "witcher". Unknown `key:value` words are searched as text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    /// Free text matched fuzzily against the title
+    pub text: String,
+    pub filters: Vec<FieldFilter>,
+}
+
+impl SearchQuery {
+    /// Parse a query typed in the search box
+    pub fn parse(query: &str) -> Self {
+        let mut text = Vec::new();
+        let mut filters = Vec::new();
+
+        for word in split_words(query) {
+            let (negate, body) = match word.strip_prefix('-') {
+                Some(rest) if rest.contains(':') => (true, rest),
+                _ => (false, word.as_str()),
+            };
+
+            let filter = body.split_once(':').and_then(|(key, value)| {
+                let field = SearchField::from_key(key)?;
+                (!value.is_empty()).then(|| FieldFilter {
+                    field,
+                    value: value.to_string(),
+                    negate,
+                })
+            });
+
+            match filter {
+                Some(filter) => filters.push(filter),
+                None => text.push(word),
+            }
+        }
+
+        Self {
+            text: text.join(" "),
+            filters,
+        }
+    }
+
+    /// True if the query decides about hidden games itself
+    pub fn filters_hidden(&self) -> bool {
+        self.filters.iter().any(|f| f.field == SearchField::Hidden)
+    }
+
+    /// Match score of a game, higher is better; None if it doesn't match
+    pub fn score(&self, game: &Game) -> Option<u32> {
+        if !self.filters.iter().all(|filter| filter.matches(game)) {
+            return None;
+        }
+        if self.text.is_empty() {
+            return Some(0);
+        }
+        fuzzy_score(&self.text, &game.title)
+    }
+}
+
+/// Split a query on whitespace, keeping double-quoted parts together
+fn split_words(query: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in query.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    words.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+/// Fuzzy match `pattern` against `candidate`, case-insensitive
+///
+/// Every pattern character (spaces ignored) must appear in order. Matches
+/// at the start, at word boundaries and in consecutive runs score higher;
+/// a plain substring match gets a large bonus so exact hits rank first.
+pub fn fuzzy_score(pattern: &str, candidate: &str) -> Option<u32> {
+    let pattern: Vec<char> = pattern
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    if pattern.is_empty() {
+        return Some(0);
+    }
+
+    let candidate_lower: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score: u32 = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+
+    for &wanted in &pattern {
+        let position = (next..candidate_lower.len()).find(|&i| candidate_lower[i] == wanted)?;
+
+        score += 1;
+        if position == 0 {
+            score += 8;
+        } else if !candidate_lower[position - 1].is_alphanumeric() {
+            score += 6;
+        }
+        match previous {
+            Some(previous) if previous + 1 == position => score += 5,
+            Some(previous) => score = score.saturating_sub((position - previous - 1).min(3) as u32),
+            None => {}
+        }
+
+        previous = Some(position);
+        next = position + 1;
+    }
+
+    let needle: String = pattern.iter().collect();
+    let haystack: String = candidate_lower
+        .iter()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    if haystack.contains(&needle) {
+        score += 20 + pattern.len() as u32;
+    }
+
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn game(title: &str) -> Game {
+        Game {
+            title: title.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let query =
+            SearchQuery::parse(r#"runner:GE-Proton9 tag:"co op" -hidden:yes foo:bar witcher"#);
+        assert_eq!(query.text, "foo:bar witcher");
+        assert_eq!(
+            query.filters,
+            vec![
+                FieldFilter {
+                    field: SearchField::Runner,
+                    value: "GE-Proton9".to_string(),
+                    negate: false,
+                },
+                FieldFilter {
+                    field: SearchField::Tag,
+                    value: "co op".to_string(),
+                    negate: false,
+                },
+                FieldFilter {
+                    field: SearchField::Hidden,
+                    value: "yes".to_string(),
+                    negate: true,
+                },
+            ]
+        );
+        assert!(query.filters_hidden());
+        assert_eq!(SearchQuery::parse("   "), SearchQuery::default());
+    }
+
+    #[test]
+    fn test_field_filters() {
+        let mut g = game("Portal 2");
+        g.runner = "GE-Proton9-20".to_string();
+        g.prefix = PathBuf::from("/mnt/games/portal");
+        g.mangohud = true;
+        g.tags = vec!["coop".to_string()];
+
+        let matches = |q: &str| SearchQuery::parse(q).score(&g).is_some();
+        assert!(matches("runner:ge-proton9"));
+        assert!(!matches("runner:Proton-Experimental"));
+        assert!(matches("tag:COOP"));
+        assert!(!matches("-tag:coop"));
+        assert!(matches("prefix:/mnt/games"));
+        assert!(!matches("prefix:/games"));
+        assert!(matches("mangohud:on gamemode:off hidden:no"));
+        assert!(!matches("mangohud:maybe"));
+        assert!(matches("tag:coop portal"));
+        assert!(!matches("tag:coop halflife"));
+    }
+
+    #[test]
+    fn test_fuzzy_ranking() {
+        let titles = [
+            "The Witcher 3",
+            "Wild West Witches",
+            "Twitch Streamer Sim",
+            "Portal",
+        ];
+        let query = SearchQuery::parse("witcher");
+        let mut ranked: Vec<(u32, &str)> = titles
+            .iter()
+            .filter_map(|t| Some((query.score(&game(t))?, *t)))
+            .collect();
+        ranked.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+        let ranked: Vec<&str> = ranked.into_iter().map(|(_, t)| t).collect();
+
+        assert_eq!(ranked[0], "The Witcher 3");
+        assert!(!ranked.contains(&"Portal"));
+
+        // Abbreviations match on word starts
+        assert!(fuzzy_score("tw3", "The Witcher 3").is_some());
+        // Matches at the start of the title rank above matches inside a word
+        assert!(fuzzy_score("port", "Portal") > fuzzy_score("port", "Sports Bar"));
+        assert_eq!(fuzzy_score("xyz", "The Witcher 3"), None);
+    }
+}
@@ -0,0 +1,349 @@
+// Library store
+// Locked, crash-safe persistence for games.json
+
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{error, info, warn};
+
+use crate::config::game_config::Game;
+use crate::config::locked_file::LockedFile;
+use crate::config::paths::Paths;
+use crate::config::schema::{self, LibraryFile, MigrationReport, UnsupportedVersion};
+
+/// Number of rotating backups kept next to games.json (.bak, .bak.1, ...)
+const BACKUP_COUNT: usize = 3;
+
+/// Persistent store for the game library
+///
+/// Every access takes an advisory lock on a sibling `.lock` file so that the
+/// GUI and `faugus-run` never interleave a read-modify-write. Writes go to a
+/// temporary file that is renamed over games.json, and the previous valid
+/// contents are kept as rotating `.bak` files used for recovery.
+#[derive(Debug, Clone)]
+pub struct LibraryStore {
+    file: LockedFile,
+}
+
+impl LibraryStore {
+    /// Create a store backed by the given games.json path
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            file: LockedFile::new(path),
+        }
+    }
+
+    /// Open the store at the default games.json location
+    pub fn open() -> Self {
+        Self::new(Paths::games_json())
+    }
+
+    /// Load all games under a shared lock
+    pub fn load(&self) -> Result<Vec<Game>> {
+        let _lock = self.file.lock(false)?;
+        self.read_locked()
+    }
+
+    /// Replace the whole library under an exclusive lock
+    pub fn save(&self, games: &[Game]) -> Result<()> {
+        let _lock = self.file.lock(true)?;
+        self.write_locked(games)
+    }
+
+    /// Locked read-modify-write of the library
+    ///
+    /// The closure sees the current on-disk games; whatever it leaves in the
+    /// vector is written back before the lock is released.
+    pub fn update<T>(&self, f: impl FnOnce(&mut Vec<Game>) -> T) -> Result<T> {
+        let _lock = self.file.lock(true)?;
+        let mut games = self.read_locked()?;
+        let result = f(&mut games);
+        self.write_locked(&games)?;
+        Ok(result)
+    }
+
+    /// Upgrade games.json to the current schema
+    ///
+    /// With `dry_run` the file is left untouched and only the report of
+    /// what would change is returned. The pre-migration file is kept as the
+    /// newest `.bak` when the migration is written.
+    pub fn migrate(&self, dry_run: bool) -> Result<MigrationReport> {
+        let _lock = self.file.lock(!dry_run)?;
+
+        if !self.file.path().exists() {
+            return Ok(MigrationReport {
+                from_version: schema::CURRENT_SCHEMA_VERSION,
+                to_version: schema::CURRENT_SCHEMA_VERSION,
+                changes: Vec::new(),
+            });
+        }
+
+        let content = fs::read_to_string(self.file.path())
+            .with_context(|| format!("Failed to read games file: {:?}", self.file.path()))?;
+        let (games, report) = schema::parse_library(&content)
+            .with_context(|| format!("Failed to migrate games file: {:?}", self.file.path()))?;
+
+        if !dry_run && !report.is_empty() {
+            self.write_locked(&games)?;
+            info!("{}", report);
+        }
+
+        Ok(report)
+    }
+
+    /// Path of the n-th backup (0 is the most recent)
+    fn backup_path(&self, n: usize) -> PathBuf {
+        if n == 0 {
+            self.file.sibling(".bak")
+        } else {
+            self.file.sibling(&format!(".bak.{}", n))
+        }
+    }
+
+    /// Parse a games file of any supported schema version
+    fn parse(path: &Path) -> Result<Vec<Game>> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read games file: {:?}", path))?;
+
+        let (games, report) = schema::parse_library(&content)
+            .with_context(|| format!("Failed to parse games JSON: {:?}", path))?;
+
+        if !report.is_empty() {
+            info!(
+                "Read {:?} as schema v{}, migrated in memory to v{}",
+                path, report.from_version, report.to_version
+            );
+        }
+
+        Ok(games)
+    }
+
+    /// Read games.json, falling back to the newest valid backup if it is corrupt
+    ///
+    /// A library from a newer build isn't corrupt: it is reported as is, an
+    /// older backup would lose whatever the newer build saved.
+    fn read_locked(&self) -> Result<Vec<Game>> {
+        if !self.file.path().exists() {
+            return Ok(Vec::new());
+        }
+
+        let parse_error = match Self::parse(self.file.path()) {
+            Ok(games) => return Ok(games),
+            Err(e) if UnsupportedVersion::find(&e).is_some() => return Err(e),
+            Err(e) => e,
+        };
+
+        error!("{:#}", parse_error);
+
+        // Keep the broken file around for manual inspection
+        let corrupt_path = self.file.sibling(".corrupt");
+        if let Err(e) = fs::copy(self.file.path(), &corrupt_path) {
+            warn!("Failed to preserve corrupt games file: {}", e);
+        }
+
+        for n in 0..BACKUP_COUNT {
+            let backup = self.backup_path(n);
+            if !backup.exists() {
+                continue;
+            }
+
+            match Self::parse(&backup) {
+                Ok(games) => {
+                    warn!(
+                        "Recovered {} games from backup {:?} (corrupt file kept at {:?})",
+                        games.len(),
+                        backup,
+                        corrupt_path
+                    );
+                    return Ok(games);
+                }
+                Err(e) => warn!("Skipping unusable backup: {:#}", e),
+            }
+        }
+
+        bail!(
+            "games.json is corrupt and no valid backup was found \
+             (corrupt file kept at {:?}): {:#}",
+            corrupt_path,
+            parse_error
+        )
+    }
+
+    /// Shift backups down by one and copy the current games.json to .bak
+    fn rotate_backups(&self) {
+        for n in (1..BACKUP_COUNT).rev() {
+            let from = self.backup_path(n - 1);
+            if from.exists() {
+                if let Err(e) = fs::rename(&from, self.backup_path(n)) {
+                    warn!("Failed to rotate backup {:?}: {}", from, e);
+                }
+            }
+        }
+
+        if let Err(e) = fs::copy(self.file.path(), self.backup_path(0)) {
+            warn!("Failed to back up games file: {}", e);
+        }
+    }
+
+    /// Write games.json through a temp file and atomic rename
+    fn write_locked(&self, games: &[Game]) -> Result<()> {
+        let library = LibraryFile::current(games.to_vec());
+        let content =
+            serde_json::to_string_pretty(&library).with_context(|| "Failed to serialize games")?;
+
+        if self.file.path().exists() {
+            match Self::parse(self.file.path()) {
+                Ok(_) => self.rotate_backups(),
+                // Never replace a library written by a newer build
+                Err(e) if UnsupportedVersion::find(&e).is_some() => return Err(e),
+                // Never let a corrupt file push good backups out of the rotation
+                Err(_) => {}
+            }
+        }
+
+        self.file.write_atomic(content.as_bytes())?;
+        info!("Saved {} games to {:?}", games.len(), self.file.path());
+        Ok(())
+    }
+}
+
+impl Default for LibraryStore {
+    fn default() -> Self {
+        Self::open()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_support::TempDir;
+
+    /// Create a store in a fresh temporary directory, removed with the guard
+    fn temp_store() -> (TempDir, LibraryStore) {
+        let dir = TempDir::new("faugus-store");
+        let store = LibraryStore::new(dir.path().join("games.json"));
+        (dir, store)
+    }
+
+    fn test_game(id: &str) -> Game {
+        Game {
+            gameid: id.to_string(),
+            title: format!("Game {}", id),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_load_missing_file_is_empty() {
+        let (_dir, store) = temp_store();
+        let games = store.load().expect("Missing file should load as empty");
+        assert!(games.is_empty());
+    }
+
+    #[test]
+    fn test_update_round_trip() {
+        let (_dir, store) = temp_store();
+        store
+            .update(|games| games.push(test_game("a")))
+            .expect("Update should succeed");
+        store
+            .update(|games| games.push(test_game("b")))
+            .expect("Update should succeed");
+
+        let games = store.load().expect("Load should succeed");
+        let ids: Vec<&str> = games.iter().map(|g| g.gameid.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+        assert!(
+            !store.file.sibling(".tmp").exists(),
+            "temp file should be renamed"
+        );
+    }
+
+    #[test]
+    fn test_backups_rotate_and_are_capped() {
+        let (_dir, store) = temp_store();
+        for id in ["a", "b", "c", "d", "e"] {
+            store
+                .update(|games| games.push(test_game(id)))
+                .expect("Update should succeed");
+        }
+
+        for n in 0..BACKUP_COUNT {
+            assert!(store.backup_path(n).exists(), "backup {} should exist", n);
+        }
+        assert!(!store.backup_path(BACKUP_COUNT).exists());
+
+        let newest = LibraryStore::parse(&store.backup_path(0)).expect("Backup should parse");
+        assert_eq!(newest.len(), 4);
+    }
+
+    #[test]
+    fn test_corrupt_file_recovers_from_backup() {
+        let (_dir, store) = temp_store();
+        store.save(&[test_game("a")]).expect("Save should succeed");
+        store
+            .save(&[test_game("a"), test_game("b")])
+            .expect("Save should succeed");
+
+        fs::write(store.file.path(), "[{ truncated").expect("Failed to corrupt file");
+
+        let games = store.load().expect("Should recover from backup");
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].gameid, "a");
+        assert!(store.file.sibling(".corrupt").exists());
+    }
+
+    #[test]
+    fn test_migrate_dry_run_leaves_file_untouched() {
+        let (_dir, store) = temp_store();
+        let legacy = serde_json::to_string(&vec![test_game("a")]).expect("serialize");
+        fs::write(store.file.path(), &legacy).expect("Failed to write legacy file");
+
+        let report = store.migrate(true).expect("Dry run should succeed");
+        assert_eq!(report.from_version, 0);
+        assert_eq!(
+            fs::read_to_string(store.file.path()).expect("read"),
+            legacy,
+            "dry run must not write"
+        );
+
+        store.migrate(false).expect("Migration should succeed");
+        assert!(store.migrate(true).expect("Dry run").is_empty());
+        assert!(store.backup_path(0).exists(), "legacy file kept as backup");
+    }
+
+    #[test]
+    fn test_corrupt_file_without_backup_is_not_overwritten() {
+        let (_dir, store) = temp_store();
+        fs::write(store.file.path(), "not json").expect("Failed to write file");
+
+        let result = store.update(|games| games.push(test_game("a")));
+        assert!(result.is_err(), "update must not clobber a corrupt library");
+
+        let content = fs::read_to_string(store.file.path()).expect("File should still exist");
+        assert_eq!(content, "not json");
+    }
+
+    #[test]
+    fn test_newer_schema_is_never_overwritten() {
+        let (_dir, store) = temp_store();
+        store.save(&[test_game("a")]).expect("Save should succeed");
+        store
+            .save(&[test_game("a"), test_game("b")])
+            .expect("Save should succeed");
+
+        let newer = r#"{"schema_version": 99, "games": [], "future_field": true}"#;
+        fs::write(store.file.path(), newer).expect("Failed to write file");
+
+        let err = store
+            .load()
+            .expect_err("Newer schema must not load from backup");
+        assert!(UnsupportedVersion::find(&err).is_some());
+        assert!(store.update(|games| games.push(test_game("c"))).is_err());
+        assert!(store.save(&[test_game("c")]).is_err());
+        assert!(store.migrate(false).is_err());
+
+        assert_eq!(fs::read_to_string(store.file.path()).expect("read"), newer);
+        assert!(!store.file.sibling(".corrupt").exists());
+    }
+}
@@ -0,0 +1,93 @@
+// Locked file
+// Advisory locking and atomic writes shared by the JSON stores
+
+use anyhow::{Context, Result};
+use fs2::FileExt;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A data file shared between launcher processes
+///
+/// Access is serialized through an advisory lock on a sibling `.lock` file,
+/// and writes go to a temporary file that is renamed over the data file, so
+/// readers never see a partial write.
+#[derive(Debug, Clone)]
+pub struct LockedFile {
+    path: PathBuf,
+}
+
+impl LockedFile {
+    /// Create a locked file for the given path
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Path of the data file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Path of a sibling file, e.g. games.json.lock
+    pub fn sibling(&self, suffix: &str) -> PathBuf {
+        let mut name = self
+            .path
+            .file_name()
+            .map(|n| n.to_os_string())
+            .unwrap_or_default();
+        name.push(suffix);
+        self.path.with_file_name(name)
+    }
+
+    /// Acquire the advisory lock; released when the returned file is dropped
+    pub fn lock(&self, exclusive: bool) -> Result<File> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+        }
+
+        let lock_path = self.sibling(".lock");
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&lock_path)
+            .with_context(|| format!("Failed to open lock file: {:?}", lock_path))?;
+
+        if exclusive {
+            file.lock_exclusive()
+        } else {
+            file.lock_shared()
+        }
+        .with_context(|| format!("Failed to lock {:?}", lock_path))?;
+
+        Ok(file)
+    }
+
+    /// Replace the file's contents through a temp file and atomic rename
+    ///
+    /// Callers hold the exclusive lock.
+    pub fn write_atomic(&self, content: &[u8]) -> Result<()> {
+        let tmp_path = self.sibling(".tmp");
+        {
+            let mut tmp = File::create(&tmp_path)
+                .with_context(|| format!("Failed to create temp file: {:?}", tmp_path))?;
+            tmp.write_all(content)
+                .with_context(|| format!("Failed to write temp file: {:?}", tmp_path))?;
+            tmp.sync_all()
+                .with_context(|| format!("Failed to sync temp file: {:?}", tmp_path))?;
+        }
+
+        fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("Failed to write {:?}", self.path))?;
+
+        // Persist the rename itself; failure here only weakens durability
+        if let Some(parent) = self.path.parent() {
+            if let Ok(dir) = File::open(parent) {
+                let _ = dir.sync_all();
+            }
+        }
+        Ok(())
+    }
+}
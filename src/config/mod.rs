@@ -4,7 +4,19 @@
 pub mod app_config;
 pub mod envar;
 pub mod game_config;
+pub mod library_order;
+pub mod library_search;
+pub mod library_store;
+pub mod locked_file;
 pub mod paths;
+pub mod schema;
+pub mod session_history;
+#[cfg(test)]
+pub mod test_support;
+pub mod wrappers;
 
-pub use app_config::{AppConfig, InterfaceMode};
-pub use game_config::{format_title, Game};
+pub use app_config::{AppConfig, InterfaceMode, SortMode};
+pub use game_config::{
+    format_duration, format_title, same_tag, Game, GameHooks, GameOverrides, GamescopeSettings,
+    GamescopeUpscaler, GamescopeWindowMode, LaunchProfile, OverrideField, ToggleOverride,
+};
@@ -171,6 +171,11 @@ impl Paths {
         Self::config_dir().join("logs")
     }
 
+    /// Directory holding per-game play session history
+    pub fn sessions_dir() -> PathBuf {
+        Self::config_dir().join("sessions")
+    }
+
     /// Get path to envar.txt (legacy compatibility)
     pub fn envar_txt() -> PathBuf {
         Self::config_dir().join("envar.txt")
@@ -285,4 +290,9 @@ impl Paths {
     pub fn gamemoderun() -> Option<PathBuf> {
         Self::find_binary("gamemoderun")
     }
+
+    /// Get gamescope binary
+    pub fn gamescope() -> Option<PathBuf> {
+        Self::find_binary("gamescope")
+    }
 }
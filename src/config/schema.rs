@@ -0,0 +1,435 @@
+// games.json schema versioning
+// Detects the stored library format and migrates it step by step to the current schema
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use thiserror::Error;
+
+use crate::config::game_config::{format_title, Game};
+
+/// Schema version written by this build
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+/// games.json was written by a newer build with a schema this one can't read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error(
+    "games.json uses schema v{found}, but this build only supports up to v{supported}. \
+     Please update Faugus Launcher."
+)]
+pub struct UnsupportedVersion {
+    pub found: u32,
+    pub supported: u32,
+}
+
+impl UnsupportedVersion {
+    /// Find an unsupported schema version among the causes of an error
+    pub fn find(error: &anyhow::Error) -> Option<Self> {
+        error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<Self>())
+            .copied()
+    }
+}
+
+/// On-disk representation of the game library
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryFile {
+    pub schema_version: u32,
+    pub games: Vec<Game>,
+}
+
+impl LibraryFile {
+    /// Wrap games in the current schema envelope
+    pub fn current(games: Vec<Game>) -> Self {
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            games,
+        }
+    }
+}
+
+/// Summary of what a migration run changed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationReport {
+    pub from_version: u32,
+    pub to_version: u32,
+    /// Human-readable description of every change, in application order
+    pub changes: Vec<String>,
+}
+
+impl MigrationReport {
+    /// True if the stored data was already current
+    pub fn is_empty(&self) -> bool {
+        self.from_version == self.to_version && self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for MigrationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "games.json is up to date (schema v{})", self.to_version);
+        }
+
+        writeln!(
+            f,
+            "games.json schema v{} -> v{} ({} changes)",
+            self.from_version,
+            self.to_version,
+            self.changes.len()
+        )?;
+        for change in &self.changes {
+            writeln!(f, "  - {}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// A single migration step from `from` to `from + 1`
+struct Migration {
+    from: u32,
+    description: &'static str,
+    apply: fn(&mut Value, &mut Vec<String>) -> Result<()>,
+}
+
+/// Ordered migration chain; each step upgrades exactly one version
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 0,
+        description: "Python 1.13.x / unversioned Rust array -> schema v1",
+        apply: migrate_v0_to_v1,
+    },
+    Migration {
+        from: 1,
+        description: "schema v1 -> v2 (per-game settings)",
+        apply: migrate_v1_to_v2,
+    },
+];
+
+/// Detect the schema version of raw games.json data
+///
+/// A bare array is the unversioned format written by the Python launcher
+/// and by Rust builds before schema versioning (version 0).
+pub fn detect_version(value: &Value) -> Result<u32> {
+    match value {
+        Value::Array(_) => Ok(0),
+        Value::Object(map) => {
+            let version = map
+                .get("schema_version")
+                .and_then(Value::as_u64)
+                .context("games.json object is missing a numeric schema_version")?;
+            u32::try_from(version).context("schema_version out of range")
+        }
+        _ => bail!("games.json must contain an array or an object"),
+    }
+}
+
+/// Run all migrations needed to bring raw data to the current schema
+pub fn migrate(mut value: Value) -> Result<(Value, MigrationReport)> {
+    let from_version = detect_version(&value)?;
+
+    if from_version > CURRENT_SCHEMA_VERSION {
+        return Err(UnsupportedVersion {
+            found: from_version,
+            supported: CURRENT_SCHEMA_VERSION,
+        }
+        .into());
+    }
+
+    let mut report = MigrationReport {
+        from_version,
+        to_version: from_version,
+        changes: Vec::new(),
+    };
+
+    while report.to_version < CURRENT_SCHEMA_VERSION {
+        let step = MIGRATIONS
+            .iter()
+            .find(|m| m.from == report.to_version)
+            .with_context(|| format!("No migration from schema v{}", report.to_version))?;
+
+        (step.apply)(&mut value, &mut report.changes)
+            .with_context(|| format!("Migration failed: {}", step.description))?;
+        report.to_version = step.from + 1;
+        set_version(&mut value, report.to_version);
+    }
+
+    Ok((value, report))
+}
+
+/// Parse games.json content of any supported version
+pub fn parse_library(content: &str) -> Result<(Vec<Game>, MigrationReport)> {
+    let value: Value = serde_json::from_str(content).context("Invalid JSON")?;
+    let (value, report) = migrate(value)?;
+    let library: LibraryFile =
+        serde_json::from_value(value).context("Failed to parse migrated library")?;
+    Ok((library.games, report))
+}
+
+/// Stamp the envelope with a schema version
+fn set_version(value: &mut Value, version: u32) {
+    if let Value::Object(map) = value {
+        map.insert("schema_version".to_string(), Value::from(version));
+    }
+}
+
+/// Interpret a Python-era flag string ("MANGOHUD=1", "gamemoderun", "") as a bool
+fn legacy_flag(s: &str) -> bool {
+    let s = s.trim();
+    !(s.is_empty() || s.eq_ignore_ascii_case("false") || s == "0")
+}
+
+/// v0 -> v1: wrap the bare array and normalize Python string-typed fields
+fn migrate_v0_to_v1(value: &mut Value, changes: &mut Vec<String>) -> Result<()> {
+    const BOOL_FIELDS: &[&str] = &[
+        "mangohud",
+        "gamemode",
+        "disable_hidraw",
+        "addapp_checkbox",
+        "lossless_enabled",
+        "lossless_flow",
+        "lossless_performance",
+        "lossless_hdr",
+        "hidden",
+    ];
+    const STRING_FIELDS: &[&str] = &[
+        "launch_arguments",
+        "game_arguments",
+        "protonfix",
+        "runner",
+        "addapp",
+        "addapp_bat",
+    ];
+
+    let Value::Array(games) = value.take() else {
+        bail!("Expected an unversioned games array");
+    };
+
+    let mut migrated = Vec::with_capacity(games.len());
+    for (index, mut game) in games.into_iter().enumerate() {
+        let obj = game
+            .as_object_mut()
+            .with_context(|| format!("Game entry {} is not an object", index))?;
+        let title = obj
+            .get("title")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let label = if title.is_empty() {
+            format!("#{}", index)
+        } else {
+            title.clone()
+        };
+
+        if !obj.get("gameid").is_some_and(Value::is_string) {
+            let gameid = format_title(&title);
+            changes.push(format!("{}: gameid set to \"{}\"", label, gameid));
+            obj.insert("gameid".to_string(), Value::from(gameid));
+        }
+
+        for &field in BOOL_FIELDS {
+            match obj.get(field) {
+                Some(Value::Bool(_)) => {}
+                Some(Value::String(s)) => {
+                    let flag = legacy_flag(s);
+                    changes.push(format!("{}: {} {:?} -> {}", label, field, s, flag));
+                    obj.insert(field.to_string(), Value::from(flag));
+                }
+                _ => {
+                    changes.push(format!("{}: {} missing -> false", label, field));
+                    obj.insert(field.to_string(), Value::from(false));
+                }
+            }
+        }
+
+        for &field in STRING_FIELDS {
+            if !obj.get(field).is_some_and(Value::is_string) {
+                changes.push(format!("{}: {} missing -> \"\"", label, field));
+                obj.insert(field.to_string(), Value::from(""));
+            }
+        }
+
+        normalize_number(obj, "lossless_multiplier", 2, &label, changes);
+        normalize_number(obj, "playtime", 0, &label, changes);
+
+        if obj.get("banner").and_then(Value::as_str) == Some("") {
+            changes.push(format!("{}: empty banner -> none", label));
+            obj.insert("banner".to_string(), Value::Null);
+        }
+
+        migrated.push(game);
+    }
+
+    changes.push(format!(
+        "wrapped {} games in schema envelope",
+        migrated.len()
+    ));
+
+    let mut envelope = Map::new();
+    envelope.insert("schema_version".to_string(), Value::from(1));
+    envelope.insert("games".to_string(), Value::Array(migrated));
+    *value = Value::Object(envelope);
+
+    Ok(())
+}
+
+/// v1 -> v2: no data changes
+///
+/// v2 games carry fields v1 builds don't know (environment, overrides,
+/// profiles, hooks, gamescope, wrappers, tags, favorite, session playtime).
+/// The bump makes v1 builds refuse to save a library they would strip.
+fn migrate_v1_to_v2(value: &mut Value, _changes: &mut Vec<String>) -> Result<()> {
+    if !value.get("games").is_some_and(Value::is_array) {
+        bail!("Expected a games array in the schema envelope");
+    }
+    Ok(())
+}
+
+/// Convert a string or missing number field to a JSON number
+fn normalize_number(
+    obj: &mut Map<String, Value>,
+    field: &str,
+    default: u64,
+    label: &str,
+    changes: &mut Vec<String>,
+) {
+    let number = match obj.get(field) {
+        Some(Value::Number(_)) => return,
+        Some(Value::String(s)) => {
+            let parsed = s.trim().parse::<u64>().unwrap_or(default);
+            changes.push(format!("{}: {} {:?} -> {}", label, field, s, parsed));
+            parsed
+        }
+        _ => {
+            changes.push(format!("{}: {} missing -> {}", label, field, default));
+            default
+        }
+    };
+    obj.insert(field.to_string(), Value::from(number));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PYTHON_LIBRARY: &str = r#"[{
+        "gameid": "test-game",
+        "title": "Test Game",
+        "path": "/path/to/game.exe",
+        "prefix": "/home/user/Faugus/test-game",
+        "launch_arguments": "",
+        "game_arguments": "",
+        "mangohud": "MANGOHUD=1",
+        "gamemode": "",
+        "disable_hidraw": "",
+        "protonfix": "",
+        "runner": "GE-Proton",
+        "addapp_checkbox": "addapp_enabled",
+        "addapp": "",
+        "addapp_bat": "",
+        "banner": "",
+        "lossless_enabled": "false",
+        "lossless_multiplier": "3",
+        "lossless_flow": "",
+        "lossless_performance": "",
+        "lossless_hdr": "",
+        "playtime": 120,
+        "hidden": false
+    }]"#;
+
+    #[test]
+    fn test_detect_version() {
+        let array: Value = serde_json::from_str("[]").expect("valid json");
+        assert_eq!(detect_version(&array).expect("array is v0"), 0);
+
+        let object: Value =
+            serde_json::from_str(r#"{"schema_version": 1, "games": []}"#).expect("valid json");
+        assert_eq!(detect_version(&object).expect("object is versioned"), 1);
+
+        let invalid: Value = serde_json::from_str(r#"{"games": []}"#).expect("valid json");
+        assert!(detect_version(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_python_library_migrates_to_current() {
+        let (games, report) = parse_library(PYTHON_LIBRARY).expect("Migration should succeed");
+
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.to_version, CURRENT_SCHEMA_VERSION);
+        assert!(!report.is_empty());
+        assert!(report
+            .changes
+            .iter()
+            .any(|c| c.contains("mangohud") && c.contains("true")));
+
+        let game = &games[0];
+        assert!(game.mangohud);
+        assert!(!game.gamemode);
+        assert!(game.addapp_checkbox);
+        assert!(
+            !game.lossless_enabled,
+            "\"false\" should not enable lossless"
+        );
+        assert_eq!(game.lossless_multiplier, 3);
+        assert!(game.banner.is_none());
+        assert_eq!(game.playtime, 120);
+    }
+
+    #[test]
+    fn test_missing_fields_are_filled() {
+        let json = r#"[{"title": "Bare Game", "path": "/g.exe", "prefix": "/p"}]"#;
+        let (games, _) = parse_library(json).expect("Migration should succeed");
+
+        assert_eq!(games[0].gameid, "bare-game");
+        assert_eq!(games[0].lossless_multiplier, 2);
+        assert!(games[0].runner.is_empty());
+    }
+
+    #[test]
+    fn test_current_library_is_unchanged() {
+        let library = LibraryFile::current(vec![Game {
+            gameid: "a".to_string(),
+            ..Default::default()
+        }]);
+        let json = serde_json::to_string(&library).expect("serialize");
+
+        let (games, report) = parse_library(&json).expect("Parse should succeed");
+        assert!(report.is_empty());
+        assert_eq!(games.len(), 1);
+    }
+
+    #[test]
+    fn test_v1_library_migrates_to_v2() {
+        let mut library = LibraryFile::current(vec![Game {
+            gameid: "a".to_string(),
+            ..Default::default()
+        }]);
+        library.schema_version = 1;
+        let json = serde_json::to_string(&library).expect("serialize");
+
+        let (games, report) = parse_library(&json).expect("Migration should succeed");
+
+        assert_eq!(report.from_version, 1);
+        assert_eq!(report.to_version, 2);
+        assert!(!report.is_empty(), "v1 files are rewritten as v2");
+        assert_eq!(games[0].gameid, "a");
+    }
+
+    #[test]
+    fn test_newer_schema_is_rejected() {
+        let json = format!(
+            r#"{{"schema_version": {}, "games": []}}"#,
+            CURRENT_SCHEMA_VERSION + 1
+        );
+        let err = parse_library(&json).expect_err("Future schema must fail");
+        assert!(format!("{:#}", err).contains("Please update"));
+        assert_eq!(
+            UnsupportedVersion::find(&err),
+            Some(UnsupportedVersion {
+                found: CURRENT_SCHEMA_VERSION + 1,
+                supported: CURRENT_SCHEMA_VERSION,
+            })
+        );
+    }
+}
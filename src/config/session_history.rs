@@ -0,0 +1,344 @@
+// Session history
+// Per-game record of play sessions and playtime accounting
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+use crate::config::library_store::LibraryStore;
+use crate::config::paths::Paths;
+
+/// Seconds in a day
+pub const DAY: u64 = 86_400;
+
+/// Current time as seconds since the Unix epoch
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// A single play session, in seconds since the Unix epoch
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SessionRecord {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl SessionRecord {
+    /// Session that started at `start` and ends now
+    pub fn ending_now(start: u64) -> Self {
+        Self {
+            start,
+            end: unix_now().max(start),
+        }
+    }
+
+    /// Length of the session in seconds
+    pub fn duration(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Seconds of the session played at or after `cutoff`
+    pub fn duration_since(&self, cutoff: u64) -> u64 {
+        self.end.saturating_sub(self.start.max(cutoff))
+    }
+}
+
+/// Play statistics of a single game
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlayStats {
+    /// Total playtime in seconds, including time tracked before session history existed
+    pub total: u64,
+    pub last_7_days: u64,
+    pub last_30_days: u64,
+    /// Number of recorded sessions
+    pub sessions: usize,
+    /// Seconds played across recorded sessions
+    pub recorded: u64,
+    /// End of the most recent session, seconds since the Unix epoch
+    pub last_played: Option<u64>,
+}
+
+impl PlayStats {
+    /// Compute statistics from a game's stored playtime and its sessions
+    pub fn from_sessions(playtime: u64, sessions: &[SessionRecord], now: u64) -> Self {
+        let played_since = |days: u64| {
+            let cutoff = now.saturating_sub(days * DAY);
+            sessions
+                .iter()
+                .map(|s| s.duration_since(cutoff))
+                .sum::<u64>()
+        };
+        let recorded = sessions.iter().map(SessionRecord::duration).sum::<u64>();
+
+        Self {
+            total: playtime.max(recorded),
+            last_7_days: played_since(7),
+            last_30_days: played_since(30),
+            sessions: sessions.len(),
+            recorded,
+            last_played: sessions.iter().map(|s| s.end).max(),
+        }
+    }
+
+    /// Average length of a recorded session in seconds
+    pub fn average_session(&self) -> u64 {
+        match self.sessions {
+            0 => 0,
+            n => self.recorded / n as u64,
+        }
+    }
+}
+
+/// Play statistics summed over the whole library
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LibraryStats {
+    /// Combined statistics of all games
+    pub combined: PlayStats,
+    /// Number of games with any playtime
+    pub games_played: usize,
+}
+
+impl LibraryStats {
+    /// Sum the statistics of individual games
+    pub fn collect<'a>(stats: impl IntoIterator<Item = &'a PlayStats>) -> Self {
+        stats
+            .into_iter()
+            .fold(Self::default(), |mut summary, game| {
+                let combined = &mut summary.combined;
+                combined.total += game.total;
+                combined.last_7_days += game.last_7_days;
+                combined.last_30_days += game.last_30_days;
+                combined.sessions += game.sessions;
+                combined.recorded += game.recorded;
+                combined.last_played = combined.last_played.max(game.last_played);
+                if game.total > 0 {
+                    summary.games_played += 1;
+                }
+                summary
+            })
+    }
+}
+
+/// Append-only session log, one JSON-lines file per game
+#[derive(Debug, Clone)]
+pub struct SessionHistory {
+    dir: PathBuf,
+}
+
+impl SessionHistory {
+    /// Create a history backed by the given directory
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Open the history at the default location
+    pub fn open() -> Self {
+        Self::new(Paths::sessions_dir())
+    }
+
+    /// History file of a game
+    fn path(&self, gameid: &str) -> PathBuf {
+        self.dir.join(format!("{}.jsonl", gameid))
+    }
+
+    /// Append a session to a game's history
+    pub fn append(&self, gameid: &str, record: &SessionRecord) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create sessions directory: {:?}", self.dir))?;
+
+        let path = self.path(gameid);
+        let line = serde_json::to_string(record).context("Failed to serialize session")?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open session history: {:?}", path))?;
+        writeln!(file, "{}", line)
+            .with_context(|| format!("Failed to write session history: {:?}", path))
+    }
+
+    /// All recorded sessions of a game, oldest first
+    ///
+    /// Malformed lines (e.g. from an interrupted write) are skipped.
+    pub fn load(&self, gameid: &str) -> Result<Vec<SessionRecord>> {
+        let path = self.path(gameid);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read session history: {:?}", path))?;
+
+        Ok(content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(record) => Some(record),
+                Err(e) => {
+                    warn!("Skipping malformed session in {:?}: {}", path, e);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    /// Statistics of a game with the given stored playtime
+    ///
+    /// An unreadable history is logged and treated as empty.
+    pub fn stats(&self, gameid: &str, playtime: u64, now: u64) -> PlayStats {
+        let sessions = self.load(gameid).unwrap_or_else(|e| {
+            warn!("Failed to load session history for {}: {:#}", gameid, e);
+            Vec::new()
+        });
+        PlayStats::from_sessions(playtime, &sessions, now)
+    }
+
+    /// Delete a game's history
+    pub fn remove(&self, gameid: &str) -> Result<()> {
+        let path = self.path(gameid);
+        if path.exists() {
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove session history: {:?}", path))?;
+        }
+        Ok(())
+    }
+}
+
+/// Add a finished session to the game's playtime and history
+pub fn record_session(gameid: &str, record: &SessionRecord) -> Result<()> {
+    let seconds = record.duration();
+
+    let found = LibraryStore::open().update(|games| {
+        games
+            .iter_mut()
+            .find(|g| g.gameid == gameid)
+            .map(|game| game.add_playtime(seconds))
+            .is_some()
+    })?;
+    if !found {
+        warn!("Session ended for unknown game: {}", gameid);
+    }
+
+    SessionHistory::open().append(gameid, record)?;
+    info!("Recorded {}s session for {}", seconds, gameid);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_support::TempDir;
+
+    fn temp_history() -> (TempDir, SessionHistory) {
+        let dir = TempDir::new("faugus-sessions");
+        let history = SessionHistory::new(dir.path().to_path_buf());
+        (dir, history)
+    }
+
+    #[test]
+    fn test_append_and_load() {
+        let (_dir, history) = temp_history();
+        assert!(history.load("game").expect("empty history").is_empty());
+
+        let first = SessionRecord {
+            start: 100,
+            end: 160,
+        };
+        let second = SessionRecord {
+            start: 200,
+            end: 500,
+        };
+        history.append("game", &first).expect("append");
+        history.append("game", &second).expect("append");
+
+        let sessions = history.load("game").expect("load");
+        assert_eq!(sessions, vec![first, second]);
+        assert_eq!(sessions.iter().map(|s| s.duration()).sum::<u64>(), 360);
+    }
+
+    #[test]
+    fn test_malformed_lines_are_skipped() {
+        let (_dir, history) = temp_history();
+        let record = SessionRecord { start: 1, end: 2 };
+        history.append("game", &record).expect("append");
+        fs::write(
+            history.path("game"),
+            format!(
+                "{}\n{{\"start\": 5",
+                serde_json::to_string(&record).expect("json")
+            ),
+        )
+        .expect("write");
+
+        assert_eq!(history.load("game").expect("load"), vec![record]);
+
+        history.remove("game").expect("remove");
+        assert!(history.load("game").expect("load").is_empty());
+    }
+
+    #[test]
+    fn test_play_stats_windows() {
+        let now = 100 * DAY;
+        let sessions = [
+            // 40 days ago, one hour
+            SessionRecord {
+                start: now - 40 * DAY,
+                end: now - 40 * DAY + 3600,
+            },
+            // Straddles the 7-day cutoff: only the last 30 minutes count
+            SessionRecord {
+                start: now - 7 * DAY - 1800,
+                end: now - 7 * DAY + 1800,
+            },
+            // Yesterday, two hours
+            SessionRecord {
+                start: now - DAY,
+                end: now - DAY + 7200,
+            },
+        ];
+
+        let stats = PlayStats::from_sessions(0, &sessions, now);
+        assert_eq!(stats.sessions, 3);
+        assert_eq!(stats.total, 3600 + 3600 + 7200);
+        assert_eq!(stats.last_7_days, 1800 + 7200);
+        assert_eq!(stats.last_30_days, 3600 + 7200);
+        assert_eq!(stats.average_session(), 14400 / 3);
+        assert_eq!(stats.last_played, Some(now - DAY + 7200));
+
+        // Playtime tracked before session history is kept in the total
+        let legacy = PlayStats::from_sessions(50_000, &sessions, now);
+        assert_eq!(legacy.total, 50_000);
+        assert_eq!(legacy.average_session(), stats.average_session());
+
+        let empty = PlayStats::from_sessions(0, &[], now);
+        assert_eq!(empty.average_session(), 0);
+        assert_eq!(empty.last_played, None);
+    }
+
+    #[test]
+    fn test_library_stats() {
+        let played = PlayStats::from_sessions(0, &[SessionRecord { start: 0, end: 600 }], 1000);
+        let legacy = PlayStats::from_sessions(3000, &[], 1000);
+        let unplayed = PlayStats::default();
+
+        let summary = LibraryStats::collect([&played, &legacy, &unplayed]);
+        assert_eq!(summary.games_played, 2);
+        assert_eq!(summary.combined.total, 3600);
+        assert_eq!(summary.combined.sessions, 1);
+        assert_eq!(summary.combined.average_session(), 600);
+        assert_eq!(summary.combined.last_played, Some(600));
+    }
+
+    #[test]
+    fn test_duration_never_negative() {
+        let record = SessionRecord { start: 10, end: 5 };
+        assert_eq!(record.duration(), 0);
+    }
+}
@@ -0,0 +1,32 @@
+// Test support
+// Helpers shared by the unit tests of the file-backed stores
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Fresh directory under the system temp dir, removed when dropped
+///
+/// Dropping also runs when a test panics, so failed runs don't leave
+/// files behind either.
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Create a uniquely named directory; `prefix` names the test suite
+    pub fn new(prefix: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("{}-{}", prefix, uuid::Uuid::new_v4()));
+        fs::create_dir_all(&path).expect("Failed to create temp dir");
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
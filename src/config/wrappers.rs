@@ -0,0 +1,135 @@
+// Launch wrappers
+// Programs the game command is run through (gamemoderun, gamescope, prime-run...)
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::config::paths::Paths;
+
+/// Kind of wrapper program
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum WrapperKind {
+    GameMode,
+    Gamescope,
+    PrimeRun,
+    ObsGameCapture,
+    Strace,
+    /// User-provided program
+    #[default]
+    Custom,
+}
+
+impl WrapperKind {
+    pub const ALL: [WrapperKind; 6] = [
+        WrapperKind::GameMode,
+        WrapperKind::Gamescope,
+        WrapperKind::PrimeRun,
+        WrapperKind::ObsGameCapture,
+        WrapperKind::Strace,
+        WrapperKind::Custom,
+    ];
+
+    /// Binary name of a built-in wrapper
+    pub fn binary(self) -> Option<&'static str> {
+        match self {
+            WrapperKind::GameMode => Some("gamemoderun"),
+            WrapperKind::Gamescope => Some("gamescope"),
+            WrapperKind::PrimeRun => Some("prime-run"),
+            WrapperKind::ObsGameCapture => Some("obs-gamecapture"),
+            WrapperKind::Strace => Some("strace"),
+            WrapperKind::Custom => None,
+        }
+    }
+
+    /// Display label (translation key)
+    pub fn label(self) -> &'static str {
+        match self {
+            WrapperKind::GameMode => "GameMode",
+            WrapperKind::Gamescope => "Gamescope",
+            WrapperKind::PrimeRun => "prime-run",
+            WrapperKind::ObsGameCapture => "OBS game capture",
+            WrapperKind::Strace => "strace",
+            WrapperKind::Custom => "Custom",
+        }
+    }
+}
+
+/// One entry of a wrapper chain
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct Wrapper {
+    pub kind: WrapperKind,
+    /// Program of a custom wrapper, a path or a name looked up in PATH
+    pub command: String,
+    /// Extra arguments placed after the wrapper program
+    pub args: String,
+}
+
+impl Wrapper {
+    /// Built-in wrapper without extra arguments
+    pub fn new(kind: WrapperKind) -> Self {
+        Self {
+            kind,
+            ..Default::default()
+        }
+    }
+
+    /// Find the wrapper program on this system
+    pub fn find_program(&self) -> Option<PathBuf> {
+        match self.kind {
+            WrapperKind::GameMode => Paths::gamemoderun(),
+            WrapperKind::Gamescope => Paths::gamescope(),
+            WrapperKind::Custom => {
+                let command = self.command.trim();
+                if command.is_empty() {
+                    None
+                } else if command.contains('/') {
+                    Some(PathBuf::from(command)).filter(|path| path.exists())
+                } else {
+                    Paths::find_binary(command)
+                }
+            }
+            kind => kind.binary().and_then(Paths::find_binary),
+        }
+    }
+}
+
+/// Parse wrappers stored in config.ini; invalid values give an empty chain
+pub fn wrappers_from_config(value: &str) -> Vec<Wrapper> {
+    if value.trim().is_empty() {
+        return Vec::new();
+    }
+    serde_json::from_str(value).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring invalid wrappers in config: {}", e);
+        Vec::new()
+    })
+}
+
+/// Serialize wrappers for config.ini, on a single line
+pub fn wrappers_to_config(wrappers: &[Wrapper]) -> String {
+    serde_json::to_string(wrappers).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_round_trip() {
+        let wrappers = vec![
+            Wrapper::new(WrapperKind::PrimeRun),
+            Wrapper {
+                kind: WrapperKind::Custom,
+                command: "/usr/bin/taskset".to_string(),
+                args: "-c 0-3".to_string(),
+            },
+        ];
+
+        let line = wrappers_to_config(&wrappers);
+        assert!(!line.contains('\n'));
+        assert_eq!(wrappers_from_config(&line), wrappers);
+        assert!(wrappers_from_config("").is_empty());
+        assert!(wrappers_from_config("not json").is_empty());
+    }
+}
@@ -8,11 +8,22 @@ use iced::{Element, Length, Padding, Task};
 use std::fmt;
 use std::path::PathBuf;
 
-use crate::config::{format_title, AppConfig, Game};
+use crate::config::paths::Paths;
+use crate::config::wrappers::Wrapper;
+use crate::config::{
+    envar, format_title, AppConfig, Game, GameHooks, GameOverrides, GamescopeSettings,
+    GamescopeUpscaler, GamescopeWindowMode, LaunchProfile, OverrideField, ToggleOverride,
+};
 use crate::gui::file_picker;
 use crate::gui::styles::DeepSpace;
+use crate::gui::wrapper_list::{self, WrapperListMessage};
+use crate::gui::Choice;
+use crate::launcher::{addapp, launch_args};
 use crate::locale::I18n;
 use crate::proton::proton_manager::ProtonManager;
+use crate::proton::runner_resolver;
+use crate::shortcuts::DesktopShortcutManager;
+use crate::steam::SteamShortcuts;
 
 /// Launcher types supported by Faugus Launcher
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -54,6 +65,38 @@ impl fmt::Display for LauncherType {
     }
 }
 
+/// Numeric gamescope settings edited through text inputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamescopeValue {
+    OutputWidth,
+    OutputHeight,
+    GameWidth,
+    GameHeight,
+    RefreshRate,
+}
+
+impl GamescopeValue {
+    fn get(self, settings: &GamescopeSettings) -> u32 {
+        match self {
+            GamescopeValue::OutputWidth => settings.output_width,
+            GamescopeValue::OutputHeight => settings.output_height,
+            GamescopeValue::GameWidth => settings.game_width,
+            GamescopeValue::GameHeight => settings.game_height,
+            GamescopeValue::RefreshRate => settings.refresh_rate,
+        }
+    }
+
+    fn set(self, settings: &mut GamescopeSettings, value: u32) {
+        match self {
+            GamescopeValue::OutputWidth => settings.output_width = value,
+            GamescopeValue::OutputHeight => settings.output_height = value,
+            GamescopeValue::GameWidth => settings.game_width = value,
+            GamescopeValue::GameHeight => settings.game_height = value,
+            GamescopeValue::RefreshRate => settings.refresh_rate = value,
+        }
+    }
+}
+
 /// Messages for the Add Game dialog
 #[derive(Debug, Clone)]
 pub enum AddGameMessage {
@@ -75,12 +118,56 @@ pub enum AddGameMessage {
     GameArgumentsChanged(String),
     /// Additional application path changed
     AddAppChanged(String),
+    /// Additional application toggled
+    AddAppToggled(bool),
+    /// Environment variable name changed (row index, name)
+    EnvKeyChanged(usize, String),
+    /// Environment variable value changed (row index, value)
+    EnvValueChanged(usize, String),
+    /// Add an empty environment variable row
+    EnvAdd,
+    /// Per-game override of a global toggle changed
+    OverrideChanged(OverrideField, ToggleOverride),
+    /// Remove an environment variable row
+    EnvRemove(usize),
+    /// Pre-launch hook command changed
+    PreLaunchHookChanged(String),
+    /// Post-exit hook command changed
+    PostExitHookChanged(String),
+    /// Hook timeout input changed
+    HookTimeoutChanged(String),
+    /// Abort the launch if the pre-launch hook fails
+    HookAbortToggled(bool),
+    /// Profile picked for editing (None = the game's own settings)
+    ProfileSelected(Option<usize>),
+    /// Profile name input changed
+    ProfileNameChanged(String),
+    /// Create a profile from the current settings
+    ProfileAdd,
+    /// Rename the profile being edited
+    ProfileRename,
+    /// Remove the profile being edited
+    ProfileRemove,
+    /// Use the profile being edited as the game's default
+    ProfileDefaultToggled(bool),
     /// MangoHud checkbox toggled
     MangoHudToggled(bool),
     /// GameMode checkbox toggled
     GameModeToggled(bool),
     /// Disable Hidraw checkbox toggled
     DisableHidrawToggled(bool),
+    /// Gamescope checkbox toggled
+    GamescopeToggled(bool),
+    /// Gamescope resolution or refresh rate input changed
+    GamescopeValueChanged(GamescopeValue, String),
+    /// Gamescope upscaler changed
+    GamescopeUpscalerChanged(GamescopeUpscaler),
+    /// Gamescope window mode changed
+    GamescopeWindowModeChanged(GamescopeWindowMode),
+    /// Gamescope HDR checkbox toggled
+    GamescopeHdrToggled(bool),
+    /// Wrapper list edited
+    Wrappers(WrapperListMessage),
     /// Create desktop shortcut toggled
     ShortcutDesktopToggled(bool),
     /// Create app menu shortcut toggled
@@ -146,10 +233,32 @@ pub struct AddGameDialog {
     launch_arguments: String,
     /// Game arguments (passed to the game executable)
     game_arguments: String,
+    /// Start an additional application alongside the game
+    addapp_enabled: bool,
     /// Additional application path
     addapp_path: PathBuf,
     /// Banner image path
     banner_path: Option<PathBuf>,
+    /// Per-game environment variables as editable (name, value) rows
+    env_vars: Vec<(String, String)>,
+    /// Per-game overrides for global toggles
+    overrides: GameOverrides,
+    /// Pre-launch and post-exit commands
+    hooks: GameHooks,
+    /// Hook timeout input in seconds (empty = global setting)
+    hook_timeout: String,
+    /// Global configuration, used to show effective override values
+    app_config: AppConfig,
+    /// The game's own launch settings while a profile is being edited
+    base_settings: LaunchProfile,
+    /// Named launch profiles
+    profiles: Vec<LaunchProfile>,
+    /// Profile currently shown in the form (None = the game's own settings)
+    active_profile: Option<usize>,
+    /// Name of the default profile
+    default_profile: Option<String>,
+    /// Profile name input
+    profile_name: String,
 
     // Checkboxes
     /// Enable MangoHud
@@ -158,6 +267,10 @@ pub struct AddGameDialog {
     gamemode: bool,
     /// Disable HIDRAW (for controller support)
     disable_hidraw: bool,
+    /// Gamescope settings
+    gamescope: GamescopeSettings,
+    /// Programs the game is run through, outermost first
+    wrappers: Vec<Wrapper>,
     /// Create desktop shortcut
     shortcut_desktop: bool,
     /// Create app menu shortcut
@@ -206,12 +319,25 @@ impl AddGameDialog {
             protonfix: String::new(),
             launch_arguments: String::new(),
             game_arguments: String::new(),
+            addapp_enabled: false,
             addapp_path: PathBuf::new(),
             banner_path: None,
+            env_vars: Vec::new(),
+            overrides: GameOverrides::default(),
+            hooks: GameHooks::default(),
+            hook_timeout: String::new(),
+            app_config: config.clone(),
+            base_settings: LaunchProfile::from_game("", &Game::default()),
+            profiles: Vec::new(),
+            active_profile: None,
+            default_profile: None,
+            profile_name: String::new(),
 
             mangohud: config.mangohud,
             gamemode: config.gamemode,
             disable_hidraw: config.disable_hidraw,
+            gamescope: GamescopeSettings::default(),
+            wrappers: Vec::new(),
             shortcut_desktop: false,
             shortcut_appmenu: false,
             shortcut_steam: false,
@@ -234,6 +360,12 @@ impl AddGameDialog {
         dialog.editing_game = Some(game.clone());
         dialog.title = i18n.t("Edit Game/App");
 
+        // Shortcut checkboxes reflect the shortcuts that exist
+        dialog.shortcut_desktop = DesktopShortcutManager::exists_on_desktop(&game);
+        dialog.shortcut_appmenu = DesktopShortcutManager::exists_in_applications(&game);
+        dialog.shortcut_steam =
+            SteamShortcuts::load().is_ok_and(|shortcuts| shortcuts.contains(&game.title));
+
         // Load game data into form
         dialog.game_title = game.title;
         dialog.game_path = game.path;
@@ -245,12 +377,28 @@ impl AddGameDialog {
         dialog.mangohud = game.mangohud;
         dialog.gamemode = game.gamemode;
         dialog.disable_hidraw = game.disable_hidraw;
-        dialog.addapp_path = if game.addapp_checkbox {
-            PathBuf::from(&game.addapp)
+        dialog.gamescope = game.gamescope;
+        dialog.wrappers = game.wrappers;
+        // Older entries stored shortcut flags in `addapp` instead of a path
+        let shortcut_flags = game
+            .addapp
+            .split(',')
+            .all(|flag| matches!(flag.trim(), "" | "desktop" | "appmenu" | "steam"));
+        if !shortcut_flags {
+            dialog.addapp_enabled = game.addapp_checkbox;
+            dialog.addapp_path = PathBuf::from(game.addapp.trim());
+        }
+        dialog.banner_path = game.banner;
+        dialog.env_vars = game.env.into_iter().collect();
+        dialog.overrides = game.overrides;
+        dialog.hook_timeout = if game.hooks.timeout > 0 {
+            game.hooks.timeout.to_string()
         } else {
-            PathBuf::new()
+            String::new()
         };
-        dialog.banner_path = game.banner;
+        dialog.hooks = game.hooks;
+        dialog.profiles = game.profiles;
+        dialog.default_profile = game.default_profile;
 
         // Load runner
         let runners = ProtonManager::new().get_available_runners();
@@ -268,11 +416,6 @@ impl AddGameDialog {
         dialog.lossless_performance = game.lossless_performance;
         dialog.lossless_hdr = game.lossless_hdr;
 
-        // Load shortcut settings from addapp fields
-        dialog.shortcut_desktop = game.addapp.contains("desktop");
-        dialog.shortcut_appmenu = game.addapp.contains("appmenu");
-        dialog.shortcut_steam = game.addapp.contains("steam");
-
         dialog
     }
 
@@ -310,12 +453,99 @@ impl AddGameDialog {
             }
             AddGameMessage::LaunchArgumentsChanged(args) => {
                 self.launch_arguments = args;
+                self.error_message = None;
             }
             AddGameMessage::GameArgumentsChanged(args) => {
                 self.game_arguments = args;
+                self.error_message = None;
             }
             AddGameMessage::AddAppChanged(path) => {
                 self.addapp_path = PathBuf::from(path);
+                self.error_message = None;
+            }
+            AddGameMessage::AddAppToggled(enabled) => {
+                self.addapp_enabled = enabled;
+                self.error_message = None;
+            }
+            AddGameMessage::EnvKeyChanged(index, key) => {
+                if let Some(entry) = self.env_vars.get_mut(index) {
+                    entry.0 = key;
+                }
+                self.error_message = None;
+            }
+            AddGameMessage::EnvValueChanged(index, value) => {
+                if let Some(entry) = self.env_vars.get_mut(index) {
+                    entry.1 = value;
+                }
+            }
+            AddGameMessage::OverrideChanged(field, value) => {
+                self.overrides.set(field, value);
+            }
+            AddGameMessage::EnvAdd => {
+                self.env_vars.push((String::new(), String::new()));
+            }
+            AddGameMessage::EnvRemove(index) => {
+                if index < self.env_vars.len() {
+                    self.env_vars.remove(index);
+                }
+                self.error_message = None;
+            }
+            AddGameMessage::PreLaunchHookChanged(command) => {
+                self.hooks.pre_launch = command;
+            }
+            AddGameMessage::PostExitHookChanged(command) => {
+                self.hooks.post_exit = command;
+            }
+            AddGameMessage::HookTimeoutChanged(timeout) => {
+                self.hook_timeout = timeout;
+                self.error_message = None;
+            }
+            AddGameMessage::HookAbortToggled(enabled) => {
+                self.hooks.abort_on_failure = enabled;
+            }
+            AddGameMessage::ProfileSelected(index) => {
+                self.select_profile(index);
+                self.error_message = None;
+            }
+            AddGameMessage::ProfileNameChanged(name) => {
+                self.profile_name = name;
+                self.error_message = None;
+            }
+            AddGameMessage::ProfileAdd => {
+                if let Some(name) = self.checked_profile_name() {
+                    self.profiles
+                        .push(self.form_settings(name, &self.runner_name()));
+                    self.select_profile(Some(self.profiles.len() - 1));
+                    self.profile_name.clear();
+                }
+            }
+            AddGameMessage::ProfileRename => {
+                if let Some(index) = self.active_profile {
+                    if let Some(name) = self.checked_profile_name() {
+                        let old = std::mem::replace(&mut self.profiles[index].name, name.clone());
+                        if self.default_profile.as_deref() == Some(old.as_str()) {
+                            self.default_profile = Some(name);
+                        }
+                        self.profile_name.clear();
+                    }
+                }
+            }
+            AddGameMessage::ProfileRemove => {
+                if let Some(index) = self.active_profile {
+                    let removed = self.profiles.remove(index);
+                    if self.default_profile.as_deref() == Some(removed.name.as_str()) {
+                        self.default_profile = None;
+                    }
+                    // Show the game's own settings without saving the removed profile back
+                    self.active_profile = None;
+                    let base = self.base_settings.clone();
+                    self.load_settings(&base);
+                }
+            }
+            AddGameMessage::ProfileDefaultToggled(enabled) => {
+                if let Some(index) = self.active_profile {
+                    self.default_profile = enabled.then(|| self.profiles[index].name.clone());
+                }
             }
             AddGameMessage::MangoHudToggled(enabled) => {
                 self.mangohud = enabled;
@@ -326,6 +556,29 @@ impl AddGameDialog {
             AddGameMessage::DisableHidrawToggled(enabled) => {
                 self.disable_hidraw = enabled;
             }
+            AddGameMessage::GamescopeToggled(enabled) => {
+                self.gamescope.enabled = enabled;
+            }
+            AddGameMessage::GamescopeValueChanged(field, value) => {
+                // Empty clears the value; anything but digits is ignored
+                if value.is_empty() {
+                    field.set(&mut self.gamescope, 0);
+                } else if let Ok(number) = value.parse() {
+                    field.set(&mut self.gamescope, number);
+                }
+            }
+            AddGameMessage::GamescopeUpscalerChanged(upscaler) => {
+                self.gamescope.upscaler = upscaler;
+            }
+            AddGameMessage::GamescopeWindowModeChanged(mode) => {
+                self.gamescope.window_mode = mode;
+            }
+            AddGameMessage::GamescopeHdrToggled(enabled) => {
+                self.gamescope.hdr = enabled;
+            }
+            AddGameMessage::Wrappers(message) => {
+                wrapper_list::update(&mut self.wrappers, message);
+            }
             AddGameMessage::ShortcutDesktopToggled(enabled) => {
                 self.shortcut_desktop = enabled;
             }
@@ -390,6 +643,89 @@ impl AddGameDialog {
         Task::none()
     }
 
+    /// Name of the selected runner
+    fn runner_name(&self) -> String {
+        self.runners
+            .get(self.runner_index)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Check if the selected runner is Linux-native (no Proton, no prefix)
+    fn is_native(&self) -> bool {
+        self.runners
+            .get(self.runner_index)
+            .is_some_and(|runner| runner_resolver::is_native(runner))
+    }
+
+    /// Launch settings currently shown in the form
+    fn form_settings(&self, name: String, runner: &str) -> LaunchProfile {
+        LaunchProfile {
+            name,
+            runner: runner.to_string(),
+            launch_arguments: self.launch_arguments.clone(),
+            game_arguments: self.game_arguments.clone(),
+            mangohud: self.mangohud,
+            gamemode: self.gamemode,
+            lossless_enabled: self.lossless_enabled,
+            lossless_multiplier: self.lossless_multiplier,
+            lossless_flow: self.lossless_flow,
+            lossless_performance: self.lossless_performance,
+            lossless_hdr: self.lossless_hdr,
+            gamescope: self.gamescope,
+        }
+    }
+
+    /// Show launch settings in the form
+    fn load_settings(&mut self, settings: &LaunchProfile) {
+        if let Some(index) = self.runners.iter().position(|r| r == &settings.runner) {
+            self.runner_index = index;
+        }
+        self.launch_arguments = settings.launch_arguments.clone();
+        self.game_arguments = settings.game_arguments.clone();
+        self.mangohud = settings.mangohud;
+        self.gamemode = settings.gamemode;
+        self.lossless_enabled = settings.lossless_enabled;
+        self.lossless_multiplier = settings.lossless_multiplier;
+        self.lossless_flow = settings.lossless_flow;
+        self.lossless_performance = settings.lossless_performance;
+        self.lossless_hdr = settings.lossless_hdr;
+        self.gamescope = settings.gamescope;
+    }
+
+    /// Store the form into the profile being edited and show another one
+    fn select_profile(&mut self, index: Option<usize>) {
+        let runner = self.runner_name();
+        match self.active_profile {
+            Some(current) => {
+                let name = self.profiles[current].name.clone();
+                self.profiles[current] = self.form_settings(name, &runner);
+            }
+            None => self.base_settings = self.form_settings(String::new(), &runner),
+        }
+
+        self.active_profile = index.filter(|&i| i < self.profiles.len());
+        let settings = match self.active_profile {
+            Some(i) => self.profiles[i].clone(),
+            None => self.base_settings.clone(),
+        };
+        self.load_settings(&settings);
+    }
+
+    /// Trimmed profile name from the input, if it is non-empty and unused
+    fn checked_profile_name(&mut self) -> Option<String> {
+        let name = self.profile_name.trim().to_string();
+        if name.is_empty() {
+            self.error_message = Some("Profile name is required".to_string());
+            return None;
+        }
+        if self.profiles.iter().any(|p| p.name == name) {
+            self.error_message = Some(format!("Profile '{}' already exists", name));
+            return None;
+        }
+        Some(name)
+    }
+
     /// Get default prefix path for a game
     fn default_prefix_for_game(title: &str) -> PathBuf {
         let formatted_title = format_title(title);
@@ -410,11 +746,59 @@ impl AddGameDialog {
             return false;
         }
 
-        if self.prefix.as_os_str().is_empty() {
+        if self.prefix.as_os_str().is_empty() && !self.is_native() {
             self.error_message = Some("Prefix is required".to_string());
             return false;
         }
 
+        if let Err(e) = launch_args::parse_launch_options(&self.launch_arguments) {
+            self.error_message = Some(format!("Launch arguments: {}", e));
+            return false;
+        }
+
+        if let Err(e) = launch_args::split(&self.game_arguments) {
+            self.error_message = Some(format!("Game arguments: {}", e));
+            return false;
+        }
+
+        for wrapper in &self.wrappers {
+            if let Err(e) = launch_args::split(&wrapper.args) {
+                self.error_message = Some(format!("Wrapper arguments: {}", e));
+                return false;
+            }
+        }
+
+        if self.addapp_enabled && !self.is_native() && !self.addapp_path.is_file() {
+            self.error_message =
+                Some("Additional application must be a valid executable".to_string());
+            return false;
+        }
+
+        let mut seen_keys = std::collections::HashSet::new();
+        for (key, _) in &self.env_vars {
+            let key = key.trim();
+            if key.is_empty() {
+                continue;
+            }
+            if !envar::is_valid_env_var_key(key) {
+                self.error_message = Some(format!(
+                    "Invalid environment variable '{}': must match [A-Za-z_][A-Za-z0-9_]*",
+                    key
+                ));
+                return false;
+            }
+            if !seen_keys.insert(key) {
+                self.error_message = Some(format!("Duplicate environment variable '{}'", key));
+                return false;
+            }
+        }
+
+        if !self.hook_timeout.trim().is_empty() && self.hook_timeout.trim().parse::<u64>().is_err()
+        {
+            self.error_message = Some("Hook timeout must be a number of seconds".to_string());
+            return false;
+        }
+
         self.error_message = None;
         true
     }
@@ -443,73 +827,64 @@ impl AddGameDialog {
             uuid::Uuid::new_v4().to_string()
         };
 
-        // Build addapp string from shortcut checkboxes
-        let mut addapp_parts = Vec::new();
-        if self.shortcut_desktop {
-            addapp_parts.push("desktop");
-        }
-        if self.shortcut_appmenu {
-            addapp_parts.push("appmenu");
-        }
-        if self.shortcut_steam {
-            addapp_parts.push("steam");
-        }
-
-        let addapp = if !self.addapp_path.as_os_str().is_empty() {
-            self.addapp_path.to_string_lossy().to_string()
-        } else if !addapp_parts.is_empty() {
-            addapp_parts.join(",")
-        } else {
-            String::new()
-        };
-
-        let addapp_bat = if !addapp.is_empty() {
-            format!(
-                "{}/faugus-{}.bat",
-                self.game_path
-                    .parent()
-                    .unwrap_or_else(|| std::path::Path::new("/"))
-                    .display(),
-                format_title(&self.game_title)
-            )
-        } else {
-            String::new()
+        // The form shows either the game's own settings or the profile being edited
+        let runner = self.runner_name();
+        let mut profiles = self.profiles.clone();
+        let base = match self.active_profile {
+            Some(index) => {
+                profiles[index] = self.form_settings(profiles[index].name.clone(), &runner);
+                self.base_settings.clone()
+            }
+            None => self.form_settings(String::new(), &runner),
         };
 
-        let addapp_checkbox = !addapp.is_empty();
-
-        Game {
+        let mut game = Game {
             gameid,
             title: self.game_title.clone(),
             path: self.game_path.clone(),
             prefix: self.prefix.clone(),
-            launch_arguments: self.launch_arguments.clone(),
-            game_arguments: self.game_arguments.clone(),
-            mangohud: self.mangohud,
-            gamemode: self.gamemode,
             disable_hidraw: self.disable_hidraw,
             protonfix: self.protonfix.clone(),
-            runner: self
-                .runners
-                .get(self.runner_index)
-                .cloned()
-                .unwrap_or_default(),
-            addapp_checkbox,
-            addapp,
-            addapp_bat,
+            addapp_checkbox: self.addapp_enabled && !self.addapp_path.as_os_str().is_empty(),
+            addapp: self.addapp_path.to_string_lossy().to_string(),
             banner: self.banner_path.clone(),
-            lossless_enabled: self.lossless_enabled,
-            lossless_multiplier: self.lossless_multiplier,
-            lossless_flow: self.lossless_flow,
-            lossless_performance: self.lossless_performance,
-            lossless_hdr: self.lossless_hdr,
             playtime: self.editing_game.as_ref().map(|g| g.playtime).unwrap_or(0),
             hidden: self
                 .editing_game
                 .as_ref()
                 .map(|g| g.hidden)
                 .unwrap_or(false),
+            favorite: self
+                .editing_game
+                .as_ref()
+                .map(|g| g.favorite)
+                .unwrap_or(false),
+            tags: self
+                .editing_game
+                .as_ref()
+                .map(|g| g.tags.clone())
+                .unwrap_or_default(),
+            env: self
+                .env_vars
+                .iter()
+                .filter(|(key, _)| !key.trim().is_empty())
+                .map(|(key, value)| (key.trim().to_string(), value.clone()))
+                .collect(),
+            overrides: self.overrides,
+            wrappers: self.wrappers.clone(),
+            hooks: GameHooks {
+                timeout: self.hook_timeout.trim().parse().unwrap_or(0),
+                ..self.hooks.clone()
+            },
+            profiles,
+            default_profile: self.default_profile.clone(),
+            ..Default::default()
+        };
+        base.apply_to(&mut game);
+        if addapp::is_enabled(&game) {
+            game.addapp_bat = addapp::batch_path(&game).to_string_lossy().to_string();
         }
+        game
     }
 
     /// View the dialog
@@ -530,50 +905,40 @@ impl AddGameDialog {
 
     /// View the main dialog
     fn view_main_dialog(&self, i18n: &I18n) -> Element<'_, AddGameMessage> {
-        let title_section = self.view_title_section(i18n);
-        let path_section = self.view_path_section(i18n);
-        let prefix_section = self.view_prefix_section(i18n);
-        let launcher_type_section = self.view_launcher_type_section(i18n);
-        let runner_section = self.view_runner_section(i18n);
-        let protonfix_section = self.view_protonfix_section(i18n);
-        let banner_section = self.view_banner_section(i18n);
-        let arguments_section = self.view_arguments_section(i18n);
-        let options_section = self.view_options_section(i18n);
-        let tools_section = self.view_tools_section(i18n);
-        let shortcuts_section = self.view_shortcuts_section(i18n);
+        // Native games have no prefix, so Wine-only settings and tools are hidden
+        let native = self.is_native();
+
+        let mut sections = vec![self.view_title_section(i18n), self.view_path_section(i18n)];
+        if !native {
+            sections.push(self.view_prefix_section(i18n));
+        }
+        sections.push(self.view_launcher_type_section(i18n));
+        sections.push(self.view_profiles_section(i18n));
+        sections.push(self.view_runner_section(i18n));
+        if !native {
+            sections.push(self.view_protonfix_section(i18n));
+            sections.push(self.view_addapp_section(i18n));
+        }
+        sections.push(self.view_banner_section(i18n));
+        sections.push(self.view_arguments_section(i18n));
+        sections.push(self.view_environment_section(i18n));
+        sections.push(self.view_overrides_section(i18n));
+        sections.push(self.view_hooks_section(i18n));
+        sections.push(self.view_options_section(i18n));
+        sections.push(self.view_gamescope_section(i18n));
+        sections.push(self.view_wrappers_section(i18n));
+        if !native {
+            sections.push(self.view_tools_section(i18n));
+        }
+        sections.push(self.view_shortcuts_section(i18n));
         let buttons_section = self.view_buttons(i18n);
 
-        let scrollable = scrollable(
-            column![
-                title_section,
-                Space::with_height(Length::Fixed(10.0)),
-                path_section,
-                Space::with_height(Length::Fixed(10.0)),
-                prefix_section,
-                Space::with_height(Length::Fixed(10.0)),
-                launcher_type_section,
-                Space::with_height(Length::Fixed(10.0)),
-                runner_section,
-                Space::with_height(Length::Fixed(10.0)),
-                protonfix_section,
-                Space::with_height(Length::Fixed(10.0)),
-                banner_section,
-                Space::with_height(Length::Fixed(10.0)),
-                arguments_section,
-                Space::with_height(Length::Fixed(10.0)),
-                options_section,
-                Space::with_height(Length::Fixed(10.0)),
-                tools_section,
-                Space::with_height(Length::Fixed(10.0)),
-                shortcuts_section,
-            ]
-            .padding(Padding {
-                top: 0.0,
-                right: 10.0,
-                bottom: 0.0,
-                left: 0.0,
-            }),
-        )
+        let scrollable = scrollable(column(sections).spacing(10).padding(Padding {
+            top: 0.0,
+            right: 10.0,
+            bottom: 0.0,
+            left: 0.0,
+        }))
         .width(Length::Fill)
         .height(Length::FillPortion(1))
         .style(DeepSpace::scrollable);
@@ -660,6 +1025,67 @@ impl AddGameDialog {
         .into()
     }
 
+    /// View the launch profile selector
+    fn view_profiles_section(&self, i18n: &I18n) -> Element<'_, AddGameMessage> {
+        let base_label = i18n.t("Game settings");
+        let mut choices = vec![base_label.clone()];
+        choices.extend(self.profiles.iter().map(|p| p.name.clone()));
+        let selected = match self.active_profile {
+            Some(index) => self.profiles[index].name.clone(),
+            None => base_label,
+        };
+
+        let profiles = self.profiles.clone();
+        let mut content = column![
+            text(i18n.t("Launch Profile")).size(14),
+            Space::with_height(Length::Fixed(5.0)),
+            pick_list(choices, Some(selected), move |name: String| {
+                AddGameMessage::ProfileSelected(profiles.iter().position(|p| p.name == name))
+            })
+            .width(Length::Fill)
+            .style(DeepSpace::pick_list)
+            .menu_style(DeepSpace::menu),
+            text(i18n.t(
+                "Proton, arguments, MangoHud, GameMode and Lossless Scaling are saved per profile"
+            ))
+            .size(12),
+        ]
+        .spacing(5);
+
+        let mut actions = row![
+            text_input(&i18n.t("Profile name"), &self.profile_name)
+                .on_input(AddGameMessage::ProfileNameChanged)
+                .style(DeepSpace::text_input),
+            button(text(i18n.t("Add")))
+                .on_press(AddGameMessage::ProfileAdd)
+                .style(DeepSpace::button),
+        ]
+        .spacing(5);
+
+        if let Some(index) = self.active_profile {
+            let is_default = self.default_profile.as_deref() == Some(&self.profiles[index].name);
+            actions = actions.push(
+                button(text(i18n.t("Rename")))
+                    .on_press(AddGameMessage::ProfileRename)
+                    .style(DeepSpace::button),
+            );
+            actions = actions.push(
+                button(text(i18n.t("Remove")))
+                    .on_press(AddGameMessage::ProfileRemove)
+                    .style(DeepSpace::button),
+            );
+            content = content.push(actions).push(
+                checkbox(i18n.t("Default profile"), is_default)
+                    .on_toggle(AddGameMessage::ProfileDefaultToggled)
+                    .style(DeepSpace::checkbox),
+            );
+        } else {
+            content = content.push(actions);
+        }
+
+        content.into()
+    }
+
     /// View the runner section
     fn view_runner_section(&self, i18n: &I18n) -> Element<'_, AddGameMessage> {
         column![
@@ -698,6 +1124,32 @@ impl AddGameDialog {
         .into()
     }
 
+    /// View the additional application section
+    fn view_addapp_section(&self, i18n: &I18n) -> Element<'_, AddGameMessage> {
+        let addapp_display = self.addapp_path.display().to_string();
+        let mut addapp_input =
+            text_input("/path/to/the/app.exe", &addapp_display).style(DeepSpace::text_input);
+        let mut browse = button(text("..."))
+            .width(Length::Fixed(50.0))
+            .style(DeepSpace::button);
+        if self.addapp_enabled {
+            addapp_input = addapp_input.on_input(AddGameMessage::AddAppChanged);
+            browse = browse.on_press(AddGameMessage::BrowseAddApp);
+        }
+
+        column![
+            checkbox(
+                i18n.t("Additional application (trainer, mod loader...)"),
+                self.addapp_enabled
+            )
+            .on_toggle(AddGameMessage::AddAppToggled)
+            .style(DeepSpace::checkbox),
+            row![addapp_input, browse].spacing(5),
+        ]
+        .spacing(5)
+        .into()
+    }
+
     /// View the banner section
     fn view_banner_section(&self, i18n: &I18n) -> Element<'_, AddGameMessage> {
         let banner_display = self
@@ -746,7 +1198,7 @@ impl AddGameDialog {
                 text(i18n.t("Launch Arguments")).size(14),
                 Space::with_height(Length::Fixed(5.0)),
                 text_input(
-                    "e.g.: PROTON_USE_WINED3D=1 gamescope -W 2560 -H 1440",
+                    "e.g.: PROTON_USE_WINED3D=1 gamescope -W 2560 -H 1440 %command% -dx11",
                     &self.launch_arguments
                 )
                 .on_input(AddGameMessage::LaunchArgumentsChanged)
@@ -766,6 +1218,144 @@ impl AddGameDialog {
         .into()
     }
 
+    /// View the per-game environment variables section
+    fn view_environment_section(&self, i18n: &I18n) -> Element<'_, AddGameMessage> {
+        let mut content = column![
+            text(i18n.t("Environment Variables")).size(14),
+            Space::with_height(Length::Fixed(5.0)),
+        ]
+        .spacing(5);
+
+        for (index, (key, value)) in self.env_vars.iter().enumerate() {
+            content = content.push(
+                row![
+                    text_input("DXVK_HUD", key)
+                        .on_input(move |k| AddGameMessage::EnvKeyChanged(index, k))
+                        .width(Length::FillPortion(1))
+                        .style(DeepSpace::text_input),
+                    text_input(&i18n.t("Value"), value)
+                        .on_input(move |v| AddGameMessage::EnvValueChanged(index, v))
+                        .width(Length::FillPortion(2))
+                        .style(DeepSpace::text_input),
+                    button(text("-"))
+                        .on_press(AddGameMessage::EnvRemove(index))
+                        .width(Length::Fixed(50.0))
+                        .style(DeepSpace::button),
+                ]
+                .spacing(5),
+            );
+        }
+
+        content
+            .push(
+                row![button(text(i18n.t("Add variable")))
+                    .on_press(AddGameMessage::EnvAdd)
+                    .style(DeepSpace::button)]
+                .padding(5),
+            )
+            .into()
+    }
+
+    /// View the per-game overrides of global toggles
+    fn view_overrides_section(&self, i18n: &I18n) -> Element<'_, AddGameMessage> {
+        let on_off = |enabled: bool| {
+            if enabled {
+                i18n.t("On")
+            } else {
+                i18n.t("Off")
+            }
+        };
+
+        let mut content = column![
+            text(i18n.t("Overrides")).size(14),
+            Space::with_height(Length::Fixed(5.0)),
+        ]
+        .spacing(5);
+
+        let choices: Vec<Choice<ToggleOverride>> = ToggleOverride::ALL
+            .iter()
+            .map(|&value| Choice::new(value, i18n.t(value.label())))
+            .collect();
+        for field in OverrideField::ALL {
+            let effective = self.overrides.resolve(field, &self.app_config);
+            let global = field.global(&self.app_config);
+
+            content = content.push(
+                row![
+                    text(i18n.t(field.label()))
+                        .size(13)
+                        .width(Length::FillPortion(2)),
+                    pick_list(
+                        choices.clone(),
+                        choices
+                            .iter()
+                            .find(|choice| choice.value == self.overrides.get(field))
+                            .cloned(),
+                        move |choice: Choice<ToggleOverride>| {
+                            AddGameMessage::OverrideChanged(field, choice.value)
+                        }
+                    )
+                    .width(Length::FillPortion(1))
+                    .style(DeepSpace::pick_list)
+                    .menu_style(DeepSpace::menu),
+                    text(format!(
+                        "{}: {} ({}: {})",
+                        i18n.t("Effective"),
+                        on_off(effective),
+                        i18n.t("global"),
+                        on_off(global)
+                    ))
+                    .size(12)
+                    .width(Length::FillPortion(2)),
+                ]
+                .spacing(10)
+                .align_y(iced::alignment::Vertical::Center),
+            );
+        }
+
+        content.into()
+    }
+
+    /// View the pre-launch and post-exit hooks section
+    fn view_hooks_section(&self, i18n: &I18n) -> Element<'_, AddGameMessage> {
+        column![
+            text(i18n.t("Hooks")).size(14),
+            Space::with_height(Length::Fixed(5.0)),
+            text_input(
+                &i18n.t("Pre-launch command, e.g.: mount /mnt/games"),
+                &self.hooks.pre_launch
+            )
+            .on_input(AddGameMessage::PreLaunchHookChanged)
+            .style(DeepSpace::text_input),
+            text_input(
+                &i18n.t("Post-exit command, e.g.: umount /mnt/games"),
+                &self.hooks.post_exit
+            )
+            .on_input(AddGameMessage::PostExitHookChanged)
+            .style(DeepSpace::text_input),
+            row![
+                text(i18n.t("Timeout (seconds)")).size(13),
+                text_input(
+                    &self.app_config.hook_timeout.to_string(),
+                    &self.hook_timeout
+                )
+                .on_input(AddGameMessage::HookTimeoutChanged)
+                .width(Length::Fixed(80.0))
+                .style(DeepSpace::text_input),
+                checkbox(
+                    i18n.t("Don't launch if the pre-launch command fails"),
+                    self.hooks.abort_on_failure
+                )
+                .on_toggle(AddGameMessage::HookAbortToggled)
+                .style(DeepSpace::checkbox),
+            ]
+            .spacing(10)
+            .align_y(iced::alignment::Vertical::Center),
+        ]
+        .spacing(5)
+        .into()
+    }
+
     /// View the options section
     fn view_options_section(&self, i18n: &I18n) -> Element<'_, AddGameMessage> {
         column![
@@ -789,6 +1379,124 @@ impl AddGameDialog {
         .into()
     }
 
+    /// View the gamescope section
+    fn view_gamescope_section(&self, i18n: &I18n) -> Element<'_, AddGameMessage> {
+        let toggle = checkbox(i18n.t("Run in gamescope"), self.gamescope.enabled)
+            .on_toggle(AddGameMessage::GamescopeToggled)
+            .style(DeepSpace::checkbox);
+
+        let mut content = column![
+            text("Gamescope").size(14),
+            Space::with_height(Length::Fixed(5.0)),
+            toggle,
+        ]
+        .spacing(5);
+
+        if !self.gamescope.enabled {
+            return content.into();
+        }
+
+        if Paths::gamescope().is_none() {
+            content = content.push(
+                text(i18n.t("gamescope was not found, the game will start without it")).size(12),
+            );
+        }
+
+        let number = |field: GamescopeValue, placeholder: &str| {
+            let value = field.get(&self.gamescope);
+            let value = if value > 0 {
+                value.to_string()
+            } else {
+                String::new()
+            };
+            text_input(placeholder, &value)
+                .on_input(move |v| AddGameMessage::GamescopeValueChanged(field, v))
+                .width(Length::Fixed(80.0))
+                .style(DeepSpace::text_input)
+        };
+        let resolution = |label: String, width: GamescopeValue, height: GamescopeValue| {
+            row![
+                text(label).size(13).width(Length::Fixed(150.0)),
+                number(width, "1920"),
+                text("x").size(13),
+                number(height, "1080"),
+            ]
+            .spacing(5)
+            .align_y(iced::alignment::Vertical::Center)
+        };
+
+        content
+            .push(resolution(
+                i18n.t("Output resolution"),
+                GamescopeValue::OutputWidth,
+                GamescopeValue::OutputHeight,
+            ))
+            .push(resolution(
+                i18n.t("Game resolution"),
+                GamescopeValue::GameWidth,
+                GamescopeValue::GameHeight,
+            ))
+            .push(
+                row![
+                    text(i18n.t("Refresh rate"))
+                        .size(13)
+                        .width(Length::Fixed(150.0)),
+                    number(GamescopeValue::RefreshRate, "60"),
+                ]
+                .spacing(5)
+                .align_y(iced::alignment::Vertical::Center),
+            )
+            .push(
+                row![
+                    text(i18n.t("Upscaling"))
+                        .size(13)
+                        .width(Length::Fixed(150.0)),
+                    pick_list(
+                        &GamescopeUpscaler::ALL[..],
+                        Some(self.gamescope.upscaler),
+                        AddGameMessage::GamescopeUpscalerChanged
+                    )
+                    .style(DeepSpace::pick_list)
+                    .menu_style(DeepSpace::menu),
+                ]
+                .spacing(5)
+                .align_y(iced::alignment::Vertical::Center),
+            )
+            .push(
+                row![
+                    text(i18n.t("Window mode"))
+                        .size(13)
+                        .width(Length::Fixed(150.0)),
+                    pick_list(
+                        &GamescopeWindowMode::ALL[..],
+                        Some(self.gamescope.window_mode),
+                        AddGameMessage::GamescopeWindowModeChanged
+                    )
+                    .style(DeepSpace::pick_list)
+                    .menu_style(DeepSpace::menu),
+                ]
+                .spacing(5)
+                .align_y(iced::alignment::Vertical::Center),
+            )
+            .push(
+                checkbox(i18n.t("Enable HDR"), self.gamescope.hdr)
+                    .on_toggle(AddGameMessage::GamescopeHdrToggled)
+                    .style(DeepSpace::checkbox),
+            )
+            .into()
+    }
+
+    /// View the wrappers section
+    fn view_wrappers_section(&self, i18n: &I18n) -> Element<'_, AddGameMessage> {
+        column![
+            text(i18n.t("Wrappers")).size(14),
+            Space::with_height(Length::Fixed(5.0)),
+            wrapper_list::view(&self.wrappers, i18n).map(AddGameMessage::Wrappers),
+        ]
+        .spacing(5)
+        .into()
+    }
+
     /// View the tools section
     fn view_tools_section(&self, i18n: &I18n) -> Element<'_, AddGameMessage> {
         column![
@@ -900,3 +1608,50 @@ impl AddGameDialog {
         .into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::wrappers::WrapperKind;
+
+    #[test]
+    fn test_edit_keeps_favorite_and_tags() {
+        let game = Game {
+            title: "Tagged".to_string(),
+            favorite: true,
+            tags: vec!["coop".to_string(), "rpg".to_string()],
+            playtime: 120,
+            ..Default::default()
+        };
+        let mut dialog = AddGameDialog::edit(game.clone(), &AppConfig::default(), &I18n::default());
+        let _ = dialog.update(AddGameMessage::TitleChanged("Renamed".to_string()));
+
+        let edited = dialog.get_game();
+        assert_eq!(edited.gameid, game.gameid);
+        assert_eq!(edited.title, "Renamed");
+        assert!(edited.favorite);
+        assert_eq!(edited.tags, game.tags);
+        assert_eq!(edited.playtime, 120);
+    }
+
+    #[test]
+    fn test_wrapper_argument_errors_are_reported() {
+        let game = Game {
+            title: "Wrapped".to_string(),
+            path: std::env::current_exe().expect("test binary path"),
+            wrappers: vec![Wrapper {
+                command: "taskset".to_string(),
+                args: "-c '0-3".to_string(),
+                ..Wrapper::new(WrapperKind::Custom)
+            }],
+            ..Default::default()
+        };
+        let mut dialog = AddGameDialog::edit(game, &AppConfig::default(), &I18n::default());
+
+        assert!(!dialog.validate());
+        assert_eq!(
+            dialog.error_message.as_deref(),
+            Some("Wrapper arguments: missing closing ' quote")
+        );
+    }
+}
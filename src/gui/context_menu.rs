@@ -6,13 +6,18 @@ use iced::{Element, Length, Point};
 #[derive(Debug, Clone)]
 pub enum ContextMenuMessage {
     Play,
+    PlayProfile(String),
     Edit,
     Delete,
     Duplicate,
     ToggleHidden,
+    ToggleFavorite,
+    EditTags,
     OpenLocation,
     OpenPrefix,
     ShowLogs,
+    ShowStats,
+    ShowLaunchCommand,
 }
 
 #[derive(Debug, Clone)]
@@ -21,15 +26,26 @@ pub struct ContextMenu {
     pub position: Point,
     pub game_title: String,
     pub is_hidden: bool,
+    pub is_favorite: bool,
+    pub profiles: Vec<String>,
 }
 
 impl ContextMenu {
-    pub fn new(game_index: usize, position: Point, game_title: String, is_hidden: bool) -> Self {
+    pub fn new(
+        game_index: usize,
+        position: Point,
+        game_title: String,
+        is_hidden: bool,
+        is_favorite: bool,
+        profiles: Vec<String>,
+    ) -> Self {
         Self {
             game_index,
             position,
             game_title,
             is_hidden,
+            is_favorite,
+            profiles,
         }
     }
 
@@ -40,7 +56,13 @@ impl ContextMenu {
             i18n.t("Hide")
         };
 
-        let content = column![
+        let favorite_label = if self.is_favorite {
+            i18n.t("Remove from favorites")
+        } else {
+            i18n.t("Add to favorites")
+        };
+
+        let mut content = column![
             container(column![text(&self.game_title).size(14),].spacing(2)).padding(8),
             horizontal_rule(1),
             button(text(i18n.t("Play")))
@@ -48,45 +70,81 @@ impl ContextMenu {
                 .width(Length::Fill)
                 .padding(8)
                 .style(DeepSpace::menu_button),
-            button(text(i18n.t("Edit")))
-                .on_press(ContextMenuMessage::Edit)
-                .width(Length::Fill)
-                .padding(8)
-                .style(DeepSpace::menu_button),
-            button(text(i18n.t("Delete")))
-                .on_press(ContextMenuMessage::Delete)
-                .width(Length::Fill)
-                .padding(8)
-                .style(DeepSpace::menu_button),
-            button(text(i18n.t("Duplicate")))
-                .on_press(ContextMenuMessage::Duplicate)
-                .width(Length::Fill)
-                .padding(8)
-                .style(DeepSpace::menu_button),
-            button(text(hide_show_label))
-                .on_press(ContextMenuMessage::ToggleHidden)
-                .width(Length::Fill)
-                .padding(8)
-                .style(DeepSpace::menu_button),
-            horizontal_rule(1),
-            button(text(i18n.t("Open game location")))
-                .on_press(ContextMenuMessage::OpenLocation)
-                .width(Length::Fill)
-                .padding(8)
-                .style(DeepSpace::menu_button),
-            button(text(i18n.t("Open prefix location")))
-                .on_press(ContextMenuMessage::OpenPrefix)
-                .width(Length::Fill)
-                .padding(8)
-                .style(DeepSpace::menu_button),
-            button(text(i18n.t("Show logs")))
-                .on_press(ContextMenuMessage::ShowLogs)
-                .width(Length::Fill)
-                .padding(8)
-                .style(DeepSpace::menu_button),
         ]
         .spacing(2);
 
+        for profile in &self.profiles {
+            content = content.push(
+                button(text(format!("{}: {}", i18n.t("Play"), profile)))
+                    .on_press(ContextMenuMessage::PlayProfile(profile.clone()))
+                    .width(Length::Fill)
+                    .padding(8)
+                    .style(DeepSpace::menu_button),
+            );
+        }
+
+        let content = content.push(
+            column![
+                button(text(i18n.t("Edit")))
+                    .on_press(ContextMenuMessage::Edit)
+                    .width(Length::Fill)
+                    .padding(8)
+                    .style(DeepSpace::menu_button),
+                button(text(i18n.t("Delete")))
+                    .on_press(ContextMenuMessage::Delete)
+                    .width(Length::Fill)
+                    .padding(8)
+                    .style(DeepSpace::menu_button),
+                button(text(i18n.t("Duplicate")))
+                    .on_press(ContextMenuMessage::Duplicate)
+                    .width(Length::Fill)
+                    .padding(8)
+                    .style(DeepSpace::menu_button),
+                button(text(hide_show_label))
+                    .on_press(ContextMenuMessage::ToggleHidden)
+                    .width(Length::Fill)
+                    .padding(8)
+                    .style(DeepSpace::menu_button),
+                button(text(favorite_label))
+                    .on_press(ContextMenuMessage::ToggleFavorite)
+                    .width(Length::Fill)
+                    .padding(8)
+                    .style(DeepSpace::menu_button),
+                button(text(i18n.t("Edit tags")))
+                    .on_press(ContextMenuMessage::EditTags)
+                    .width(Length::Fill)
+                    .padding(8)
+                    .style(DeepSpace::menu_button),
+                horizontal_rule(1),
+                button(text(i18n.t("Open game location")))
+                    .on_press(ContextMenuMessage::OpenLocation)
+                    .width(Length::Fill)
+                    .padding(8)
+                    .style(DeepSpace::menu_button),
+                button(text(i18n.t("Open prefix location")))
+                    .on_press(ContextMenuMessage::OpenPrefix)
+                    .width(Length::Fill)
+                    .padding(8)
+                    .style(DeepSpace::menu_button),
+                button(text(i18n.t("Show logs")))
+                    .on_press(ContextMenuMessage::ShowLogs)
+                    .width(Length::Fill)
+                    .padding(8)
+                    .style(DeepSpace::menu_button),
+                button(text(i18n.t("Statistics")))
+                    .on_press(ContextMenuMessage::ShowStats)
+                    .width(Length::Fill)
+                    .padding(8)
+                    .style(DeepSpace::menu_button),
+                button(text(i18n.t("Show launch command")))
+                    .on_press(ContextMenuMessage::ShowLaunchCommand)
+                    .width(Length::Fill)
+                    .padding(8)
+                    .style(DeepSpace::menu_button),
+            ]
+            .spacing(2),
+        );
+
         container(content)
             .width(Length::Fixed(220.0))
             .padding(4)
@@ -20,3 +20,14 @@ pub async fn pick_folder() -> Option<PathBuf> {
 
     folder.map(|f| f.path().to_path_buf())
 }
+
+/// Choose where to save a file using a native dialog
+pub async fn save_file(file_name: String) -> Option<PathBuf> {
+    let file = AsyncFileDialog::new()
+        .set_title("Save File")
+        .set_file_name(file_name)
+        .save_file()
+        .await;
+
+    file.map(|f| f.path().to_path_buf())
+}
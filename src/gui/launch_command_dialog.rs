@@ -0,0 +1,208 @@
+// Launch Command Dialog
+// Shows the exact command a game launches with, to copy or export
+
+use iced::widget::{button, column, container, pick_list, row, scrollable, text, Space};
+use iced::{Element, Font, Length, Task};
+use std::path::PathBuf;
+
+use crate::config::Game;
+use crate::gui::file_picker;
+use crate::gui::styles::DeepSpace;
+use crate::launcher::game_launcher::GameLauncher;
+use crate::launcher::launch_plan::LaunchPlan;
+use crate::locale::I18n;
+
+/// Messages for the Launch Command dialog
+#[derive(Debug, Clone)]
+pub enum LaunchCommandMessage {
+    /// Show the command of a profile, or of the game settings with None
+    ProfileSelected(Option<String>),
+    /// Copy the command line to the clipboard
+    Copy,
+    /// Pick a file to export the launch script to
+    Export,
+    /// Export destination picked, or the picker cancelled
+    ExportPicked(Option<PathBuf>),
+    /// Close dialog
+    Close,
+}
+
+/// Outcome of the last copy or export
+#[derive(Debug, Clone)]
+enum Status {
+    Copied,
+    Exported(PathBuf),
+    Failed(String),
+}
+
+/// State for the Launch Command dialog
+#[derive(Debug, Clone)]
+pub struct LaunchCommandDialog {
+    game: Game,
+    /// Profile the command is shown for, None for the game settings
+    profile: Option<String>,
+    /// The plan, or why it could not be made
+    plan: Result<LaunchPlan, String>,
+    /// Result of the last copy or export
+    status: Option<Status>,
+}
+
+impl LaunchCommandDialog {
+    /// Create a new Launch Command dialog for a game
+    ///
+    /// Like Play, the game's default profile is shown first.
+    pub fn new(game: Game) -> Self {
+        let profile = game
+            .default_profile
+            .clone()
+            .filter(|name| game.profile(name).is_some());
+        let plan = Self::plan(&game, profile.as_deref());
+        Self {
+            game,
+            profile,
+            plan,
+            status: None,
+        }
+    }
+
+    /// Plan of the game with a profile applied, or with its own settings
+    fn plan(game: &Game, profile: Option<&str>) -> Result<LaunchPlan, String> {
+        let resolved = match profile {
+            Some(name) => game.resolve_profile(Some(name)),
+            None => Ok(game.clone()),
+        };
+        resolved
+            .and_then(|game| GameLauncher::launch_plan(&game))
+            .map_err(|e| format!("{:#}", e))
+    }
+
+    /// Update the dialog state
+    pub fn update(&mut self, message: LaunchCommandMessage) -> Task<LaunchCommandMessage> {
+        if let LaunchCommandMessage::ProfileSelected(profile) = message {
+            self.plan = Self::plan(&self.game, profile.as_deref());
+            self.profile = profile;
+            self.status = None;
+            return Task::none();
+        }
+
+        let Ok(plan) = &self.plan else {
+            return Task::none();
+        };
+
+        match message {
+            LaunchCommandMessage::Copy => {
+                self.status = Some(Status::Copied);
+                return iced::clipboard::write(plan.command_line());
+            }
+            LaunchCommandMessage::Export => {
+                let file_name = format!("{}.sh", plan.gameid);
+                return Task::perform(
+                    file_picker::save_file(file_name),
+                    LaunchCommandMessage::ExportPicked,
+                );
+            }
+            LaunchCommandMessage::ExportPicked(Some(path)) => {
+                self.status = Some(match plan.export_script(&path) {
+                    Ok(()) => Status::Exported(path),
+                    Err(e) => Status::Failed(format!("{:#}", e)),
+                });
+            }
+            LaunchCommandMessage::ExportPicked(None) | LaunchCommandMessage::ProfileSelected(_) => {
+            }
+            LaunchCommandMessage::Close => {
+                // Handled by parent
+            }
+        }
+        Task::none()
+    }
+
+    /// View the dialog
+    pub fn view(&self, i18n: &I18n) -> Element<'_, LaunchCommandMessage> {
+        let header =
+            container(text(format!("{}: {}", i18n.t("Launch command"), self.game.title)).size(20))
+                .padding(10)
+                .width(Length::Fill);
+
+        // The same choice as Play in the context menu
+        let base_label = i18n.t("Game settings");
+        let mut choices = vec![base_label.clone()];
+        choices.extend(self.game.profile_names());
+        let selected = self.profile.clone().unwrap_or(base_label.clone());
+        let profiles = (!self.game.profiles.is_empty()).then(|| {
+            row![
+                text(i18n.t("Launch Profile")).size(14),
+                pick_list(choices, Some(selected), move |name: String| {
+                    LaunchCommandMessage::ProfileSelected((name != base_label).then_some(name))
+                })
+                .style(DeepSpace::pick_list)
+                .menu_style(DeepSpace::menu),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center)
+        });
+
+        let (body, ready) = match &self.plan {
+            Ok(plan) => (plan.to_string(), true),
+            Err(e) => (e.clone(), false),
+        };
+        let body = scrollable(text(body).size(12).font(Font::MONOSPACE))
+            .height(Length::Fill)
+            .width(Length::Fill);
+
+        let status = match &self.status {
+            None => String::new(),
+            Some(Status::Copied) => i18n.t("Command copied to the clipboard"),
+            Some(Status::Exported(path)) => {
+                format!("{} {}", i18n.t("Launch script saved to"), path.display())
+            }
+            Some(Status::Failed(e)) => e.clone(),
+        };
+        let status = text(status).size(12);
+
+        let buttons = row![
+            button(text(i18n.t("Copy")).size(14))
+                .on_press_maybe(ready.then_some(LaunchCommandMessage::Copy)),
+            button(text(i18n.t("Export as .sh")).size(14))
+                .on_press_maybe(ready.then_some(LaunchCommandMessage::Export)),
+            Space::with_width(Length::Fill),
+            button(text(i18n.t("Close")).size(14)).on_press(LaunchCommandMessage::Close),
+        ]
+        .spacing(10);
+
+        column![header]
+            .push_maybe(profiles)
+            .push(body)
+            .push(status)
+            .push(buttons)
+            .spacing(10)
+            .padding(20)
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LaunchProfile;
+
+    #[test]
+    fn test_starts_with_default_profile() {
+        let mut game = Game {
+            title: "Profiled".to_string(),
+            ..Default::default()
+        };
+        game.profiles
+            .push(LaunchProfile::from_game("Benchmark", &game));
+        game.default_profile = Some("Benchmark".to_string());
+
+        let mut dialog = LaunchCommandDialog::new(game.clone());
+        assert_eq!(dialog.profile.as_deref(), Some("Benchmark"));
+
+        let _ = dialog.update(LaunchCommandMessage::ProfileSelected(None));
+        assert_eq!(dialog.profile, None);
+
+        // A default profile that was deleted falls back to the game settings
+        game.profiles.clear();
+        assert_eq!(LaunchCommandDialog::new(game).profile, None);
+    }
+}
@@ -1,18 +1,53 @@
 // Log Viewer Dialog
-// Simple dialog for viewing and managing application logs
+// Dialog for viewing the session logs of each game
 
-use iced::widget::{button, column, container, row, scrollable, text, Space};
+use iced::widget::{button, column, container, pick_list, row, scrollable, text, Space};
 use iced::{Element, Length};
+use std::fmt;
 use std::fs;
 use std::path::PathBuf;
 use tracing::{error, info, warn};
 
 use crate::config::paths::Paths;
+use crate::config::Game;
+use crate::gui::styles::DeepSpace;
+use crate::launcher::game_logs;
 use crate::locale::I18n;
 
+/// Game entry of the game picker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogGame {
+    gameid: String,
+    title: String,
+}
+
+impl fmt::Display for LogGame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.title)
+    }
+}
+
+/// Log file entry of the session picker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogSession(PathBuf);
+
+impl fmt::Display for LogSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.0.file_name().unwrap_or_default().to_string_lossy()
+        )
+    }
+}
+
 /// Messages for the Log Viewer dialog
 #[derive(Debug, Clone)]
 pub enum LogViewerMessage {
+    /// Show the logs of a game
+    GameSelected(LogGame),
+    /// Show a log file of the selected game
+    SessionSelected(LogSession),
     /// Refresh logs
     Refresh,
     /// Clear current log file
@@ -26,98 +61,117 @@ pub enum LogViewerMessage {
 /// State for the Log Viewer dialog
 #[derive(Debug, Clone)]
 pub struct LogViewerDialog {
+    /// Games of the library
+    games: Vec<LogGame>,
+    /// Game whose logs are shown
+    selected_game: Option<LogGame>,
+    /// Log files of the selected game, newest first
+    sessions: Vec<LogSession>,
+    /// Log file shown
+    selected_session: Option<LogSession>,
     /// Current log content
     log_content: String,
-    /// Current log file path
-    log_file_path: Option<PathBuf>,
 }
 
 impl LogViewerDialog {
-    /// Create a new Log Viewer dialog
-    pub fn new() -> Self {
+    /// Create a new Log Viewer dialog, showing the latest log of `selected`
+    pub fn new(games: &[Game], selected: Option<&str>) -> Self {
+        let mut games: Vec<LogGame> = games
+            .iter()
+            .map(|game| LogGame {
+                gameid: game.gameid.clone(),
+                title: game.title.clone(),
+            })
+            .collect();
+        games.sort_by_key(|game| game.title.to_lowercase());
+
+        let selected_game = selected
+            .and_then(|id| games.iter().find(|game| game.gameid == id))
+            .cloned();
+
         let mut dialog = Self {
+            games,
+            selected_game,
+            sessions: Vec::new(),
+            selected_session: None,
             log_content: String::new(),
-            log_file_path: None,
         };
-
-        dialog.load_latest_log();
+        dialog.load_sessions();
         dialog
     }
 
-    /// Load the most recent log file
-    fn load_latest_log(&mut self) {
-        let logs_dir = Paths::logs_dir();
+    /// List the logs of the selected game and show the newest one
+    fn load_sessions(&mut self) {
+        self.sessions = self
+            .selected_game
+            .as_ref()
+            .map(|game| {
+                game_logs::list_logs(&game.gameid)
+                    .into_iter()
+                    .map(LogSession)
+                    .collect()
+            })
+            .unwrap_or_default();
 
-        // Create logs directory if it doesn't exist
-        if !logs_dir.exists() {
-            if let Err(e) = fs::create_dir_all(&logs_dir) {
-                error!("Failed to create logs directory: {}", e);
-            }
+        // Keep the shown log across refreshes while it exists
+        if !self
+            .selected_session
+            .as_ref()
+            .is_some_and(|session| self.sessions.contains(session))
+        {
+            self.selected_session = self.sessions.first().cloned();
         }
+        self.load_log();
+    }
 
-        // Find the most recent log file
-        if let Ok(entries) = fs::read_dir(&logs_dir) {
-            let mut most_recent: Option<(PathBuf, std::time::SystemTime)> = None;
-
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path
-                    .extension()
-                    .is_some_and(|ext| ext == "log" || ext == "txt")
-                {
-                    if let Ok(metadata) = fs::metadata(&path) {
-                        if let Ok(modified) = metadata.modified() {
-                            let is_more_recent =
-                                most_recent.as_ref().is_none_or(|(_, m)| modified > *m);
-                            if is_more_recent {
-                                most_recent = Some((path, modified));
-                            }
-                        }
-                    }
-                }
-            }
-
-            if let Some((path, _)) = most_recent {
-                self.log_file_path = Some(path.clone());
-                self.log_content = fs::read_to_string(&path)
-                    .unwrap_or_else(|e| format!("Failed to read log file: {}", e));
-            } else {
-                self.log_content =
-                    "No log files found. Logs will appear here once the application has been run."
-                        .to_string();
+    /// Read the selected log file
+    fn load_log(&mut self) {
+        self.log_content = match (&self.selected_game, &self.selected_session) {
+            (None, _) => "Select a game to see its logs.".to_string(),
+            (Some(_), None) => {
+                "No log files found. Logs will appear here once the game has been run.".to_string()
             }
-        } else {
-            self.log_content =
-                "Logs directory not found. Enable logging in Settings to create log files."
-                    .to_string();
-        }
-
-        // If log is empty, show a message
-        if self.log_content.is_empty() && self.log_file_path.is_some() {
-            self.log_content =
-                "Log file is empty. Logs will appear here as the application runs.".to_string();
-        }
+            (Some(_), Some(LogSession(path))) => match fs::read_to_string(path) {
+                Ok(content) if content.is_empty() => "Log file is empty.".to_string(),
+                Ok(content) => content,
+                Err(e) => format!("Failed to read log file: {}", e),
+            },
+        };
     }
 
     /// Update the dialog state
     pub fn update(&mut self, message: LogViewerMessage) {
         match message {
+            LogViewerMessage::GameSelected(game) => {
+                self.selected_game = Some(game);
+                self.selected_session = None;
+                self.load_sessions();
+            }
+            LogViewerMessage::SessionSelected(session) => {
+                self.selected_session = Some(session);
+                self.load_log();
+            }
             LogViewerMessage::Refresh => {
-                self.load_latest_log();
+                self.load_sessions();
                 info!("Log viewer refreshed");
             }
             LogViewerMessage::ClearLogs => {
-                if let Some(path) = &self.log_file_path {
+                if let Some(LogSession(path)) = &self.selected_session {
                     if let Err(e) = fs::write(path, "") {
                         error!("Failed to clear log file: {}", e);
                     } else {
                         info!("Cleared log file: {:?}", path);
-                        self.load_latest_log();
+                        self.load_log();
                     }
                 }
             }
             LogViewerMessage::OpenLogDirectory => {
-                let logs_dir = Paths::logs_dir();
+                let logs_dir = self
+                    .selected_game
+                    .as_ref()
+                    .map(|game| game_logs::game_logs_dir(&game.gameid))
+                    .filter(|dir| dir.exists())
+                    .unwrap_or_else(Paths::logs_dir);
                 info!("Opening log directory: {:?}", logs_dir);
 
                 // Try to open with xdg-open
@@ -140,20 +194,34 @@ impl LogViewerDialog {
     /// View the dialog
     pub fn view(&self, i18n: &I18n) -> Element<'_, LogViewerMessage> {
         // Header with title
-        let header = container(text(i18n.t("Application Logs")).size(20))
+        let header = container(text(i18n.t("Game Logs")).size(20))
             .padding(10)
             .width(Length::Fill);
 
-        // File info
-        let file_info = if let Some(path) = &self.log_file_path {
-            text(format!(
-                "File: {}",
-                path.file_name().unwrap_or_default().to_string_lossy()
-            ))
-            .size(12)
-        } else {
-            text("No log file").size(12)
-        };
+        // Game and session pickers
+        let pickers = row![
+            text(i18n.t("Game")).size(14),
+            pick_list(
+                &self.games[..],
+                self.selected_game.clone(),
+                LogViewerMessage::GameSelected
+            )
+            .placeholder(i18n.t("Select a game"))
+            .width(Length::FillPortion(1))
+            .style(DeepSpace::pick_list)
+            .menu_style(DeepSpace::menu),
+            text(i18n.t("Session")).size(14),
+            pick_list(
+                &self.sessions[..],
+                self.selected_session.clone(),
+                LogViewerMessage::SessionSelected
+            )
+            .width(Length::FillPortion(1))
+            .style(DeepSpace::pick_list)
+            .menu_style(DeepSpace::menu),
+        ]
+        .spacing(10)
+        .align_y(iced::alignment::Vertical::Center);
 
         // Log content display
         let log_display = self.view_log_display();
@@ -164,7 +232,7 @@ impl LogViewerDialog {
         column![
             header,
             Space::with_height(Length::Fixed(10.0)),
-            file_info,
+            pickers,
             Space::with_height(Length::Fixed(10.0)),
             log_display,
             Space::with_height(Length::Fixed(10.0)),
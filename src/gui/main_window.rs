@@ -2,17 +2,25 @@
 // Primary GUI window for Faugus Launcher using Iced
 
 use iced::widget::{
-    button, column, container, horizontal_space, image, mouse_area, row, scrollable, text,
-    text_input,
+    button, column, container, horizontal_space, image, mouse_area, pick_list, row, scrollable,
+    text, text_input,
 };
 use iced::{Alignment, Element, Length, Task};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::path::PathBuf;
+use std::time::Duration;
 use tracing::{error, info};
 
-use crate::config::{AppConfig, Game, InterfaceMode};
+use crate::config::library_order::{self, Activity};
+use crate::config::library_search::SearchQuery;
+use crate::config::session_history::{self, PlayStats, SessionHistory};
+use crate::config::{format_duration, same_tag, AppConfig, Game, InterfaceMode, SortMode};
+use crate::gui::stats_dialog::format_last_played;
 use crate::gui::styles::DeepSpace;
+use crate::gui::tag_dialog::tag_chip;
 use crate::icons::IconManager;
+use crate::launcher::game_launcher::{GameLauncher, Remedy, StopOutcome};
+use crate::launcher::umu_progress::UmuProgress;
 use crate::launcher::{GameLaunchController, LaunchStatus};
 use crate::locale::I18n;
 use crate::shortcuts::DesktopShortcutManager;
@@ -21,6 +29,9 @@ use crate::Message;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Number of games shown in the "recently played" strip
+const RECENT_GAMES_LIMIT: usize = 6;
+
 /// Main application window
 pub struct MainWindow {
     config: AppConfig,
@@ -28,25 +39,44 @@ pub struct MainWindow {
     games: Vec<Game>,
     selected_game_index: Option<usize>,
     search_query: String,
+    /// Parsed form of `search_query`
+    search: SearchQuery,
+    /// Tags a game must all have to be shown (lowercase)
+    tag_filter: BTreeSet<String>,
+    /// Only show favorite games
+    favorites_only: bool,
     launch_controller: GameLaunchController,
+    /// Launch status by game ID
     launch_status: HashMap<String, LaunchStatus>,
+    /// Setup step of games that are still starting, by game ID
+    launch_progress: HashMap<String, UmuProgress>,
+    /// How the last stop of a game went, by game ID
+    stop_outcomes: HashMap<String, StopOutcome>,
     icon_cache: HashMap<String, PathBuf>,
+    /// Play statistics by game ID, refreshed with the games list
+    play_stats: HashMap<String, PlayStats>,
+    /// Game IDs from latest-games.txt, most recent first
+    latest_games: Vec<String>,
     last_click: Option<(usize, std::time::Instant)>,
     show_error_dialog: Option<String>,
+    /// Action offered in the error dialog, with the game it applies to
+    error_remedy: Option<(String, Remedy)>,
 }
 
 impl MainWindow {
     /// Create a new main window
     pub fn new(config: AppConfig, i18n: I18n, games: Vec<Game>) -> Self {
         let launch_controller = GameLaunchController::new();
+        // Games launched before the launcher was restarted
+        launch_controller.restore_running_games();
         let mut launch_status = HashMap::new();
         let mut icon_cache = HashMap::new();
 
         // Check initial launch status for all games
         for game in &games {
             launch_status.insert(
-                game.title.clone(),
-                launch_controller.get_status(&game.title),
+                game.gameid.clone(),
+                launch_controller.get_status(&game.gameid),
             );
             // Cache icon paths
             let icon_path = IconManager::get_icon_path(&game.gameid);
@@ -55,17 +85,27 @@ impl MainWindow {
             }
         }
 
+        let play_stats = Self::compute_play_stats(&games);
+
         Self {
             config,
             i18n,
             games,
             selected_game_index: None,
             search_query: String::new(),
+            search: SearchQuery::default(),
+            tag_filter: BTreeSet::new(),
+            favorites_only: false,
             launch_controller,
             launch_status,
+            launch_progress: HashMap::new(),
+            stop_outcomes: HashMap::new(),
             icon_cache,
+            play_stats,
+            latest_games: GameLauncher::latest_games(),
             last_click: None,
             show_error_dialog: None,
+            error_remedy: None,
         }
     }
 
@@ -89,14 +129,87 @@ impl MainWindow {
         self.selected_game_index
     }
 
-    /// Get the launch controller
-    pub fn launch_controller(&self) -> &GameLaunchController {
-        &self.launch_controller
+    /// Get play statistics by game ID
+    pub fn play_stats(&self) -> &HashMap<String, PlayStats> {
+        &self.play_stats
+    }
+
+    /// Compute play statistics of every game from its session history
+    fn compute_play_stats(games: &[Game]) -> HashMap<String, PlayStats> {
+        let history = SessionHistory::open();
+        let now = session_history::unix_now();
+        games
+            .iter()
+            .map(|game| {
+                (
+                    game.gameid.clone(),
+                    history.stats(&game.gameid, game.playtime, now),
+                )
+            })
+            .collect()
+    }
+
+    /// Play activity used to order the library
+    fn activity(&self) -> Activity<'_> {
+        Activity {
+            stats: &self.play_stats,
+            latest_games: &self.latest_games,
+        }
+    }
+
+    /// Games matching the search, with their index in `games`, in display order
+    ///
+    /// With search text, the best title matches come first; games with the
+    /// same score keep the library sort order.
+    fn visible_games(&self) -> Vec<(usize, &Game)> {
+        let mut scored: Vec<(u32, usize)> =
+            library_order::sorted_indices(&self.games, self.config.sort_mode, self.activity())
+                .into_iter()
+                .filter_map(|index| Some((self.search_score(&self.games[index])?, index)))
+                .collect();
+        if !self.search.text.is_empty() {
+            scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+        }
+        scored
+            .into_iter()
+            .map(|(_, index)| (index, &self.games[index]))
+            .collect()
+    }
+
+    /// IDs of the games currently shown, in display order
+    pub fn visible_game_ids(&self) -> Vec<String> {
+        self.visible_games()
+            .into_iter()
+            .map(|(_, game)| game.gameid.clone())
+            .collect()
+    }
+
+    /// All tags used in the library, sorted and without duplicates
+    pub fn all_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in self.games.iter().flat_map(|game| &game.tags) {
+            if !tags.iter().any(|t| same_tag(t, tag)) {
+                tags.push(tag.clone());
+            }
+        }
+        tags.sort_by_key(|t| t.to_lowercase());
+        tags
+    }
+
+    /// Title shown in the library, starred for favorites
+    fn display_title(game: &Game) -> String {
+        if game.favorite {
+            format!("★ {}", game.title)
+        } else {
+            game.title.clone()
+        }
     }
 
     /// Reload games from storage
     pub fn reload_games(&mut self) {
         self.games = Game::load_all().unwrap_or_default();
+        self.play_stats = Self::compute_play_stats(&self.games);
+        self.latest_games = GameLauncher::latest_games();
 
         // Update icon cache
         for game in &self.games {
@@ -118,8 +231,68 @@ impl MainWindow {
     }
 
     /// Update launch status for a game
-    pub fn update_launch_status(&mut self, title: &str, status: LaunchStatus) {
-        self.launch_status.insert(title.to_string(), status);
+    pub fn update_launch_status(&mut self, gameid: &str, status: LaunchStatus) {
+        if !matches!(status, LaunchStatus::Running(_)) {
+            self.launch_progress.remove(gameid);
+        }
+        self.launch_status.insert(gameid.to_string(), status);
+    }
+
+    /// Title of a game for messages, its ID if it is no longer in the library
+    fn game_title(&self, gameid: &str) -> String {
+        self.games
+            .iter()
+            .find(|game| game.gameid == gameid)
+            .map_or_else(|| gameid.to_string(), |game| game.title.clone())
+    }
+
+    /// Seconds a stopped game gets to exit before it is killed
+    fn stop_grace_period(&self) -> Duration {
+        Duration::from_secs(self.config.stop_grace_period)
+    }
+
+    /// Setup steps of the games being started, one per line
+    pub fn launch_progress_summary(&self) -> Option<String> {
+        let mut lines: Vec<String> = self
+            .launch_progress
+            .iter()
+            .map(|(gameid, progress)| format!("{}: {}", self.game_title(gameid), progress))
+            .collect();
+        lines.sort();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+
+    /// Launch a game with the given profile, or its default profile
+    fn launch_game_at(&mut self, index: usize, profile: Option<&str>) -> Task<Message> {
+        let Some(game) = self.games.get(index) else {
+            return Task::none();
+        };
+        // Launches from other instances are refused when the game starts
+        if self.launch_controller.is_running(&game.gameid) {
+            info!("Not launching {}: already running", game.title);
+            self.show_error_dialog = Some(format!("{} is already running", game.title));
+            return Task::none();
+        }
+        self.stop_outcomes.remove(&game.gameid);
+
+        match game.resolve_profile(profile) {
+            Ok(resolved) => {
+                info!(
+                    "Launching game: {} (profile: {})",
+                    resolved.title,
+                    profile
+                        .or(game.default_profile.as_deref())
+                        .unwrap_or("game settings")
+                );
+                let task = self.launch_controller.launch_game(resolved);
+                task.map(Message::LaunchMessage)
+            }
+            Err(e) => {
+                error!("{:#}", e);
+                self.show_error_dialog = Some(format!("{:#}", e));
+                Task::none()
+            }
+        }
     }
 
     /// Get icon path for a game (from cache or load)
@@ -211,35 +384,41 @@ impl MainWindow {
                 Task::none()
             }
             Message::GameDoubleClicked(index) => {
-                // Double click launches the game
+                // Double click launches the game with its default profile
                 self.selected_game_index = Some(index);
-                if let Some(game) = self.games.get(index).cloned() {
-                    info!("Launching game via double-click: {}", game.title);
-                    let task = self.launch_controller.launch_game(game);
-                    return task.map(Message::LaunchMessage);
-                }
-                Task::none()
+                self.launch_game_at(index, None)
+            }
+            Message::LaunchProfile(index, profile) => {
+                self.selected_game_index = Some(index);
+                self.launch_game_at(index, Some(&profile))
             }
             Message::PlayClicked => {
                 if let Some(index) = self.selected_game_index {
-                    if let Some(game) = self.games.get(index).cloned() {
-                        info!("Launching game: {}", game.title);
-                        let task = self.launch_controller.launch_game(game);
-                        return task.map(Message::LaunchMessage);
-                    }
+                    return self.launch_game_at(index, None);
                 }
                 Task::none()
             }
             Message::KillProcessClicked => {
-                if let Some(index) = self.selected_game_index {
-                    if let Some(game) = self.games.get(index) {
-                        info!("Killing game process: {}", game.title);
-                        if let Err(e) = self.launch_controller.terminate_game(&game.title) {
-                            eprintln!("Failed to terminate game: {}", e);
-                        }
-                    }
+                let Some(game) = self.selected_game_index.and_then(|i| self.games.get(i)) else {
+                    return Task::none();
+                };
+                let gameid = game.gameid.clone();
+                let task = self
+                    .launch_controller
+                    .stop_game(&gameid, self.stop_grace_period());
+                let status = self.launch_controller.get_status(&gameid);
+                self.update_launch_status(&gameid, status);
+                task.map(Message::LaunchMessage)
+            }
+            Message::KillAllProcesses => {
+                info!("Stopping all games");
+                let running = self.launch_controller.get_running_games();
+                let task = self.launch_controller.stop_all(self.stop_grace_period());
+                for (gameid, _) in running {
+                    let status = self.launch_controller.get_status(&gameid);
+                    self.update_launch_status(&gameid, status);
                 }
-                Task::none()
+                task.map(Message::LaunchMessage)
             }
             Message::AddClicked => {
                 info!("Add game clicked");
@@ -317,6 +496,11 @@ impl MainWindow {
                             error!("Failed to remove icon: {}", e);
                         }
 
+                        // Remove play session history
+                        if let Err(e) = SessionHistory::open().remove(&game.gameid) {
+                            error!("Failed to remove session history: {}", e);
+                        }
+
                         // Reload games list
                         self.reload_games();
 
@@ -331,53 +515,116 @@ impl MainWindow {
                 Task::done(Message::ShowSettingsDialog)
             }
             Message::SearchChanged(query) => {
+                self.search = SearchQuery::parse(&query);
                 self.search_query = query;
-                // TODO: Filter games based on query
                 Task::none()
             }
-            Message::Tick => {
-                let dead_games = self.launch_controller.check_processes();
-                for title in dead_games {
-                    self.update_launch_status(&title, LaunchStatus::NotRunning);
+            Message::ToggleTagFilter(tag) => {
+                let tag = tag.to_lowercase();
+                if !self.tag_filter.remove(&tag) {
+                    self.tag_filter.insert(tag);
                 }
                 Task::none()
             }
+            Message::ToggleFavoritesFilter => {
+                self.favorites_only = !self.favorites_only;
+                Task::none()
+            }
+            Message::SortModeChanged(mode) => {
+                self.config.sort_mode = mode;
+                if let Err(e) = self.config.save() {
+                    error!("Failed to save sort mode: {}", e);
+                }
+                Task::none()
+            }
+            Message::Tick => self
+                .launch_controller
+                .check_processes()
+                .map(Message::LaunchMessage),
             Message::LaunchMessage(msg) => {
                 use crate::launcher::LaunchMessage;
                 match msg {
-                    LaunchMessage::Launched(title, process) => {
-                        info!("Game launched successfully: {}", title);
-                        self.update_launch_status(&title, LaunchStatus::Running(process));
+                    LaunchMessage::Launched(gameid, process) => {
+                        info!("Game launched successfully: {}", process.game_title);
+                        self.latest_games = GameLauncher::latest_games();
+                        self.update_launch_status(&gameid, LaunchStatus::Running(process));
                         // Handle close on launch
                         if self.config.close_on_launch {
                             // Close application
                             // TODO: Implement proper close
                         }
                     }
-                    LaunchMessage::LaunchFailed(title, error) => {
+                    LaunchMessage::LaunchFailed(gameid, error) => {
+                        let title = self.game_title(&gameid);
                         info!("Game launch failed: {} - {}", title, error);
-                        self.update_launch_status(&title, LaunchStatus::Error(error.clone()));
+                        self.update_launch_status(&gameid, LaunchStatus::Error(error.to_string()));
                         self.show_error_dialog =
                             Some(format!("Failed to launch {}: {}", title, error));
+                        self.error_remedy = error.remedy().map(|remedy| (gameid, remedy));
+                    }
+                    LaunchMessage::ProcessExited(gameid, _pid) => {
+                        info!("Game process exited: {}", self.game_title(&gameid));
+                        self.update_launch_status(&gameid, LaunchStatus::NotRunning);
+                        // The finished session added playtime
+                        self.reload_games();
+                    }
+                    LaunchMessage::Progress(gameid, progress) => {
+                        info!(
+                            "Launch progress for {}: {}",
+                            self.game_title(&gameid),
+                            progress
+                        );
+                        if progress == UmuProgress::Started {
+                            self.launch_progress.remove(&gameid);
+                        } else {
+                            self.launch_progress.insert(gameid, progress);
+                        }
                     }
-                    LaunchMessage::ProcessExited(title, _pid) => {
-                        info!("Game process exited: {}", title);
-                        self.update_launch_status(&title, LaunchStatus::NotRunning);
+                    LaunchMessage::ProgressFinished(gameid) => {
+                        self.launch_progress.remove(&gameid);
                     }
-                    LaunchMessage::Progress(title, msg) => {
-                        info!("Launch progress for {}: {}", title, msg);
+                    LaunchMessage::Stopped(gameid, outcome) => {
+                        info!("{}: {}", self.game_title(&gameid), outcome);
+                        self.update_launch_status(&gameid, LaunchStatus::NotRunning);
+                        self.stop_outcomes.insert(gameid, outcome);
+                        // The stopped session added playtime
+                        self.reload_games();
+                    }
+                    LaunchMessage::StopFailed(gameid, error) => {
+                        self.update_launch_status(&gameid, LaunchStatus::NotRunning);
+                        self.reload_games();
+                        self.show_error_dialog = Some(format!(
+                            "Failed to stop {}: {}",
+                            self.game_title(&gameid),
+                            error
+                        ));
                     }
                 }
                 Task::none()
             }
-            Message::ProcessExited(title) => {
-                info!("Process exited: {}", title);
-                self.launch_controller.on_process_exited(&title);
-                self.update_launch_status(&title, LaunchStatus::NotRunning);
+            Message::ProcessExited(gameid) => {
+                info!("Process exited: {}", self.game_title(&gameid));
+                self.launch_controller.on_process_exited(&gameid);
+                self.update_launch_status(&gameid, LaunchStatus::NotRunning);
+                self.reload_games();
                 Task::none()
             }
             Message::CloseErrorDialog => {
                 self.show_error_dialog = None;
+                self.error_remedy = None;
+                Task::none()
+            }
+            Message::ExecutableRelinked(gameid, Some(path)) => {
+                if let Some(game) = self.games.iter().find(|game| game.gameid == gameid) {
+                    let mut game = game.clone();
+                    info!("Relinking {} to {:?}", game.title, path);
+                    game.path = path;
+                    if let Err(e) = game.save() {
+                        error!("Failed to save game: {:#}", e);
+                        self.show_error_dialog = Some(format!("{:#}", e));
+                    }
+                    self.reload_games();
+                }
                 Task::none()
             }
             _ => Task::none(),
@@ -400,16 +647,25 @@ impl MainWindow {
             .style(DeepSpace::main_window_container);
 
         if let Some(error) = &self.show_error_dialog {
-            let error_modal = container(
-                column![
-                    text("Error").size(20),
-                    text(error),
-                    button(text("Close"))
-                        .on_press(Message::CloseErrorDialog)
+            let mut buttons = row![].spacing(10);
+            if let Some((gameid, remedy)) = &self.error_remedy {
+                buttons = buttons.push(
+                    button(text(self.remedy_label(remedy)))
+                        .on_press(Message::ApplyRemedy(gameid.clone(), remedy.clone()))
                         .padding(10)
-                ]
-                .spacing(20)
-                .align_x(Alignment::Center),
+                        .style(DeepSpace::primary_button),
+                );
+            }
+            let buttons = buttons.push(
+                button(text("Close"))
+                    .on_press(Message::CloseErrorDialog)
+                    .padding(10),
+            );
+
+            let error_modal = container(
+                column![text("Error").size(20), text(error), buttons]
+                    .spacing(20)
+                    .align_x(Alignment::Center),
             )
             .width(Length::Fixed(400.0))
             .padding(20)
@@ -433,6 +689,17 @@ impl MainWindow {
         }
     }
 
+    /// Button label of an error dialog action
+    fn remedy_label(&self, remedy: &Remedy) -> String {
+        match remedy {
+            Remedy::InstallUmu => self.i18n.t("Get UMU-Launcher"),
+            Remedy::InstallRunner(name) => format!("{} {}", self.i18n.t("Install"), name),
+            Remedy::RelinkExecutable => self.i18n.t("Relink executable"),
+            Remedy::EditGame => self.i18n.t("Edit game"),
+            Remedy::OpenSettings => self.i18n.t("Open settings"),
+        }
+    }
+
     /// View the header
     fn view_header(&self) -> Element<'_, Message> {
         let version_text = format!("Version: {}", VERSION);
@@ -449,20 +716,76 @@ impl MainWindow {
 
     /// View the content area (games list)
     fn view_content(&self) -> Element<'_, Message> {
-        match self.config.interface_mode {
+        let games = match self.config.interface_mode {
             InterfaceMode::List => self.view_list_mode(),
             InterfaceMode::Blocks => self.view_blocks_mode(),
             InterfaceMode::Banners => self.view_banners_mode(),
+        };
+
+        match self.view_recent_strip() {
+            Some(strip) => column![strip, games].spacing(10).into(),
+            None => games,
         }
     }
 
-    /// View in list mode
-    fn view_list_mode(&self) -> Element<'_, Message> {
-        let games_list: Vec<Element<Message>> = self
+    /// View the "recently played" strip above the library
+    fn view_recent_strip(&self) -> Option<Element<'_, Message>> {
+        let candidates = self
             .games
             .iter()
-            .filter(|game| self.game_matches_search(game))
             .enumerate()
+            .filter(|(_, game)| !game.hidden || self.config.show_hidden)
+            .map(|(index, _)| index);
+        let recent = library_order::recently_played(
+            &self.games,
+            candidates,
+            self.activity(),
+            RECENT_GAMES_LIMIT,
+        );
+        if recent.is_empty() {
+            return None;
+        }
+
+        let entries: Vec<Element<Message>> = recent
+            .into_iter()
+            .map(|index| {
+                let game = &self.games[index];
+                let is_selected = self.selected_game_index == Some(index);
+
+                let entry = row![self.load_icon(&game.gameid), text(&game.title).size(13)]
+                    .spacing(8)
+                    .align_y(Alignment::Center);
+
+                let container = container(entry).padding(6).style(if is_selected {
+                    DeepSpace::container
+                } else {
+                    DeepSpace::transparent_container
+                });
+
+                mouse_area(container)
+                    .on_press(Message::GameClicked(index))
+                    .on_right_press(Message::GameRightClicked(index))
+                    .into()
+            })
+            .collect();
+
+        Some(
+            column![
+                text(self.i18n.t("Recently played")).size(12),
+                scrollable(row(entries).spacing(10)).direction(scrollable::Direction::Horizontal(
+                    scrollable::Scrollbar::default()
+                )),
+            ]
+            .spacing(5)
+            .into(),
+        )
+    }
+
+    /// View in list mode
+    fn view_list_mode(&self) -> Element<'_, Message> {
+        let games_list: Vec<Element<Message>> = self
+            .visible_games()
+            .into_iter()
             .map(|(index, game)| {
                 let is_selected = self.selected_game_index == Some(index);
                 let is_hidden = game.hidden;
@@ -472,13 +795,13 @@ impl MainWindow {
 
                 // Dim the title if hidden
                 let title_text = if is_hidden {
-                    text(&game.title).size(16).style(|_theme: &iced::Theme| {
-                        iced::widget::text::Style {
+                    text(Self::display_title(game))
+                        .size(16)
+                        .style(|_theme: &iced::Theme| iced::widget::text::Style {
                             color: Some(iced::Color::from_rgb(0.6, 0.6, 0.6)),
-                        }
-                    })
+                        })
                 } else {
-                    text(&game.title).size(16)
+                    text(Self::display_title(game)).size(16)
                 };
 
                 let game_row = row![icon, title_text, horizontal_space(),]
@@ -513,10 +836,8 @@ impl MainWindow {
     /// View in blocks mode
     fn view_blocks_mode(&self) -> Element<'_, Message> {
         let games_blocks: Vec<Element<Message>> =
-            self.games
-                .iter()
-                .filter(|game| self.game_matches_search(game))
-                .enumerate()
+            self.visible_games()
+                .into_iter()
                 .map(|(index, game)| {
                     let is_selected = self.selected_game_index == Some(index);
                     let is_hidden = game.hidden;
@@ -541,13 +862,13 @@ impl MainWindow {
 
                     // Dim the title if hidden
                     let title_text = if is_hidden {
-                        text(&game.title).size(16).style(|_theme: &iced::Theme| {
-                            iced::widget::text::Style {
+                        text(Self::display_title(game))
+                            .size(16)
+                            .style(|_theme: &iced::Theme| iced::widget::text::Style {
                                 color: Some(iced::Color::from_rgb(0.6, 0.6, 0.6)),
-                            }
-                        })
+                            })
                     } else {
-                        text(&game.title).size(16)
+                        text(Self::display_title(game)).size(16)
                     };
 
                     let content = column![icon, title_text]
@@ -577,10 +898,8 @@ impl MainWindow {
     /// View in banners mode
     fn view_banners_mode(&self) -> Element<'_, Message> {
         let games_banners: Vec<Element<Message>> = self
-            .games
-            .iter()
-            .filter(|game| self.game_matches_search(game))
-            .enumerate()
+            .visible_games()
+            .into_iter()
             .map(|(index, game)| {
                 let is_selected = self.selected_game_index == Some(index);
                 let is_hidden = game.hidden;
@@ -605,13 +924,13 @@ impl MainWindow {
 
                 // Dim the title if hidden
                 let title_text = if is_hidden {
-                    text(&game.title).size(16).style(|_theme: &iced::Theme| {
-                        iced::widget::text::Style {
+                    text(Self::display_title(game))
+                        .size(16)
+                        .style(|_theme: &iced::Theme| iced::widget::text::Style {
                             color: Some(iced::Color::from_rgb(0.6, 0.6, 0.6)),
-                        }
-                    })
+                        })
                 } else {
-                    text(&game.title).size(16)
+                    text(Self::display_title(game)).size(16)
                 };
 
                 let content = column![icon, title_text]
@@ -654,18 +973,28 @@ impl MainWindow {
             .width(Length::Fill)
             .style(DeepSpace::text_input);
 
+        let sort_picker = pick_list(
+            &SortMode::ALL[..],
+            Some(self.config.sort_mode),
+            Message::SortModeChanged,
+        )
+        .width(Length::Fill)
+        .style(DeepSpace::pick_list)
+        .menu_style(DeepSpace::menu);
+
         // Get selected game status
         let selected_status = self
             .selected_game_index
             .and_then(|index| self.games.get(index))
-            .and_then(|game| self.launch_status.get(&game.title))
+            .and_then(|game| self.launch_status.get(&game.gameid))
             .cloned()
             .unwrap_or(LaunchStatus::NotRunning);
 
         let is_running = matches!(
             selected_status,
-            LaunchStatus::Running(_) | LaunchStatus::Launching
+            LaunchStatus::Running(_) | LaunchStatus::Launching | LaunchStatus::Stopping(_)
         );
+        let is_stopping = matches!(selected_status, LaunchStatus::Stopping(_));
         let has_error = matches!(selected_status, LaunchStatus::Error(_));
 
         // Play/Kill button text
@@ -678,23 +1007,31 @@ impl MainWindow {
         };
 
         let play_kill_button = button(text(play_kill_text))
-            .on_press(if is_running {
-                Message::KillProcessClicked
+            .on_press_maybe(if is_stopping {
+                None
+            } else if is_running {
+                Some(Message::KillProcessClicked)
             } else {
-                Message::PlayClicked
+                Some(Message::PlayClicked)
             })
             .padding(10)
             .width(Length::Fill)
             .style(DeepSpace::button);
 
+        let selected_progress = self
+            .selected_game_index
+            .and_then(|index| self.games.get(index))
+            .and_then(|game| self.launch_progress.get(&game.gameid));
+
         let status_text = match selected_status {
-            LaunchStatus::Running(_) => {
-                text("Running")
-                    .size(11)
-                    .style(|_| iced::widget::text::Style {
-                        color: Some(crate::gui::styles::colors::ACCENT),
-                    })
-            }
+            LaunchStatus::Running(_) => text(match selected_progress {
+                Some(progress) => progress.to_string(),
+                None => "Running".to_string(),
+            })
+            .size(11)
+            .style(|_| iced::widget::text::Style {
+                color: Some(crate::gui::styles::colors::ACCENT),
+            }),
             LaunchStatus::Launching => {
                 text("Launching...")
                     .size(11)
@@ -709,9 +1046,83 @@ impl MainWindow {
                         color: Some(iced::Color::from_rgb(1.0, 0.3, 0.3)),
                     })
             }
-            LaunchStatus::NotRunning => text("").size(11),
+            LaunchStatus::Stopping(_) => {
+                text("Stopping...")
+                    .size(11)
+                    .style(|_| iced::widget::text::Style {
+                        color: Some(crate::gui::styles::colors::ACCENT),
+                    })
+            }
+            LaunchStatus::NotRunning => text(
+                self.selected_game_index
+                    .and_then(|index| self.games.get(index))
+                    .and_then(|game| self.stop_outcomes.get(&game.gameid))
+                    .map(|outcome| outcome.to_string())
+                    .unwrap_or_default(),
+            )
+            .size(11),
         };
 
+        // Playtime of the selected game
+        let game_details: Element<'_, Message> = match self
+            .selected_game_index
+            .and_then(|index| self.games.get(index))
+        {
+            Some(game) => {
+                let stats = self
+                    .play_stats
+                    .get(&game.gameid)
+                    .copied()
+                    .unwrap_or_default();
+                column![
+                    text(Self::display_title(game)).size(14),
+                    text(format!(
+                        "{}: {}",
+                        self.i18n.t("Playtime"),
+                        format_duration(stats.total)
+                    ))
+                    .size(11),
+                    text(format!(
+                        "{}: {}",
+                        self.i18n.t("Last played"),
+                        format_last_played(
+                            &self.i18n,
+                            stats.last_played,
+                            session_history::unix_now()
+                        )
+                    ))
+                    .size(11),
+                    text(if game.tags.is_empty() {
+                        String::new()
+                    } else {
+                        format!("{}: {}", self.i18n.t("Tags"), game.tags.join(", "))
+                    })
+                    .size(11),
+                ]
+                .spacing(2)
+                .into()
+            }
+            None => column![].into(),
+        };
+
+        // Favorites and tag filter chips
+        let mut chips = vec![tag_chip(
+            format!("★ {}", self.i18n.t("Favorites")),
+            self.favorites_only,
+            Message::ToggleFavoritesFilter,
+        )];
+        chips.extend(self.all_tags().into_iter().map(|tag| {
+            let active = self.tag_filter.contains(&tag.to_lowercase());
+            tag_chip(tag.clone(), active, Message::ToggleTagFilter(tag))
+        }));
+        let tag_filters = row(chips).spacing(5).wrap();
+
+        let stats_button = button(text(self.i18n.t("Statistics")))
+            .on_press(Message::ShowStatsDialog)
+            .padding(10)
+            .width(Length::Fill)
+            .style(DeepSpace::menu_button);
+
         let add_button = button(text(self.i18n.t("Add")))
             .on_press(Message::AddClicked)
             .padding(10)
@@ -732,9 +1143,13 @@ impl MainWindow {
 
         column![
             search,
+            sort_picker,
+            tag_filters,
             play_kill_button,
             status_text,
+            game_details,
             add_button,
+            stats_button,
             settings_button,
             kill_all_button,
         ]
@@ -743,24 +1158,23 @@ impl MainWindow {
         .into()
     }
 
-    /// Filter games based on search query and hidden state
-    fn game_matches_search(&self, game: &Game) -> bool {
-        // Check if game matches search query
-        let matches_query = if self.search_query.is_empty() {
-            true
-        } else {
-            let query = self.search_query.to_lowercase();
-            game.title.to_lowercase().contains(&query)
-        };
+    /// Search score of a game, None if the search, tag filters or hidden
+    /// state exclude it
+    fn search_score(&self, game: &Game) -> Option<u32> {
+        // Check if hidden games should be shown; a `hidden:` filter in the
+        // query overrides the setting
+        let matches_hidden =
+            !game.hidden || self.config.show_hidden || self.search.filters_hidden();
 
-        // Check if hidden games should be shown
-        let matches_hidden = if game.hidden {
-            self.config.show_hidden
-        } else {
-            true
-        };
+        // Check the tag and favorites filters
+        let matches_tags = self.tag_filter.iter().all(|tag| game.has_tag(tag));
+        let matches_favorite = !self.favorites_only || game.favorite;
+
+        if !(matches_hidden && matches_tags && matches_favorite) {
+            return None;
+        }
 
-        matches_query && matches_hidden
+        self.search.score(game)
     }
 
     /// Subscribe to events
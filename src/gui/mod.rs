@@ -5,18 +5,47 @@ pub mod add_game_dialog;
 pub mod confirmation_dialog;
 pub mod context_menu;
 pub mod file_picker;
+pub mod launch_command_dialog;
 pub mod log_viewer_dialog;
 pub mod main_window;
 pub mod proton_manager_dialog;
 pub mod settings_dialog;
+pub mod stats_dialog;
 pub mod styles;
+pub mod tag_dialog;
+pub mod wrapper_list;
 
 use add_game_dialog::AddGameDialog;
 use confirmation_dialog::ConfirmationDialog;
 use context_menu::ContextMenu;
+use launch_command_dialog::LaunchCommandDialog;
 use log_viewer_dialog::LogViewerDialog;
 use proton_manager_dialog::ProtonManagerDialog;
 use settings_dialog::SettingsDialog;
+use stats_dialog::StatsDialog;
+use tag_dialog::TagDialog;
+
+use std::fmt;
+
+/// A pick-list option shown by its translated label
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Choice<T> {
+    pub value: T,
+    label: String,
+}
+
+impl<T> Choice<T> {
+    /// Create an option for a value with its translated label
+    pub fn new(value: T, label: String) -> Self {
+        Self { value, label }
+    }
+}
+
+impl<T> fmt::Display for Choice<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.label)
+    }
+}
 
 /// Dialog state for the application
 #[derive(Debug, Clone)]
@@ -27,5 +56,8 @@ pub enum DialogState {
     Confirmation(Box<ConfirmationDialog>),
     LogViewer(Box<LogViewerDialog>),
     ProtonManager(Box<ProtonManagerDialog>),
+    Stats(Box<StatsDialog>),
+    Tags(Box<TagDialog>),
+    LaunchCommand(Box<LaunchCommandDialog>),
     ContextMenu(Box<ContextMenu>),
 }
@@ -7,7 +7,7 @@ use std::path::PathBuf;
 
 use crate::gui::styles::DeepSpace;
 use crate::locale::I18n;
-use crate::proton::proton_manager::{ProtonManager, ProtonRelease, PROTON_CONFIGS};
+use crate::proton::proton_manager::{self, ProtonManager, ProtonRelease, PROTON_CONFIGS};
 
 /// Messages for the Proton Manager
 #[derive(Debug, Clone)]
@@ -103,6 +103,9 @@ pub struct ProtonManagerDialog {
 
     /// Steam compat directory
     compat_dir: PathBuf,
+
+    /// Runner a game is missing, highlighted in the list
+    preselected: Option<String>,
 }
 
 impl ProtonManagerDialog {
@@ -122,11 +125,30 @@ impl ProtonManagerDialog {
                 progress_value: 0.0,
                 show_progress: false,
                 compat_dir,
+                preselected: None,
             },
             Task::done(ProtonManagerMessage::FetchReleases),
         )
     }
 
+    /// Create a Proton Manager dialog opened on the tab of a runner, with it highlighted
+    pub fn with_runner(runner: &str) -> (Self, Task<ProtonManagerMessage>) {
+        let (mut dialog, task) = Self::new();
+        if let Some(tab) = proton_manager::config_for_runner(runner) {
+            dialog.selected_tab = tab;
+        }
+        dialog.preselected = Some(runner.to_string());
+        (dialog, task)
+    }
+
+    /// Check if a release is the runner the dialog was opened for
+    fn is_preselected(&self, release: &ProtonVersionEntry) -> bool {
+        self.preselected.as_deref().is_some_and(|runner| {
+            runner.eq_ignore_ascii_case(&release.display_name)
+                || runner.eq_ignore_ascii_case(&release.tag_name)
+        })
+    }
+
     /// Update the dialog state
     pub fn update(&mut self, message: ProtonManagerMessage) -> Task<ProtonManagerMessage> {
         match message {
@@ -420,6 +442,7 @@ impl ProtonManagerDialog {
         _i18n: &I18n,
     ) -> Element<'a, ProtonManagerMessage> {
         let version_text = text(&release.display_name).size(14);
+        let highlighted = self.is_preselected(release);
         let size_text = text(format_size(release.size)).size(12);
 
         let button_text = if release.downloading {
@@ -432,7 +455,11 @@ impl ProtonManagerDialog {
 
         let action_button = button(text(button_text).size(12))
             .width(Length::Fixed(120.0))
-            .style(DeepSpace::button);
+            .style(if highlighted {
+                DeepSpace::primary_button
+            } else {
+                DeepSpace::button
+            });
 
         let action_button = if release.downloading {
             action_button
@@ -11,6 +11,7 @@ use std::path::PathBuf;
 use crate::config::{AppConfig, InterfaceMode};
 use crate::gui::file_picker;
 use crate::gui::styles::DeepSpace;
+use crate::gui::wrapper_list::{self, WrapperListMessage};
 use crate::locale::I18n;
 use crate::proton::proton_manager::ProtonManager;
 
@@ -47,8 +48,19 @@ pub enum SettingsMessage {
     StartBootToggled(bool),
     CloseOnLaunchToggled(bool),
     SplashDisableToggled(bool),
+    StopGracePeriodChanged(String),
+    PreventSharedPrefixToggled(bool),
     EnableLoggingToggled(bool),
 
+    // Hooks
+    PreLaunchHookChanged(String),
+    PostExitHookChanged(String),
+    HookTimeoutChanged(String),
+    HookAbortToggled(bool),
+
+    // Wrappers
+    Wrappers(WrapperListMessage),
+
     // Experimental Settings
     WaylandDriverToggled(bool),
     EnableHdrToggled(bool),
@@ -99,6 +111,12 @@ pub struct SettingsDialog {
     interface_modes: Vec<InterfaceMode>,
     runners: Vec<String>,
 
+    /// Hook timeout input in seconds
+    hook_timeout: String,
+
+    /// Stop grace period input in seconds
+    stop_grace_period: String,
+
     // Dialog state
     logging_warning_shown: bool,
 
@@ -132,6 +150,8 @@ impl SettingsDialog {
             .unwrap_or(0);
 
         Self {
+            hook_timeout: config.hook_timeout.to_string(),
+            stop_grace_period: config.stop_grace_period.to_string(),
             config,
             language_index,
             interface_mode_index,
@@ -321,6 +341,34 @@ impl SettingsDialog {
                 }
                 self.config.enable_logging = enabled;
             }
+            SettingsMessage::PreLaunchHookChanged(command) => {
+                self.config.pre_launch_hook = command;
+            }
+            SettingsMessage::PostExitHookChanged(command) => {
+                self.config.post_exit_hook = command;
+            }
+            SettingsMessage::PreventSharedPrefixToggled(enabled) => {
+                self.config.prevent_shared_prefix = enabled;
+            }
+            SettingsMessage::StopGracePeriodChanged(period) => {
+                if let Ok(seconds) = period.trim().parse() {
+                    self.config.stop_grace_period = seconds;
+                }
+                self.stop_grace_period = period;
+            }
+            SettingsMessage::HookTimeoutChanged(timeout) => {
+                // Keep the last valid value in the config
+                if let Ok(seconds) = timeout.trim().parse() {
+                    self.config.hook_timeout = seconds;
+                }
+                self.hook_timeout = timeout;
+            }
+            SettingsMessage::HookAbortToggled(enabled) => {
+                self.config.hook_abort_on_failure = enabled;
+            }
+            SettingsMessage::Wrappers(message) => {
+                wrapper_list::update(&mut self.config.wrappers, message);
+            }
             SettingsMessage::WaylandDriverToggled(enabled) => {
                 self.config.wayland_driver = enabled;
             }
@@ -366,6 +414,8 @@ impl SettingsDialog {
             }
             SettingsMessage::ResetToDefaults => {
                 self.config = AppConfig::default();
+                self.hook_timeout = self.config.hook_timeout.to_string();
+                self.stop_grace_period = self.config.stop_grace_period.to_string();
                 self.language_index = self
                     .languages
                     .iter()
@@ -405,6 +455,7 @@ impl SettingsDialog {
         let paths_section = self.view_paths_section(i18n);
         let performance_section = self.view_performance_section(i18n);
         let system_section = self.view_system_section(i18n);
+        let hooks_section = self.view_hooks_section(i18n);
         let experimental_section = self.view_experimental_section(i18n);
         let tools_section = self.view_tools_section(i18n);
         let actions_section = self.view_actions_section(i18n);
@@ -433,6 +484,8 @@ impl SettingsDialog {
                 Space::with_height(Length::Fixed(20.0)),
                 system_section,
                 Space::with_height(Length::Fixed(20.0)),
+                hooks_section,
+                Space::with_height(Length::Fixed(20.0)),
                 experimental_section,
                 Space::with_height(Length::Fixed(20.0)),
                 tools_section,
@@ -631,6 +684,64 @@ impl SettingsDialog {
             checkbox(i18n.t("Enable logging"), self.config.enable_logging)
                 .on_toggle(SettingsMessage::EnableLoggingToggled)
                 .style(DeepSpace::checkbox),
+            checkbox(
+                i18n.t("Don't launch games sharing a prefix with a running game"),
+                self.config.prevent_shared_prefix
+            )
+            .on_toggle(SettingsMessage::PreventSharedPrefixToggled)
+            .style(DeepSpace::checkbox),
+            row![
+                text(i18n.t("Seconds a stopped game gets to exit")).size(14),
+                text_input("10", &self.stop_grace_period)
+                    .on_input(SettingsMessage::StopGracePeriodChanged)
+                    .width(Length::Fixed(80.0))
+                    .style(DeepSpace::text_input),
+            ]
+            .spacing(10)
+            .align_y(iced::alignment::Vertical::Center),
+        ]
+        .spacing(5)
+        .into()
+    }
+
+    /// View global hooks section
+    fn view_hooks_section(&self, i18n: &I18n) -> Element<'_, SettingsMessage> {
+        column![
+            text(i18n.t("Hooks")).size(18),
+            Space::with_height(Length::Fixed(10.0)),
+            text(i18n.t("Commands run for every game, before and after its own hooks")).size(12),
+            text_input(
+                &i18n.t("Pre-launch command, e.g.: mount /mnt/games"),
+                &self.config.pre_launch_hook
+            )
+            .on_input(SettingsMessage::PreLaunchHookChanged)
+            .style(DeepSpace::text_input),
+            text_input(
+                &i18n.t("Post-exit command, e.g.: umount /mnt/games"),
+                &self.config.post_exit_hook
+            )
+            .on_input(SettingsMessage::PostExitHookChanged)
+            .style(DeepSpace::text_input),
+            row![
+                text(i18n.t("Timeout (seconds)")).size(14),
+                text_input("60", &self.hook_timeout)
+                    .on_input(SettingsMessage::HookTimeoutChanged)
+                    .width(Length::Fixed(80.0))
+                    .style(DeepSpace::text_input),
+            ]
+            .spacing(10)
+            .align_y(iced::alignment::Vertical::Center),
+            checkbox(
+                i18n.t("Don't launch if the pre-launch command fails"),
+                self.config.hook_abort_on_failure
+            )
+            .on_toggle(SettingsMessage::HookAbortToggled)
+            .style(DeepSpace::checkbox),
+            Space::with_height(Length::Fixed(10.0)),
+            text(i18n.t("Wrappers")).size(14),
+            text(i18n.t("Programs every game is run through, around the game's own wrappers"))
+                .size(12),
+            wrapper_list::view(&self.config.wrappers, i18n).map(SettingsMessage::Wrappers),
         ]
         .spacing(5)
         .into()
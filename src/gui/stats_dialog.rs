@@ -0,0 +1,235 @@
+// Statistics Dialog
+// Per-game and library-wide playtime statistics from session history
+
+use iced::widget::{button, column, container, horizontal_rule, row, scrollable, text, Space};
+use iced::{Alignment, Element, Length};
+use std::collections::HashMap;
+
+use crate::config::format_duration;
+use crate::config::session_history::{self, LibraryStats, PlayStats, DAY};
+use crate::config::Game;
+use crate::gui::styles::DeepSpace;
+use crate::locale::I18n;
+
+/// Messages for the Statistics dialog
+#[derive(Debug, Clone)]
+pub enum StatsMessage {
+    /// Show the details of a game
+    SelectGame(String),
+    /// Close dialog
+    Close,
+}
+
+/// Statistics of one game in the table
+#[derive(Debug, Clone)]
+struct StatsRow {
+    gameid: String,
+    title: String,
+    stats: PlayStats,
+}
+
+/// State for the Statistics dialog
+#[derive(Debug, Clone)]
+pub struct StatsDialog {
+    /// Games, most played first
+    rows: Vec<StatsRow>,
+    /// Library-wide summary
+    summary: LibraryStats,
+    /// Game whose details are shown
+    selected: Option<String>,
+    /// Reference time for the "last N days" and "last played" values
+    now: u64,
+}
+
+impl StatsDialog {
+    /// Create a new Statistics dialog from the games and their computed statistics
+    pub fn new(games: &[Game], stats: &HashMap<String, PlayStats>, selected: Option<&str>) -> Self {
+        let mut rows: Vec<StatsRow> = games
+            .iter()
+            .map(|game| StatsRow {
+                gameid: game.gameid.clone(),
+                title: game.title.clone(),
+                stats: stats.get(&game.gameid).copied().unwrap_or_default(),
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            b.stats
+                .total
+                .cmp(&a.stats.total)
+                .then_with(|| a.title.to_lowercase().cmp(&b.title.to_lowercase()))
+        });
+
+        Self {
+            summary: LibraryStats::collect(rows.iter().map(|row| &row.stats)),
+            rows,
+            selected: selected.map(str::to_string),
+            now: session_history::unix_now(),
+        }
+    }
+
+    /// Update the dialog state
+    pub fn update(&mut self, message: StatsMessage) {
+        match message {
+            StatsMessage::SelectGame(gameid) => {
+                self.selected = Some(gameid);
+            }
+            StatsMessage::Close => {
+                // Handled by parent
+            }
+        }
+    }
+
+    /// View the dialog
+    pub fn view(&self, i18n: &I18n) -> Element<'_, StatsMessage> {
+        let header = container(text(i18n.t("Statistics")).size(20))
+            .padding(10)
+            .width(Length::Fill);
+
+        let summary = self.view_summary(i18n);
+
+        let details: Element<'_, StatsMessage> = match self
+            .selected
+            .as_ref()
+            .and_then(|id| self.rows.iter().find(|row| &row.gameid == id))
+        {
+            Some(row) => column![text(&row.title).size(16), self.view_stats(&row.stats, i18n),]
+                .spacing(5)
+                .into(),
+            None => text(i18n.t("Select a game to see its statistics"))
+                .size(12)
+                .into(),
+        };
+
+        let buttons = row![
+            Space::with_width(Length::Fill),
+            button(text(i18n.t("Close")).size(14)).on_press(StatsMessage::Close),
+        ];
+
+        column![
+            header,
+            summary,
+            horizontal_rule(1),
+            details,
+            horizontal_rule(1),
+            self.view_table(i18n),
+            Space::with_height(Length::Fixed(10.0)),
+            buttons,
+        ]
+        .spacing(10)
+        .padding(20)
+        .into()
+    }
+
+    /// View the library-wide summary
+    fn view_summary(&self, i18n: &I18n) -> Element<'_, StatsMessage> {
+        column![
+            text(i18n.t("Library")).size(16),
+            self.view_stats(&self.summary.combined, i18n),
+            text(format!(
+                "{}: {} / {}",
+                i18n.t("Games played"),
+                self.summary.games_played,
+                self.rows.len()
+            ))
+            .size(12),
+        ]
+        .spacing(5)
+        .into()
+    }
+
+    /// View the figures of one set of statistics
+    fn view_stats(&self, stats: &PlayStats, i18n: &I18n) -> Element<'_, StatsMessage> {
+        let field = |label: &str, value: String| stat_field(i18n.t(label), value);
+
+        row![
+            field("Total", format_duration(stats.total)),
+            field("Last 7 days", format_duration(stats.last_7_days)),
+            field("Last 30 days", format_duration(stats.last_30_days)),
+            field("Sessions", stats.sessions.to_string()),
+            field("Average session", format_duration(stats.average_session())),
+            field(
+                "Last played",
+                format_last_played(i18n, stats.last_played, self.now)
+            ),
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    /// View the per-game table
+    fn view_table(&self, i18n: &I18n) -> Element<'_, StatsMessage> {
+        let heading = row![
+            text(i18n.t("Game")).size(12).width(Length::FillPortion(3)),
+            text(i18n.t("Total")).size(12).width(Length::FillPortion(1)),
+            text(i18n.t("Last 7 days"))
+                .size(12)
+                .width(Length::FillPortion(1)),
+            text(i18n.t("Sessions"))
+                .size(12)
+                .width(Length::FillPortion(1)),
+            text(i18n.t("Last played"))
+                .size(12)
+                .width(Length::FillPortion(1)),
+        ]
+        .spacing(10)
+        .padding([0, 8]);
+
+        let rows: Vec<Element<'_, StatsMessage>> = self
+            .rows
+            .iter()
+            .map(|entry| {
+                let content = row![
+                    text(&entry.title).size(13).width(Length::FillPortion(3)),
+                    text(format_duration(entry.stats.total))
+                        .size(13)
+                        .width(Length::FillPortion(1)),
+                    text(format_duration(entry.stats.last_7_days))
+                        .size(13)
+                        .width(Length::FillPortion(1)),
+                    text(entry.stats.sessions.to_string())
+                        .size(13)
+                        .width(Length::FillPortion(1)),
+                    text(format_last_played(i18n, entry.stats.last_played, self.now))
+                        .size(13)
+                        .width(Length::FillPortion(1)),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center);
+
+                button(content)
+                    .on_press(StatsMessage::SelectGame(entry.gameid.clone()))
+                    .width(Length::Fill)
+                    .padding(8)
+                    .style(DeepSpace::menu_button)
+                    .into()
+            })
+            .collect();
+
+        column![
+            heading,
+            scrollable(column(rows).spacing(2))
+                .width(Length::Fill)
+                .height(Length::Fixed(300.0)),
+        ]
+        .spacing(5)
+        .into()
+    }
+}
+
+/// Labelled figure in a statistics row
+fn stat_field<'a>(label: String, value: String) -> Element<'a, StatsMessage> {
+    column![text(label).size(11), text(value).size(14)]
+        .spacing(2)
+        .width(Length::FillPortion(1))
+        .into()
+}
+
+/// Describe when a game was last played relative to `now`
+pub fn format_last_played(i18n: &I18n, last_played: Option<u64>, now: u64) -> String {
+    match last_played.map(|ts| now.saturating_sub(ts) / DAY) {
+        None => i18n.t("Never"),
+        Some(0) => i18n.t("Today"),
+        Some(1) => i18n.t("Yesterday"),
+        Some(days) => format!("{} {}", days, i18n.t("days ago")),
+    }
+}
@@ -0,0 +1,260 @@
+// Tag Dialog
+// Edit the tags of a game, or apply tag changes to every game shown in the library
+
+use iced::widget::{button, checkbox, column, container, row, text, text_input, Space};
+use iced::{Alignment, Element, Length};
+
+use crate::config::{same_tag, Game};
+use crate::gui::styles::DeepSpace;
+use crate::locale::I18n;
+
+/// Messages for the Tag dialog
+#[derive(Debug, Clone)]
+pub enum TagMessage {
+    NewTagChanged(String),
+    /// Add the tag typed in the input
+    AddTag,
+    /// Add or remove an existing tag
+    ToggleTag(String),
+    ApplyToShownToggled(bool),
+    Confirm,
+    Cancel,
+}
+
+/// Tag changes to store once the dialog is confirmed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagChanges {
+    /// IDs of the games to retag
+    pub gameids: Vec<String>,
+    pub add: Vec<String>,
+    pub remove: Vec<String>,
+}
+
+/// State for the Tag dialog
+#[derive(Debug, Clone)]
+pub struct TagDialog {
+    /// Game being edited; its tags hold the edited state
+    game: Game,
+    /// Tags of the game when the dialog opened
+    original: Vec<String>,
+    /// Tags used anywhere in the library, offered as suggestions
+    known_tags: Vec<String>,
+    new_tag: String,
+    /// IDs of the games currently shown in the library
+    shown: Vec<String>,
+    /// Apply the added/removed tags to all shown games
+    apply_to_shown: bool,
+}
+
+impl TagDialog {
+    /// Create a new Tag dialog for a game
+    pub fn new(game: Game, known_tags: Vec<String>, shown: Vec<String>) -> Self {
+        Self {
+            original: game.tags.clone(),
+            game,
+            known_tags,
+            new_tag: String::new(),
+            shown,
+            apply_to_shown: false,
+        }
+    }
+
+    /// Update the dialog state
+    pub fn update(&mut self, message: TagMessage) {
+        match message {
+            TagMessage::NewTagChanged(value) => {
+                self.new_tag = value;
+            }
+            TagMessage::AddTag => {
+                self.game.add_tag(&self.new_tag);
+                self.new_tag.clear();
+            }
+            TagMessage::ToggleTag(tag) => {
+                if self.game.has_tag(&tag) {
+                    self.game.remove_tag(&tag);
+                } else {
+                    self.game.add_tag(&tag);
+                }
+            }
+            TagMessage::ApplyToShownToggled(enabled) => {
+                self.apply_to_shown = enabled;
+            }
+            TagMessage::Confirm | TagMessage::Cancel => {
+                // Handled by parent
+            }
+        }
+    }
+
+    /// Tags added and removed compared to when the dialog opened
+    pub fn changes(&self) -> TagChanges {
+        let gameids = if self.apply_to_shown && !self.shown.is_empty() {
+            self.shown.clone()
+        } else {
+            vec![self.game.gameid.clone()]
+        };
+        let contains = |tags: &[String], tag: &str| tags.iter().any(|t| same_tag(t, tag));
+
+        TagChanges {
+            gameids,
+            add: self
+                .game
+                .tags
+                .iter()
+                .filter(|tag| !contains(&self.original, tag))
+                .cloned()
+                .collect(),
+            remove: self
+                .original
+                .iter()
+                .filter(|tag| !contains(&self.game.tags, tag))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// View the dialog
+    pub fn view(&self, i18n: &I18n) -> Element<'_, TagMessage> {
+        let header = container(text(format!("{}: {}", i18n.t("Tags"), self.game.title)).size(20))
+            .padding(10)
+            .width(Length::Fill);
+
+        // Current tags; clicking one removes it
+        let current: Element<'_, TagMessage> = if self.game.tags.is_empty() {
+            text(i18n.t("No tags")).size(12).into()
+        } else {
+            iced::widget::row(self.game.tags.iter().map(|tag| {
+                tag_chip(
+                    format!("{} ✕", tag),
+                    true,
+                    TagMessage::ToggleTag(tag.clone()),
+                )
+            }))
+            .spacing(5)
+            .wrap()
+            .into()
+        };
+
+        let input = row![
+            text_input(&i18n.t("New tag"), &self.new_tag)
+                .on_input(TagMessage::NewTagChanged)
+                .on_submit(TagMessage::AddTag)
+                .style(DeepSpace::text_input),
+            button(text(i18n.t("Add")))
+                .on_press(TagMessage::AddTag)
+                .style(DeepSpace::button),
+        ]
+        .spacing(5)
+        .align_y(Alignment::Center);
+
+        // Library tags the game doesn't have yet
+        let suggestions: Vec<Element<'_, TagMessage>> = self
+            .known_tags
+            .iter()
+            .filter(|tag| !self.game.has_tag(tag))
+            .map(|tag| {
+                tag_chip(
+                    format!("+ {}", tag),
+                    false,
+                    TagMessage::ToggleTag(tag.clone()),
+                )
+            })
+            .collect();
+
+        let mut content = column![header, current, input].spacing(10);
+
+        if !suggestions.is_empty() {
+            content = content.push(iced::widget::row(suggestions).spacing(5).wrap());
+        }
+
+        if self.shown.len() > 1 {
+            content = content.push(
+                checkbox(
+                    format!(
+                        "{} ({})",
+                        i18n.t("Apply changes to all games shown"),
+                        self.shown.len()
+                    ),
+                    self.apply_to_shown,
+                )
+                .on_toggle(TagMessage::ApplyToShownToggled)
+                .style(DeepSpace::checkbox),
+            );
+        }
+
+        content
+            .push(
+                row![
+                    Space::with_width(Length::Fill),
+                    button(text(i18n.t("Cancel")).size(14)).on_press(TagMessage::Cancel),
+                    button(text(i18n.t("OK")).size(14))
+                        .on_press(TagMessage::Confirm)
+                        .style(DeepSpace::primary_button),
+                ]
+                .spacing(10),
+            )
+            .padding(20)
+            .into()
+    }
+}
+
+/// Clickable tag label; `active` chips are highlighted
+pub fn tag_chip<'a, M: Clone + 'a>(label: String, active: bool, on_press: M) -> Element<'a, M> {
+    button(text(label).size(12))
+        .on_press(on_press)
+        .padding([2, 8])
+        .style(if active {
+            DeepSpace::primary_button
+        } else {
+            DeepSpace::button
+        })
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tagged_game(id: &str, tags: &[&str]) -> Game {
+        Game {
+            gameid: id.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_changes_single_and_bulk() {
+        let game = tagged_game("a", &["coop", "rpg"]);
+        let mut dialog = TagDialog::new(
+            game,
+            vec!["coop".to_string(), "racing".to_string(), "rpg".to_string()],
+            vec!["a".to_string(), "b".to_string()],
+        );
+
+        dialog.update(TagMessage::ToggleTag("rpg".to_string()));
+        dialog.update(TagMessage::NewTagChanged(" Retro ".to_string()));
+        dialog.update(TagMessage::AddTag);
+
+        let changes = dialog.changes();
+        assert_eq!(changes.gameids, vec!["a"]);
+        assert_eq!(changes.add, vec!["Retro"]);
+        assert_eq!(changes.remove, vec!["rpg"]);
+
+        dialog.update(TagMessage::ApplyToShownToggled(true));
+        assert_eq!(dialog.changes().gameids, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_changes_ignore_non_ascii_case() {
+        let game = tagged_game("a", &["ärger"]);
+        let mut dialog = TagDialog::new(game, vec!["ärger".to_string()], Vec::new());
+
+        dialog.update(TagMessage::ToggleTag("ÄRGER".to_string()));
+        assert!(dialog.game.tags.is_empty());
+        dialog.update(TagMessage::ToggleTag("Ärger".to_string()));
+
+        let changes = dialog.changes();
+        assert!(changes.add.is_empty());
+        assert!(changes.remove.is_empty());
+    }
+}
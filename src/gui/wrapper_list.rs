@@ -0,0 +1,156 @@
+// Wrapper list editor
+// Ordered list of launch wrappers, shared by the game and settings dialogs
+
+use iced::widget::{button, column, pick_list, row, text, text_input};
+use iced::{Alignment, Element, Length};
+
+use crate::config::wrappers::{Wrapper, WrapperKind};
+use crate::gui::styles::DeepSpace;
+use crate::gui::Choice;
+use crate::locale::I18n;
+
+/// Messages for editing a wrapper list
+#[derive(Debug, Clone)]
+pub enum WrapperListMessage {
+    Add,
+    Remove(usize),
+    /// Move a wrapper one step outwards
+    MoveUp(usize),
+    /// Move a wrapper one step inwards
+    MoveDown(usize),
+    KindChanged(usize, WrapperKind),
+    CommandChanged(usize, String),
+    ArgsChanged(usize, String),
+}
+
+/// Apply an edit to a wrapper list
+pub fn update(wrappers: &mut Vec<Wrapper>, message: WrapperListMessage) {
+    match message {
+        WrapperListMessage::Add => wrappers.push(Wrapper::default()),
+        WrapperListMessage::Remove(index) => {
+            if index < wrappers.len() {
+                wrappers.remove(index);
+            }
+        }
+        WrapperListMessage::MoveUp(index) => {
+            if index > 0 && index < wrappers.len() {
+                wrappers.swap(index - 1, index);
+            }
+        }
+        WrapperListMessage::MoveDown(index) => {
+            if index + 1 < wrappers.len() {
+                wrappers.swap(index, index + 1);
+            }
+        }
+        WrapperListMessage::KindChanged(index, kind) => {
+            if let Some(wrapper) = wrappers.get_mut(index) {
+                wrapper.kind = kind;
+            }
+        }
+        WrapperListMessage::CommandChanged(index, command) => {
+            if let Some(wrapper) = wrappers.get_mut(index) {
+                wrapper.command = command;
+            }
+        }
+        WrapperListMessage::ArgsChanged(index, args) => {
+            if let Some(wrapper) = wrappers.get_mut(index) {
+                wrapper.args = args;
+            }
+        }
+    }
+}
+
+/// View a wrapper list, outermost wrapper first
+pub fn view<'a>(wrappers: &'a [Wrapper], i18n: &I18n) -> Element<'a, WrapperListMessage> {
+    let mut content = column![].spacing(5);
+    let kinds: Vec<Choice<WrapperKind>> = WrapperKind::ALL
+        .iter()
+        .map(|&kind| Choice::new(kind, i18n.t(kind.label())))
+        .collect();
+
+    for (index, wrapper) in wrappers.iter().enumerate() {
+        let mut line = row![pick_list(
+            kinds.clone(),
+            kinds
+                .iter()
+                .find(|kind| kind.value == wrapper.kind)
+                .cloned(),
+            move |kind: Choice<WrapperKind>| WrapperListMessage::KindChanged(index, kind.value)
+        )
+        .width(Length::Fixed(170.0))
+        .style(DeepSpace::pick_list)
+        .menu_style(DeepSpace::menu)]
+        .spacing(5)
+        .align_y(Alignment::Center);
+
+        if wrapper.kind == WrapperKind::Custom {
+            line = line.push(
+                text_input(&i18n.t("Program"), &wrapper.command)
+                    .on_input(move |c| WrapperListMessage::CommandChanged(index, c))
+                    .width(Length::FillPortion(1))
+                    .style(DeepSpace::text_input),
+            );
+        }
+
+        line = line
+            .push(
+                text_input(&i18n.t("Arguments"), &wrapper.args)
+                    .on_input(move |a| WrapperListMessage::ArgsChanged(index, a))
+                    .width(Length::FillPortion(2))
+                    .style(DeepSpace::text_input),
+            )
+            .push(
+                button(text("↑"))
+                    .on_press(WrapperListMessage::MoveUp(index))
+                    .style(DeepSpace::button),
+            )
+            .push(
+                button(text("↓"))
+                    .on_press(WrapperListMessage::MoveDown(index))
+                    .style(DeepSpace::button),
+            )
+            .push(
+                button(text("-"))
+                    .on_press(WrapperListMessage::Remove(index))
+                    .width(Length::Fixed(50.0))
+                    .style(DeepSpace::button),
+            );
+
+        content = content.push(line);
+    }
+
+    content
+        .push(
+            row![button(text(i18n.t("Add wrapper")))
+                .on_press(WrapperListMessage::Add)
+                .style(DeepSpace::button)]
+            .padding(5),
+        )
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reorder_and_edit() {
+        let mut wrappers = vec![Wrapper::new(WrapperKind::PrimeRun)];
+        update(&mut wrappers, WrapperListMessage::Add);
+        update(
+            &mut wrappers,
+            WrapperListMessage::CommandChanged(1, "taskset".to_string()),
+        );
+        update(&mut wrappers, WrapperListMessage::MoveUp(1));
+        assert_eq!(wrappers[0].command, "taskset");
+        assert_eq!(wrappers[1].kind, WrapperKind::PrimeRun);
+
+        // Out of range moves are ignored
+        update(&mut wrappers, WrapperListMessage::MoveDown(1));
+        update(&mut wrappers, WrapperListMessage::MoveUp(0));
+        assert_eq!(wrappers[1].kind, WrapperKind::PrimeRun);
+
+        update(&mut wrappers, WrapperListMessage::Remove(0));
+        assert_eq!(wrappers, vec![Wrapper::new(WrapperKind::PrimeRun)]);
+    }
+}
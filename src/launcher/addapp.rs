@@ -0,0 +1,221 @@
+// Additional application
+// Starts a companion Windows app (trainer, mod loader...) in the game's prefix
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use crate::config::Game;
+use crate::proton::runner_resolver;
+
+/// Check if the game starts an additional application
+pub fn is_enabled(game: &Game) -> bool {
+    game.addapp_checkbox
+        && !game.addapp.trim().is_empty()
+        && !runner_resolver::is_native(&game.runner)
+}
+
+/// Batch file started instead of the game, inside the prefix
+pub fn batch_path(game: &Game) -> PathBuf {
+    game.prefix
+        .join("drive_c")
+        .join(format!("faugus-addapp-{}.bat", game.gameid))
+}
+
+/// Path as seen by Wine, through the Z: drive
+fn wine_path(path: &Path) -> String {
+    format!("Z:{}", path.to_string_lossy().replace('/', "\\"))
+}
+
+/// Quote an argument the way Windows programs split their command line
+fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.to_string();
+    }
+
+    let mut quoted = String::from("\"");
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                // Backslashes before a quote are escaped, as is the quote
+                quoted.push_str(&"\\".repeat(backslashes * 2 + 1));
+                quoted.push('"');
+                backslashes = 0;
+            }
+            _ => {
+                quoted.push_str(&"\\".repeat(backslashes));
+                quoted.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    // Backslashes before the closing quote are escaped
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+/// Escape a command line so cmd passes it on instead of interpreting it
+///
+/// `%` is doubled everywhere in a batch file; `& | < > ^ ( )` only need
+/// `^` outside of what cmd sees as quotes.
+fn cmd_escape(line: &str) -> String {
+    let mut escaped = String::new();
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            '%' => escaped.push('%'),
+            '&' | '|' | '<' | '>' | '^' | '(' | ')' if !quoted => escaped.push('^'),
+            _ => {}
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// `start` line for a program, run from its own directory
+fn start_line(program: &Path, wait: bool, args: &[String]) -> String {
+    let mut line = String::from("start \"\"");
+    if let Some(dir) = program.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        line.push_str(&format!(" /D \"{}\"", wine_path(dir)));
+    }
+    if wait {
+        line.push_str(" /wait");
+    }
+    line.push_str(&format!(" \"{}\"", wine_path(program)));
+    for arg in args {
+        line.push(' ');
+        line.push_str(&quote_arg(arg));
+    }
+    cmd_escape(&line)
+}
+
+/// Batch file content: start the app, then the game
+///
+/// The batch waits for the game so the launch lasts as long as the game does.
+pub fn batch_content(game: &Game, args: &[String]) -> String {
+    let lines = [
+        "@echo off".to_string(),
+        start_line(Path::new(game.addapp.trim()), false, &[]),
+        start_line(&game.path, true, args),
+    ];
+    let mut content = lines.join("\r\n");
+    content.push_str("\r\n");
+    content
+}
+
+/// Write the batch file of a launch
+pub fn write_batch(path: &Path, content: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory: {:?}", dir))?;
+    }
+    std::fs::write(path, content).with_context(|| format!("Failed to write batch file: {:?}", path))
+}
+
+/// Remove the batch file of a finished launch
+pub fn remove_batch(path: &Path) {
+    match std::fs::remove_file(path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => warn!("Failed to remove batch file {:?}: {}", path, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addapp_game() -> Game {
+        Game {
+            gameid: "addapp-game".to_string(),
+            title: "Addapp Game".to_string(),
+            path: PathBuf::from("/games/My Game/game.exe"),
+            prefix: PathBuf::from("/prefixes/game"),
+            addapp_checkbox: true,
+            addapp: "/tools/trainer.exe".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_batch_content() {
+        let game = addapp_game();
+        let content = batch_content(
+            &game,
+            &["-windowed".to_string(), "C:\\My Games\\x.ini".to_string()],
+        );
+
+        assert_eq!(
+            content,
+            "@echo off\r\n\
+             start \"\" /D \"Z:\\tools\" \"Z:\\tools\\trainer.exe\"\r\n\
+             start \"\" /D \"Z:\\games\\My Game\" /wait \"Z:\\games\\My Game\\game.exe\" -windowed \"C:\\My Games\\x.ini\"\r\n"
+        );
+        assert_eq!(
+            batch_path(&game),
+            PathBuf::from("/prefixes/game/drive_c/faugus-addapp-addapp-game.bat")
+        );
+    }
+
+    #[test]
+    fn test_batch_arguments_are_quoted() {
+        let mut game = addapp_game();
+        game.path = PathBuf::from("/games/100% Fun/game.exe");
+        let args = [
+            "-config",
+            "C:\\My Games\\x.ini",
+            "a&b|c",
+            "50%",
+            "say \"hi\" & bye",
+            "",
+            "dir\\",
+            "dir with space\\",
+        ]
+        .map(String::from);
+
+        // cmd sees the `&` between escaped quotes as quoted and leaves it alone
+        let content = batch_content(&game, &args);
+        let game_line = content.lines().nth(2).expect("game line");
+        assert_eq!(
+            game_line,
+            "start \"\" /D \"Z:\\games\\100%% Fun\" /wait \"Z:\\games\\100%% Fun\\game.exe\" \
+             -config \"C:\\My Games\\x.ini\" a^&b^|c 50%% \"say \\\"hi\\\" & bye\" \"\" dir\\ \
+             \"dir with space\\\\\""
+        );
+    }
+
+    #[test]
+    fn test_enabled() {
+        let mut game = addapp_game();
+        assert!(is_enabled(&game));
+
+        game.runner = runner_resolver::LINUX_NATIVE.to_string();
+        assert!(!is_enabled(&game));
+
+        game = addapp_game();
+        game.addapp_checkbox = false;
+        assert!(!is_enabled(&game));
+    }
+
+    #[test]
+    fn test_write_and_remove_batch() {
+        let dir = std::env::temp_dir().join(format!("faugus-addapp-test-{}", std::process::id()));
+        let mut game = addapp_game();
+        game.prefix = dir.clone();
+
+        let path = batch_path(&game);
+        write_batch(&path, &batch_content(&game, &[])).expect("batch file should be written");
+        assert!(path.starts_with(dir.join("drive_c")));
+        assert!(path.exists());
+
+        remove_batch(&path);
+        assert!(!path.exists());
+        // Removing twice is fine
+        remove_batch(&path);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -3,24 +3,296 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{Duration, Instant};
 use sysinfo::{Pid, System};
-use tokio::process::Command as AsyncCommand;
-use tracing::{debug, info, warn};
+use thiserror::Error;
+use tokio::process::{Child, Command as AsyncCommand};
+use tracing::{debug, error, info, warn};
 
 use crate::config::app_config::AppConfig;
 use crate::config::envar;
 use crate::config::paths::Paths;
-use crate::config::Game;
-use crate::proton::runner_resolver;
+use crate::config::session_history::{self, SessionRecord};
+use crate::config::wrappers::{Wrapper, WrapperKind};
+use crate::config::{Game, OverrideField};
+use crate::launcher::addapp;
+use crate::launcher::game_logs;
+use crate::launcher::hooks::{self, HookStage};
+use crate::launcher::launch_args::{self, LaunchOptions};
+use crate::launcher::launch_plan::LaunchPlan;
+use crate::launcher::process_tree::{self, ProcessInfo};
+use crate::launcher::running_store::RunningStore;
+use crate::launcher::wrappers;
+use crate::proton::proton_manager;
+use crate::proton::runner_resolver::{self, RunnerError};
+
+/// How often a stopping game is checked for exit
+const STOP_POLL: Duration = Duration::from_millis(250);
+
+/// How a game was stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopOutcome {
+    /// All of its processes exited within the grace period
+    Exited,
+    /// This many processes were still running and had to be killed
+    Killed(usize),
+}
+
+impl std::fmt::Display for StopOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StopOutcome::Exited => write!(f, "Stopped"),
+            StopOutcome::Killed(count) => {
+                write!(f, "Stopped, {} processes had to be killed", count)
+            }
+        }
+    }
+}
 
 /// Process information for running games
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct GameProcess {
     pub game_title: String,
+    /// The game's own process, or the launcher process until it is found
     pub main_pid: u32,
+    /// Launcher process started for the game (umu-run or its outermost wrapper)
     pub umu_pid: Option<u32>,
+    /// ID of the launched game, used to account playtime
+    #[serde(default)]
+    pub gameid: String,
+    /// Session start, seconds since the Unix epoch
+    #[serde(default)]
+    pub started_at: u64,
+    /// Batch file starting the additional application, removed on exit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub addapp_bat: Option<PathBuf>,
+    /// Session log receiving the output of umu-run and the game
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_file: Option<PathBuf>,
+    /// Executable of the game, used to find its process
+    #[serde(default)]
+    pub game_path: PathBuf,
+    /// Wine prefix of the game, None for native games
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<PathBuf>,
+    /// Launcher instance following the game (the GUI or faugus-run)
+    #[serde(default)]
+    pub owner_pid: u32,
+    /// Reserved while the launch is prepared, before the game has a PID
+    #[serde(default)]
+    pub launching: bool,
+}
+
+/// Launch refused because of a game that is already running
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum LaunchConflict {
+    #[error("{0} is already running")]
+    AlreadyRunning(String),
+
+    #[error("{0} is running in the same prefix")]
+    PrefixInUse(String),
+}
+
+/// Where UMU-Launcher is downloaded from
+pub const UMU_LAUNCHER_URL: &str = "https://github.com/Open-Wine-Components/umu-launcher";
+
+/// Why a game could not be launched
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum LaunchError {
+    #[error("umu-run not found. Please install UMU-Launcher.")]
+    UmuRunMissing,
+
+    #[error("Runner '{0}' is not installed")]
+    RunnerNotInstalled(String),
+
+    #[error("Executable not found: {}", .0.display())]
+    ExecutableMissing(PathBuf),
+
+    #[error("Prefix is not writable: {}", .0.display())]
+    PrefixNotWritable(PathBuf),
+
+    /// A wrapper whose program isn't installed; `global` if set in Settings
+    #[error("{name} wrapper not found")]
+    WrapperMissing { name: String, global: bool },
+
+    #[error("{0}")]
+    Other(String),
+}
+
+/// What the user can do about a launch error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Remedy {
+    /// Open the UMU-Launcher project page
+    InstallUmu,
+    /// Open the Proton Manager on the runner
+    InstallRunner(String),
+    /// Pick the game's executable again
+    RelinkExecutable,
+    /// Open the game in the Edit dialog
+    EditGame,
+    /// Open the Settings dialog
+    OpenSettings,
+}
+
+impl LaunchError {
+    /// Typed cause of a failed launch, or its message when there is none
+    pub fn from_error(error: &anyhow::Error) -> Self {
+        for cause in error.chain() {
+            if let Some(e) = cause.downcast_ref::<LaunchError>() {
+                return e.clone();
+            }
+            if let Some(RunnerError::NotInstalled { name }) = cause.downcast_ref::<RunnerError>() {
+                return LaunchError::RunnerNotInstalled(name.clone());
+            }
+        }
+        LaunchError::Other(format!("{:#}", error))
+    }
+
+    /// Action that fixes the error, if there is one
+    pub fn remedy(&self) -> Option<Remedy> {
+        match self {
+            LaunchError::UmuRunMissing => Some(Remedy::InstallUmu),
+            LaunchError::RunnerNotInstalled(name) => {
+                // Runners the Proton Manager doesn't offer are changed in the game
+                Some(match proton_manager::config_for_runner(name) {
+                    Some(_) => Remedy::InstallRunner(name.clone()),
+                    None => Remedy::EditGame,
+                })
+            }
+            LaunchError::ExecutableMissing(_) => Some(Remedy::RelinkExecutable),
+            LaunchError::PrefixNotWritable(_) => Some(Remedy::EditGame),
+            LaunchError::WrapperMissing { global: true, .. } => Some(Remedy::OpenSettings),
+            LaunchError::WrapperMissing { global: false, .. } => Some(Remedy::EditGame),
+            LaunchError::Other(_) => None,
+        }
+    }
+}
+
+impl GameProcess {
+    /// Placeholder saved while the launch of a game is prepared
+    fn reservation(game: &Game) -> Self {
+        Self {
+            game_title: game.title.clone(),
+            main_pid: 0,
+            umu_pid: None,
+            gameid: game.gameid.clone(),
+            started_at: 0,
+            addapp_bat: None,
+            log_file: None,
+            game_path: game.path.clone(),
+            prefix: (!runner_resolver::is_native(&game.runner)).then(|| game.prefix.clone()),
+            owner_pid: std::process::id(),
+            launching: true,
+        }
+    }
+
+    /// Check if a saved process still runs, or is still being launched
+    pub fn is_live(&mut self, processes: &[ProcessInfo]) -> bool {
+        if self.launching {
+            // Held until the launcher instance starts the game or gives up
+            return processes
+                .iter()
+                .any(|process| process.pid == self.owner_pid);
+        }
+        self.adopt(processes)
+    }
+
+    /// Roots of the game's process trees, the game and its launcher process
+    ///
+    /// PIDs get reused, so a root is only kept while its process still looks
+    /// like the game or its launcher.
+    pub fn stop_roots(&self, processes: &[ProcessInfo]) -> Vec<u32> {
+        let prefix = self.prefix.as_deref();
+        let mut roots = Vec::new();
+        for pid in [Some(self.main_pid), self.umu_pid].into_iter().flatten() {
+            let owned = processes
+                .iter()
+                .find(|process| process.pid == pid)
+                .is_some_and(|process| {
+                    process_tree::is_game_process(process, &self.game_path, prefix)
+                        || process_tree::is_launcher_of(process, &self.game_path)
+                });
+            if owned && !roots.contains(&pid) {
+                roots.push(pid);
+            }
+        }
+        roots
+    }
+
+    /// Check if the game's own process has been found
+    pub fn game_found(&self) -> bool {
+        self.umu_pid != Some(self.main_pid)
+    }
+
+    /// Follow the game in a process snapshot; false once it has ended
+    ///
+    /// The game is looked up again when its process goes away, since some
+    /// games restart themselves. Until it is found, the launcher process
+    /// stands in for it.
+    pub fn refresh(&mut self, processes: &[ProcessInfo]) -> bool {
+        let alive = |pid: u32| processes.iter().any(|process| process.pid == pid);
+        if self.game_found() && alive(self.main_pid) {
+            return true;
+        }
+
+        if !self.game_path.as_os_str().is_empty() {
+            if let Some(pid) = process_tree::find_game_pid(
+                processes,
+                &self.game_path,
+                self.prefix.as_deref(),
+                self.umu_pid,
+            ) {
+                if pid != self.main_pid {
+                    info!("Found process of {}: {}", self.game_title, pid);
+                }
+                self.main_pid = pid;
+                return true;
+            }
+        }
+
+        // Still starting while the launcher runs; a game that was found has ended
+        !self.game_found() && alive(self.main_pid)
+    }
+
+    /// Check a saved process against a snapshot after the launcher restarted
+    ///
+    /// PIDs get reused, so the process must still look like the game or its
+    /// launcher. A game that restarted under another PID is found again.
+    pub fn adopt(&mut self, processes: &[ProcessInfo]) -> bool {
+        if self.game_path.as_os_str().is_empty() {
+            // Saved before the game's executable was recorded
+            return false;
+        }
+
+        let owned = processes
+            .iter()
+            .find(|process| process.pid == self.main_pid)
+            .is_some_and(|process| {
+                if self.game_found() {
+                    process_tree::is_game_process(process, &self.game_path, self.prefix.as_deref())
+                } else {
+                    process_tree::is_launcher_of(process, &self.game_path)
+                }
+            });
+        if owned {
+            return true;
+        }
+
+        match process_tree::find_game_pid(processes, &self.game_path, self.prefix.as_deref(), None)
+        {
+            Some(pid) => {
+                self.main_pid = pid;
+                // The launcher process is gone or no longer ours
+                self.umu_pid = None;
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 /// Game launcher
@@ -29,62 +301,244 @@ pub struct GameLauncher;
 impl GameLauncher {
     /// Launch a game
     pub async fn launch(game: &Game) -> Result<GameProcess> {
+        let (process, _child) = Self::spawn(game).await?;
+        Ok(process)
+    }
+
+    /// Launch a game and keep the child handle, for callers that wait on it
+    ///
+    /// The game is saved in running_games.json from the moment the launch is
+    /// accepted, so other launcher instances refuse to start it meanwhile.
+    pub async fn spawn(game: &Game) -> Result<(GameProcess, Child)> {
         info!("Launching game: {}", game.title);
 
         // Ensure required directories exist
         Self::ensure_directories(game)?;
 
-        // Build the command
-        let umu_run = Self::get_umu_run()?;
+        let app_config = Self::load_app_config();
+        Self::reserve_launch(game, &app_config)?;
 
-        // Check if GameMode is enabled - wrap with gamemoderun prefix if so
-        let mut cmd = if game.gamemode {
-            if let Some(gamemoderun) = Paths::gamemoderun() {
-                info!("Enabling GameMode as command prefix");
-                let mut cmd = AsyncCommand::new(&gamemoderun);
-                cmd.arg(&umu_run);
-                cmd
-            } else {
-                // GameMode requested but not installed - proceed without it
-                info!("GameMode requested but not found, proceeding without it");
-                AsyncCommand::new(&umu_run)
+        match Self::start(game, &app_config).await {
+            Ok((process, child)) => {
+                // Replaces the reservation
+                if let Err(e) = Self::save_process(&process) {
+                    error!("Failed to save process info: {:#}", e);
+                }
+                Ok((process, child))
             }
-        } else {
-            AsyncCommand::new(&umu_run)
-        };
+            Err(e) => {
+                if let Err(e) = Self::remove_process(&game.gameid) {
+                    warn!("Failed to release launch of {}: {:#}", game.title, e);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Run the pre-launch hooks and start the game's command
+    async fn start(game: &Game, app_config: &AppConfig) -> Result<(GameProcess, Child)> {
+        let plan = Self::plan(game, app_config)?;
 
-        // Set environment variables
-        Self::setup_environment(&mut cmd, game)?;
+        // Pre-launch hooks may block for a while, keep them off the async runtime
+        {
+            let game = game.clone();
+            let app_config = app_config.clone();
+            let env = plan.env.clone();
+            tokio::task::spawn_blocking(move || {
+                hooks::run_hooks(HookStage::PreLaunch, &game, &app_config, &env)
+            })
+            .await
+            .context("Pre-launch hook task failed")??;
+        }
+
+        match Self::spawn_plan(game, app_config, &plan) {
+            Ok(started) => Ok(started),
+            Err(e) => {
+                // Undo what the pre-launch hooks set up, as after a normal exit
+                let game = game.clone();
+                let app_config = app_config.clone();
+                let env = plan.env;
+                let undone = tokio::task::spawn_blocking(move || {
+                    hooks::run_hooks(HookStage::PostExit, &game, &app_config, &env)
+                })
+                .await;
+                match undone {
+                    Ok(Ok(())) => {}
+                    Ok(Err(hook_error)) => error!("Post-exit hooks failed: {:#}", hook_error),
+                    Err(join_error) => error!("Post-exit hook task failed: {}", join_error),
+                }
+                Err(e)
+            }
+        }
+    }
 
-        // Set up arguments
-        let args = Self::build_arguments(game);
-        cmd.args(&args);
+    /// Start the command of a launch plan
+    fn spawn_plan(
+        game: &Game,
+        app_config: &AppConfig,
+        plan: &LaunchPlan,
+    ) -> Result<(GameProcess, Child)> {
+        // The additional application is started through a batch file in the prefix
+        let addapp_bat = match &plan.batch_file {
+            Some((path, content)) => {
+                addapp::write_batch(path, content)?;
+                info!("Starting {} alongside {}", game.addapp.trim(), game.title);
+                Some(path.clone())
+            }
+            None => None,
+        };
+        let mut cmd = AsyncCommand::new(&plan.program);
+        cmd.args(&plan.args).envs(&plan.env);
+
+        // Output of umu-run and the game goes to a session log
+        let started_at = session_history::unix_now();
+        let mut log_file = None;
+        match game_logs::create_session_log(&game.gameid, started_at, app_config.log_sessions) {
+            Ok((path, mut file)) => {
+                let _ = writeln!(
+                    file,
+                    "# {} ({})\n# {}\n",
+                    game.title,
+                    game.gameid,
+                    plan.argv().join(" ")
+                );
+                match file.try_clone() {
+                    Ok(stdout) => {
+                        cmd.stdout(stdout).stderr(file);
+                        info!("Logging {} to {:?}", game.title, path);
+                        log_file = Some(path);
+                    }
+                    Err(e) => warn!("Failed to open session log {:?}: {}", path, e),
+                }
+            }
+            Err(e) => warn!("Failed to create session log for {}: {:#}", game.title, e),
+        }
 
         // Spawn the process
-        let child = cmd
-            .spawn()
-            .with_context(|| format!("Failed to launch game: {}", game.title))?;
+        let child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                if let Some(path) = &addapp_bat {
+                    addapp::remove_batch(path);
+                }
+                return Err(e).with_context(|| format!("Failed to launch game: {}", game.title));
+            }
+        };
 
         let pid = child.id().unwrap_or(0);
         info!("Game {} launched with PID: {}", game.title, pid);
 
-        Ok(GameProcess {
+        let process = GameProcess {
             game_title: game.title.clone(),
             main_pid: pid,
-            umu_pid: None,
+            umu_pid: Some(pid),
+            gameid: game.gameid.clone(),
+            started_at,
+            addapp_bat,
+            log_file,
+            game_path: game.path.clone(),
+            prefix: (!runner_resolver::is_native(&game.runner)).then(|| game.prefix.clone()),
+            owner_pid: std::process::id(),
+            launching: false,
+        };
+        Ok((process, child))
+    }
+
+    /// Launch plan of a game with the current settings
+    pub fn launch_plan(game: &Game) -> Result<LaunchPlan> {
+        Self::plan(game, &Self::load_app_config())
+    }
+
+    /// Work out what launching a game runs, without starting anything
+    pub fn plan(game: &Game, app_config: &AppConfig) -> Result<LaunchPlan> {
+        let umu_run = Self::get_umu_run()?;
+        let env = Self::resolve_environment(game, app_config)?;
+        if !game.path.exists() {
+            return Err(LaunchError::ExecutableMissing(game.path.clone()).into());
+        }
+
+        let wrappers = installed_wrappers(game, app_config, Wrapper::find_program)?;
+
+        // Wrappers, umu-run and the game command
+        let mut argv = Self::command_line(game, app_config, &umu_run)?.into_iter();
+        let program = argv.next().context("Empty launch command")?;
+
+        let batch_file = if addapp::is_enabled(game) {
+            let content = addapp::batch_content(game, &Self::launch_options(game)?.suffix);
+            Some((addapp::batch_path(game), content))
+        } else {
+            None
+        };
+
+        // The game inherits the launcher's working directory
+        let working_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/"));
+
+        Ok(LaunchPlan {
+            title: game.title.clone(),
+            gameid: game.gameid.clone(),
+            program,
+            args: argv.collect(),
+            env,
+            working_dir,
+            wrappers,
+            batch_file,
         })
     }
 
+    /// Refuse a launch that would run a game twice, or reserve the game
+    ///
+    /// With `prevent_shared_prefix`, games sharing the prefix of a running
+    /// game are refused as well. Saved processes that no longer run are
+    /// ignored. Other launcher instances save their games in
+    /// running_games.json; checking and reserving under one lock keeps two
+    /// of them from starting the same game.
+    fn reserve_launch(game: &Game, app_config: &AppConfig) -> Result<()> {
+        RunningStore::open()
+            .update(|saved| {
+                // Taken under the lock to see launchers that reserved meanwhile
+                let processes = process_tree::snapshot();
+                find_conflict(game, app_config, saved, &processes)?;
+                saved.retain(|p| p.gameid != game.gameid);
+                saved.push(GameProcess::reservation(game));
+                Ok::<_, LaunchConflict>(())
+            })?
+            .map_err(Into::into)
+    }
+
+    /// Account the session of a process that has ended
+    pub fn finish_session(process: &GameProcess) {
+        if let Some(path) = &process.addapp_bat {
+            addapp::remove_batch(path);
+        }
+
+        if process.gameid.is_empty() || process.started_at == 0 {
+            return;
+        }
+
+        let record = SessionRecord::ending_now(process.started_at);
+        if let Err(e) = session_history::record_session(&process.gameid, &record) {
+            error!(
+                "Failed to record playtime for {}: {:#}",
+                process.game_title, e
+            );
+        }
+    }
+
     /// Ensure required directories exist
     fn ensure_directories(game: &Game) -> Result<()> {
-        // Create prefix if it doesn't exist
-        if !game.prefix.exists() {
-            std::fs::create_dir_all(&game.prefix)
-                .with_context(|| format!("Failed to create prefix: {:?}", game.prefix))?;
+        // Create prefix if it doesn't exist; native games don't use one
+        if !runner_resolver::is_native(&game.runner) {
+            if let Err(e) = std::fs::create_dir_all(&game.prefix) {
+                warn!("Failed to create prefix {:?}: {}", game.prefix, e);
+                return Err(LaunchError::PrefixNotWritable(game.prefix.clone()).into());
+            }
+            if !is_writable(&game.prefix) {
+                return Err(LaunchError::PrefixNotWritable(game.prefix.clone()).into());
+            }
         }
 
-        // Create logs directory
-        let logs_dir = Paths::logs_dir();
+        // Create the game's logs directory, also used for PROTON_LOG_DIR
+        let logs_dir = game_logs::game_logs_dir(&game.gameid);
         if !logs_dir.exists() {
             std::fs::create_dir_all(&logs_dir)
                 .with_context(|| format!("Failed to create logs directory: {:?}", logs_dir))?;
@@ -103,77 +557,147 @@ impl GameLauncher {
                 return Ok(path);
             }
 
-            return Err(anyhow::anyhow!(
-                "umu-run not found. Please install UMU-Launcher."
-            ));
+            return Err(LaunchError::UmuRunMissing.into());
         }
 
         Ok(umu_run)
     }
 
-    /// Set up environment variables for the game
-    fn setup_environment(cmd: &mut tokio::process::Command, game: &Game) -> Result<()> {
-        // Load envar.txt first (global env vars, overridden by game-specific later)
+    /// Load config.ini using structured AppConfig
+    fn load_app_config() -> AppConfig {
+        match AppConfig::load() {
+            Ok(config) => config,
+            Err(e) => {
+                warn!("Failed to load config.ini, using defaults: {}", e);
+                AppConfig::default()
+            }
+        }
+    }
+
+    /// Environment variables for launching the game, with its runner resolved
+    fn resolve_environment(
+        game: &Game,
+        app_config: &AppConfig,
+    ) -> Result<BTreeMap<String, String>> {
+        // Proton runner
+        runner_resolver::validate_runner(&game.runner)?;
+        let runner = runner_resolver::resolve_runner(&game.runner)?;
+
         let global_env_vars = envar::load_envar_txt();
+        Ok(Self::build_environment(
+            game,
+            app_config,
+            &global_env_vars,
+            &runner,
+        ))
+    }
+
+    /// Run the post-exit hooks of a game; blocks until they finish
+    pub fn run_post_exit_hooks(gameid: &str) {
+        if gameid.is_empty() {
+            return;
+        }
+
+        // Hooks are read from the library, the game may have been edited while running
+        let game = match Game::load_all() {
+            Ok(games) => match games.into_iter().find(|g| g.gameid == gameid) {
+                Some(game) => game,
+                None => return,
+            },
+            Err(e) => {
+                error!("Failed to load games for post-exit hooks: {:#}", e);
+                return;
+            }
+        };
+
+        let app_config = Self::load_app_config();
+        if hooks::hooks_for(HookStage::PostExit, &game, &app_config).is_empty() {
+            return;
+        }
+
+        let env = Self::resolve_environment(&game, &app_config).unwrap_or_else(|e| {
+            warn!("Failed to resolve runner for post-exit hooks: {:#}", e);
+            Self::build_environment(&game, &app_config, &envar::load_envar_txt(), "")
+        });
+        if let Err(e) = hooks::run_hooks(HookStage::PostExit, &game, &app_config, &env) {
+            error!("Post-exit hooks of {} failed: {:#}", game.title, e);
+        }
+    }
+
+    /// Build the environment for a game launch
+    ///
+    /// Precedence, lowest to highest:
+    /// 1. envar.txt (global user variables)
+    /// 2. launcher-managed variables from the game entry and AppConfig toggles
+    /// 3. leading `VAR=value` assignments of the launch arguments
+    /// 4. the game's own `env` map
+    pub fn build_environment(
+        game: &Game,
+        app_config: &AppConfig,
+        global_env_vars: &HashMap<String, String>,
+        protonpath: &str,
+    ) -> BTreeMap<String, String> {
+        let mut env = BTreeMap::new();
+        let mut set = |key: &str, value: &str| {
+            env.insert(key.to_string(), value.to_string());
+        };
+
+        // Load envar.txt first (global env vars, overridden by everything below)
         if !global_env_vars.is_empty() {
             debug!(
                 "Applying {} environment variables from envar.txt",
                 global_env_vars.len()
             );
-            for (key, value) in &global_env_vars {
-                cmd.env(key, value);
+            for (key, value) in global_env_vars {
+                set(key, value);
             }
         }
 
-        // Wine prefix (game-specific, overrides envar.txt if set there)
-        cmd.env("WINEPREFIX", &game.prefix);
-
-        // Proton runner
-        runner_resolver::validate_runner(&game.runner)?;
-        let runner = runner_resolver::resolve_runner(&game.runner)?;
+        if runner_resolver::is_native(&game.runner) {
+            // Run the executable directly in the Steam Runtime, without Proton
+            set("UMU_NO_PROTON", "1");
+        } else {
+            // Wine prefix (game-specific, overrides envar.txt if set there)
+            set("WINEPREFIX", &game.prefix.to_string_lossy());
+        }
 
-        if !runner.is_empty() {
-            cmd.env("PROTONPATH", &runner);
+        if !protonpath.is_empty() {
+            set("PROTONPATH", protonpath);
         }
 
         // Game ID for UMU
-        cmd.env("GAMEID", &game.gameid);
+        set("GAMEID", &game.gameid);
 
-        // MangoHud
-        if game.mangohud {
+        // MangoHud; inside gamescope the overlay comes from --mangoapp instead
+        if game.mangohud && !Self::uses_gamescope(game, app_config) {
             if let Some(_mangohud) = Paths::mangohud() {
                 info!("Enabling MangoHud");
-                cmd.env("MANGOHUD", "1");
+                set("MANGOHUD", "1");
             }
         }
 
         // Disable hidraw
         if game.disable_hidraw {
-            cmd.env("WINE_DISABLE_HIDRAW", "1");
+            set("WINE_DISABLE_HIDRAW", "1");
         }
 
-        // Load config.ini using structured AppConfig
-        let app_config = match AppConfig::load() {
-            Ok(config) => config,
-            Err(e) => {
-                warn!("Failed to load config.ini, using defaults: {}", e);
-                AppConfig::default()
-            }
-        };
-
+        // AppConfig toggles, resolved against the game's overrides
         // Wayland driver
-        if app_config.wayland_driver {
-            cmd.env("PROTON_ENABLE_WAYLAND", "1");
+        if game
+            .overrides
+            .resolve(OverrideField::WaylandDriver, app_config)
+        {
+            set("PROTON_ENABLE_WAYLAND", "1");
         }
 
         // HDR
-        if app_config.enable_hdr {
-            cmd.env("ENABLE_HDR", "1");
+        if game.overrides.resolve(OverrideField::Hdr, app_config) {
+            set("ENABLE_HDR", "1");
         }
 
         // WOW64
-        if app_config.enable_wow64 {
-            cmd.env("PROTON_USE_WOW64", "1");
+        if game.overrides.resolve(OverrideField::Wow64, app_config) {
+            set("PROTON_USE_WOW64", "1");
         }
 
         // Lossless scaling - uses LSFG_* environment variables (Linux native)
@@ -181,73 +705,123 @@ impl GameLauncher {
         if game.lossless_enabled {
             info!("Enabling Lossless Scaling via LSFG environment variables");
             // Enable legacy mode for Wine/Proton compatibility
-            cmd.env("LSFG_LEGACY", "1");
+            set("LSFG_LEGACY", "1");
 
             // Map multiplier if > 0
             if game.lossless_multiplier > 0 {
-                cmd.env("LSFG_MULTIPLIER", game.lossless_multiplier.to_string());
+                set("LSFG_MULTIPLIER", &game.lossless_multiplier.to_string());
             }
 
             // Map performance mode (1 for enabled, 0 for disabled)
-            cmd.env(
+            set(
                 "LSFG_PERFORMANCE_MODE",
                 if game.lossless_performance { "1" } else { "0" },
             );
 
             // Map HDR mode (1 for enabled, 0 for disabled)
-            cmd.env("LSFG_HDR_MODE", if game.lossless_hdr { "1" } else { "0" });
+            set("LSFG_HDR_MODE", if game.lossless_hdr { "1" } else { "0" });
 
             // Conservative mapping for flow scale:
             // Only set LSFG_FLOW_SCALE to 1.0 when explicitly enabled, otherwise omit.
             // This follows the principle of minimal environment variable pollution.
             if game.lossless_flow {
-                cmd.env("LSFG_FLOW_SCALE", "1.0");
+                set("LSFG_FLOW_SCALE", "1.0");
             }
             // Note: Not overriding WINEDLLOVERRIDES to preserve user configurations
         }
 
         // Discrete GPU
-        if app_config.discrete_gpu {
-            cmd.env("__GLX_VENDOR_LIBRARY_NAME", "nvidia");
+        if game
+            .overrides
+            .resolve(OverrideField::DiscreteGpu, app_config)
+        {
+            set("__GLX_VENDOR_LIBRARY_NAME", "nvidia");
         }
 
         // Proton fixes
         if !game.protonfix.is_empty() {
-            cmd.env("PROTON_NO_FSYNC", "1");
-            cmd.env("PROTON_NO_ESYNC", "1");
+            set("PROTON_NO_FSYNC", "1");
+            set("PROTON_NO_ESYNC", "1");
         }
 
         // Logging
-        if app_config.enable_logging {
-            let _log_file = Paths::logs_dir().join(format!("{}.log", game.gameid));
-            cmd.env("WINEDEBUG", "+all");
-            cmd.env("WINE_MONO_TRACE", "E:System.Windows.Forms");
+        if game.overrides.resolve(OverrideField::Logging, app_config) {
+            let log_dir = game_logs::game_logs_dir(&game.gameid);
+            set("PROTON_LOG", "1");
+            set("PROTON_LOG_DIR", &log_dir.to_string_lossy());
+            set("UMU_LOG", "1");
+            set("WINEDEBUG", "+all");
+            set("WINE_MONO_TRACE", "E:System.Windows.Forms");
         }
 
-        Ok(())
+        // Assignments in the launch arguments; syntax errors are reported by command_line
+        if let Ok(options) = launch_args::parse_launch_options(&game.launch_arguments) {
+            for (key, value) in &options.env {
+                set(key, value);
+            }
+        }
+
+        // Per-game variables win over everything else
+        for (key, value) in &game.env {
+            if envar::is_valid_env_var_key(key) {
+                set(key, value);
+            } else {
+                warn!(
+                    "Ignoring invalid environment variable '{}' for {}",
+                    key, game.title
+                );
+            }
+        }
+
+        env
     }
 
-    /// Build command arguments
-    fn build_arguments(game: &Game) -> Vec<String> {
-        let mut args = Vec::new();
+    /// Full argv of a launch: wrapper chain, umu-run, executable and arguments
+    pub fn command_line(
+        game: &Game,
+        app_config: &AppConfig,
+        umu_run: &Path,
+    ) -> Result<Vec<String>> {
+        let options = Self::launch_options(game)?;
+
+        // Commands from the launch arguments wrap umu-run, inside the wrapper chain
+        let mut command = options.prefix;
+        command.push(umu_run.to_string_lossy().to_string());
+        command.extend(Self::build_arguments(game, options.suffix));
+
+        let chain = wrappers::wrapper_chain(game, app_config);
+        wrappers::build_argv(&chain, game, command, Wrapper::find_program)
+            .with_context(|| format!("Invalid wrapper arguments for {}", game.title))
+    }
 
-        // Game executable path
-        args.push(game.path.to_string_lossy().to_string());
+    /// Parse the launch arguments, with the game arguments appended to the suffix
+    fn launch_options(game: &Game) -> Result<LaunchOptions> {
+        let mut options = launch_args::parse_launch_options(&game.launch_arguments)
+            .with_context(|| format!("Invalid launch arguments for {}", game.title))?;
+        options.suffix.extend(
+            launch_args::split(&game.game_arguments)
+                .with_context(|| format!("Invalid game arguments for {}", game.title))?,
+        );
+        Ok(options)
+    }
 
-        // Launch arguments (e.g., -no-dwrite)
-        if !game.launch_arguments.is_empty() {
-            for arg in game.launch_arguments.split_whitespace() {
-                args.push(arg.to_string());
-            }
-        }
+    /// Check if the game will run inside gamescope
+    fn uses_gamescope(game: &Game, app_config: &AppConfig) -> bool {
+        wrappers::wrapper_chain(game, app_config)
+            .iter()
+            .any(|wrapper| wrapper.kind == WrapperKind::Gamescope)
+            && Paths::gamescope().is_some()
+    }
 
-        // Game-specific arguments
-        if !game.game_arguments.is_empty() {
-            for arg in game.game_arguments.split_whitespace() {
-                args.push(arg.to_string());
-            }
+    /// Build command arguments: the executable followed by its arguments
+    fn build_arguments(game: &Game, game_args: Vec<String>) -> Vec<String> {
+        // The batch file starts the game itself, with its arguments
+        if addapp::is_enabled(game) {
+            return vec![addapp::batch_path(game).to_string_lossy().to_string()];
         }
 
+        let mut args = vec![game.path.to_string_lossy().to_string()];
+        args.extend(game_args);
         args
     }
 
@@ -260,151 +834,846 @@ impl GameLauncher {
         sys.processes().contains_key(&pid)
     }
 
-    /// Terminate a game process
-    pub fn terminate(pid: u32) -> Result<()> {
-        info!("Terminating process tree: {}", pid);
-        Self::terminate_tree(pid);
-        Ok(())
-    }
-
-    /// Terminate a process tree recursively
-    pub fn terminate_tree(pid: u32) {
-        let mut sys = System::new();
-        sys.refresh_processes();
-        Self::terminate_tree_recursive(&mut sys, pid);
-    }
-
-    fn terminate_tree_recursive(sys: &mut System, pid: u32) {
-        let target_pid = Pid::from_u32(pid);
-
-        // Find children
-        let mut children = Vec::new();
-        for (p, proc) in sys.processes() {
-            if let Some(parent) = proc.parent() {
-                if parent == target_pid {
-                    children.push(p.as_u32());
-                }
+    /// Stop a game: ask its processes to exit, then kill what is left
+    ///
+    /// The game's process trees get SIGTERM and, for Wine games, the prefix's
+    /// wineserver is told to shut down unless another running game shares the
+    /// prefix. Processes still running after `grace` are killed. Nothing
+    /// outside the game's own trees is signalled.
+    pub fn stop(process: &GameProcess, grace: Duration) -> StopOutcome {
+        let prefix = process.prefix.as_deref();
+
+        let processes = process_tree::snapshot();
+        let roots = process.stop_roots(&processes);
+        let targets = process_tree::game_processes(&processes, &roots);
+        info!(
+            "Stopping {}: {} processes, {}s grace period",
+            process.game_title,
+            targets.len(),
+            grace.as_secs()
+        );
+        for &pid in &targets {
+            Self::send_signal(pid, false);
+        }
+        if let Some(prefix) = prefix {
+            let shared = Self::load_processes().iter().any(|other| {
+                other.gameid != process.gameid && other.prefix.as_deref() == Some(prefix)
+            });
+            if shared {
+                info!("Keeping wineserver of {:?}, another game uses it", prefix);
+            } else {
+                Self::kill_wineserver(&processes, prefix);
             }
         }
 
-        // Kill children first
-        for child_pid in children {
-            Self::terminate_tree_recursive(sys, child_pid);
+        let deadline = Instant::now() + grace;
+        loop {
+            // Processes started while stopping belong to the game as well
+            let processes = process_tree::snapshot();
+            let mut survivors = process_tree::game_processes(&processes, &roots);
+            for &pid in &targets {
+                if !survivors.contains(&pid) && processes.iter().any(|p| p.pid == pid) {
+                    survivors.push(pid);
+                }
+            }
+            if survivors.is_empty() {
+                info!("{} stopped", process.game_title);
+                return StopOutcome::Exited;
+            }
+            if Instant::now() >= deadline {
+                warn!(
+                    "Killing {} processes of {} that are still running",
+                    survivors.len(),
+                    process.game_title
+                );
+                for &pid in &survivors {
+                    Self::send_signal(pid, true);
+                }
+                return StopOutcome::Killed(survivors.len());
+            }
+            std::thread::sleep(STOP_POLL);
         }
+    }
 
-        // Kill the parent
-        info!("Killing process: {}", pid);
+    /// Ask a process to exit, or kill it when `force` is set
+    fn send_signal(pid: u32, force: bool) {
+        debug!(
+            "Sending {} to {}",
+            if force { "SIGKILL" } else { "SIGTERM" },
+            pid
+        );
         #[cfg(unix)]
         {
             use nix::sys::signal::{self, Signal};
             use nix::unistd::Pid as NixPid;
-            let _ = signal::kill(NixPid::from_raw(pid as i32), Signal::SIGKILL);
+            let signal = if force {
+                Signal::SIGKILL
+            } else {
+                Signal::SIGTERM
+            };
+            let _ = signal::kill(NixPid::from_raw(pid as i32), signal);
         }
         #[cfg(windows)]
         {
-            let _ = Command::new("taskkill")
-                .args(["/PID", &pid.to_string(), "/F"])
-                .output();
-        }
-    }
-
-    /// Kill all common Wine processes
-    pub fn kill_all_wine_processes() {
-        info!("Killing all common Wine processes");
-        let wine_binaries = [
-            "wineserver",
-            "wine64-preloader",
-            "winedevice.exe",
-            "plugplay.exe",
-            "services.exe",
-            "explorer.exe",
-        ];
+            let pid = pid.to_string();
+            let mut args = vec!["/PID", pid.as_str()];
+            if force {
+                args.push("/F");
+            }
+            let _ = Command::new("taskkill").args(args).output();
+        }
+    }
+
+    /// Shut down the wineserver of a prefix, if one is running
+    ///
+    /// The running wineserver's own binary is preferred, since it matches the
+    /// Proton build that started it.
+    fn kill_wineserver(processes: &[ProcessInfo], prefix: &Path) {
+        let Some(server) = processes.iter().find(|process| {
+            process.name == "wineserver" && process_tree::in_prefix(process, prefix)
+        }) else {
+            return;
+        };
 
-        for bin in wine_binaries {
-            let _ = Command::new("pkill").args(["-9", bin]).output();
+        let binary = server
+            .cmd
+            .first()
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute() && path.exists())
+            .unwrap_or_else(|| PathBuf::from("wineserver"));
+        info!("Shutting down wineserver of {:?}", prefix);
+        match Command::new(&binary)
+            .arg("-k")
+            .env("WINEPREFIX", prefix)
+            .output()
+        {
+            Ok(output) if !output.status.success() => {
+                warn!("{:?} -k exited with {}", binary, output.status)
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to run {:?} -k: {}", binary, e),
         }
     }
 
-    /// Get game process by title
+    /// Get the saved process of a game
     /// TODO: Use for game process monitoring, status display
     #[allow(dead_code)]
-    pub fn get_game_process(title: &str) -> Option<GameProcess> {
-        let running_games = Paths::running_games_json();
+    pub fn get_game_process(gameid: &str) -> Option<GameProcess> {
+        Self::load_processes()
+            .into_iter()
+            .find(|p| p.gameid == gameid)
+    }
 
-        if !running_games.exists() {
-            return None;
-        }
+    /// Processes saved in running_games.json
+    pub fn load_processes() -> Vec<GameProcess> {
+        RunningStore::open().load()
+    }
 
-        let content = std::fs::read_to_string(running_games).ok()?;
-        let processes: Vec<GameProcess> = serde_json::from_str(&content).ok()?;
+    /// Save running game process, replacing an earlier one of the same game
+    pub fn save_process(process: &GameProcess) -> Result<()> {
+        RunningStore::open().update(|processes| {
+            processes.retain(|p| p.gameid != process.gameid);
+            processes.push(process.clone());
+        })
+    }
 
-        processes.into_iter().find(|p| p.game_title == title)
+    /// Remove this launcher instance's process of a game from running games
+    ///
+    /// Another instance may have launched the game again once it exited.
+    pub fn remove_process(gameid: &str) -> Result<()> {
+        let own_pid = std::process::id();
+        RunningStore::open().update(|processes| {
+            processes.retain(|p| p.gameid != gameid || (p.owner_pid != 0 && p.owner_pid != own_pid))
+        })
     }
 
-    /// Save running game process
-    pub fn save_process(process: &GameProcess) -> Result<()> {
-        let running_games = Paths::running_games_json();
+    /// Game IDs from the latest games file, most recent first
+    pub fn latest_games() -> Vec<String> {
+        std::fs::read_to_string(Paths::latest_games_txt())
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
 
-        let mut processes: Vec<GameProcess> = if running_games.exists() {
-            let content = std::fs::read_to_string(&running_games)?;
-            serde_json::from_str(&content).unwrap_or_default()
-        } else {
-            Vec::new()
-        };
+    /// Move a game to the front of the latest games file
+    pub fn update_latest_games(gameid: &str) -> Result<()> {
+        let mut games = Self::latest_games();
+
+        // Remove if already exists
+        games.retain(|g| g != gameid);
+
+        // Add to front
+        games.insert(0, gameid.to_string());
 
-        processes.push(process.clone());
+        // Keep only last 10
+        games.truncate(10);
 
-        let content = serde_json::to_string_pretty(&processes)?;
-        std::fs::write(&running_games, content)?;
+        std::fs::write(Paths::latest_games_txt(), games.join("\n") + "\n")?;
 
         Ok(())
     }
 
-    /// Remove game process from running games
-    pub fn remove_process(title: &str) -> Result<()> {
-        let running_games = Paths::running_games_json();
+    /// Key running_games.json and latest-games.txt by game ID
+    ///
+    /// Both used to name games by title. Titles are matched against the
+    /// library, which must have loaded. Processes recorded by title for games
+    /// that no longer exist are dropped; latest-games.txt entries matching
+    /// no title may be IDs and are kept.
+    pub fn migrate_running_state(games: &[Game]) -> Result<()> {
+        if migrate_processes(&Self::load_processes(), games).is_some() {
+            RunningStore::open().update(|processes| {
+                if let Some(migrated) = migrate_processes(processes, games) {
+                    info!("Migrating running_games.json to game IDs");
+                    *processes = migrated;
+                }
+            })?;
+        }
 
-        if !running_games.exists() {
-            return Ok(());
+        let latest = Self::latest_games();
+        if let Some(migrated) = migrate_latest_games(&latest, games) {
+            info!("Migrating latest-games.txt to game IDs");
+            let latest_games = Paths::latest_games_txt();
+            std::fs::write(&latest_games, migrated.join("\n") + "\n")
+                .with_context(|| format!("Failed to write {:?}", latest_games))?;
         }
 
-        let content = std::fs::read_to_string(&running_games)?;
-        let mut processes: Vec<GameProcess> = serde_json::from_str(&content)?;
+        Ok(())
+    }
+}
 
-        processes.retain(|p| p.game_title != title);
+/// Names of the wrappers a launch runs through, outermost first
+///
+/// Wrappers listed in Settings or the game that aren't installed fail the
+/// launch. GameMode and gamescope turned on only through their checkboxes
+/// are skipped when missing, like the Python launcher did.
+fn installed_wrappers(
+    game: &Game,
+    app_config: &AppConfig,
+    find: impl Fn(&Wrapper) -> Option<PathBuf>,
+) -> Result<Vec<String>, LaunchError> {
+    let mut installed = Vec::new();
+    for wrapper in wrappers::wrapper_chain(game, app_config) {
+        let name = match wrapper.kind {
+            WrapperKind::Custom => wrapper.command.trim().to_string(),
+            kind => kind.label().to_string(),
+        };
+        if find(&wrapper).is_some() {
+            installed.push(name);
+            continue;
+        }
 
-        let content = serde_json::to_string_pretty(&processes)?;
-        std::fs::write(&running_games, content)?;
+        let global = app_config.wrappers.contains(&wrapper);
+        let listed = global || game.wrappers.contains(&wrapper);
+        if listed {
+            let global = global && !game.wrappers.contains(&wrapper);
+            return Err(LaunchError::WrapperMissing { name, global });
+        }
+    }
+    Ok(installed)
+}
 
-        Ok(())
+/// Check if files can be created in a directory
+fn is_writable(dir: &Path) -> bool {
+    let probe = dir.join(format!(".faugus-write-check-{}", std::process::id()));
+    match std::fs::File::create(&probe) {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
     }
+}
 
-    /// Update latest games file
-    pub fn update_latest_games(title: &str) -> Result<()> {
-        let latest_games = Paths::latest_games_txt();
+/// First saved process that is still running and conflicts with `game`
+fn find_conflict(
+    game: &Game,
+    app_config: &AppConfig,
+    saved: &[GameProcess],
+    processes: &[ProcessInfo],
+) -> Result<(), LaunchConflict> {
+    let prefix = (!runner_resolver::is_native(&game.runner)).then_some(game.prefix.as_path());
+
+    for running in saved {
+        let same_game = running.gameid == game.gameid;
+        let same_prefix = app_config.prevent_shared_prefix
+            && prefix.is_some()
+            && running.prefix.as_deref() == prefix;
+        if !(same_game || same_prefix) || !running.clone().is_live(processes) {
+            continue;
+        }
 
-        let content = if latest_games.exists() {
-            std::fs::read_to_string(&latest_games).unwrap_or_default()
+        return Err(if same_game {
+            LaunchConflict::AlreadyRunning(running.game_title.clone())
         } else {
-            String::new()
+            LaunchConflict::PrefixInUse(running.game_title.clone())
+        });
+    }
+    Ok(())
+}
+
+/// Game ID of a title from the library
+fn gameid_for_title<'a>(games: &'a [Game], title: &str) -> Option<&'a str> {
+    games
+        .iter()
+        .find(|game| game.title == title)
+        .map(|game| game.gameid.as_str())
+}
+
+/// Fill in the game ID of processes saved before it was recorded
+///
+/// None if nothing needs to change.
+fn migrate_processes(processes: &[GameProcess], games: &[Game]) -> Option<Vec<GameProcess>> {
+    if processes.iter().all(|p| !p.gameid.is_empty()) {
+        return None;
+    }
+
+    let mut migrated: Vec<GameProcess> = Vec::new();
+    for process in processes {
+        let mut process = process.clone();
+        if process.gameid.is_empty() {
+            match gameid_for_title(games, &process.game_title) {
+                Some(gameid) => process.gameid = gameid.to_string(),
+                None => {
+                    warn!("Dropping process of unknown game: {}", process.game_title);
+                    continue;
+                }
+            }
+        }
+        // A title shared by two entries resolves to one game, keep its newest
+        migrated.retain(|p| p.gameid != process.gameid);
+        migrated.push(process);
+    }
+    Some(migrated)
+}
+
+/// Replace the titles of the latest games list with game IDs
+///
+/// Entries that are neither an ID nor a title in the library are kept as
+/// they are. None if nothing needs to change.
+fn migrate_latest_games(latest: &[String], games: &[Game]) -> Option<Vec<String>> {
+    let mut migrated: Vec<String> = Vec::new();
+    for entry in latest {
+        let gameid = if games.iter().any(|game| &game.gameid == entry) {
+            entry.as_str()
+        } else {
+            gameid_for_title(games, entry).unwrap_or(entry)
         };
+        if !migrated.iter().any(|m| m == gameid) {
+            migrated.push(gameid.to_string());
+        }
+    }
+    (migrated != latest).then_some(migrated)
+}
 
-        let mut games: Vec<String> = content
-            .lines()
-            .map(|line| line.trim().to_string())
-            .collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ToggleOverride;
+
+    fn test_game() -> Game {
+        Game {
+            gameid: "test-game".to_string(),
+            title: "Test Game".to_string(),
+            prefix: PathBuf::from("/tmp/prefix"),
+            ..Default::default()
+        }
+    }
 
-        // Remove if already exists
-        games.retain(|g| g != title);
+    #[test]
+    fn test_environment_precedence() {
+        let mut game = test_game();
+        game.env
+            .insert("PROTON_ENABLE_WAYLAND".to_string(), "0".to_string());
+        game.env.insert("DXVK_HUD".to_string(), "fps".to_string());
+
+        let app_config = AppConfig {
+            wayland_driver: true,
+            enable_hdr: true,
+            ..Default::default()
+        };
 
-        // Add to front
-        games.insert(0, title.to_string());
+        let global = HashMap::from([
+            ("ENABLE_HDR".to_string(), "0".to_string()),
+            ("DXVK_HUD".to_string(), "full".to_string()),
+            ("WINEDLLOVERRIDES".to_string(), "d3d9=n,b".to_string()),
+        ]);
+
+        let env = GameLauncher::build_environment(&game, &app_config, &global, "GE-Proton9-20");
+
+        // envar.txt only
+        assert_eq!(
+            env.get("WINEDLLOVERRIDES").map(String::as_str),
+            Some("d3d9=n,b")
+        );
+        // AppConfig toggle beats envar.txt
+        assert_eq!(env.get("ENABLE_HDR").map(String::as_str), Some("1"));
+        // Per-game beats AppConfig toggle and envar.txt
+        assert_eq!(
+            env.get("PROTON_ENABLE_WAYLAND").map(String::as_str),
+            Some("0")
+        );
+        assert_eq!(env.get("DXVK_HUD").map(String::as_str), Some("fps"));
+        // Launcher-managed values
+        assert_eq!(
+            env.get("WINEPREFIX").map(String::as_str),
+            Some("/tmp/prefix")
+        );
+        assert_eq!(
+            env.get("PROTONPATH").map(String::as_str),
+            Some("GE-Proton9-20")
+        );
+        assert_eq!(env.get("GAMEID").map(String::as_str), Some("test-game"));
+    }
 
-        // Keep only last 10
-        games.truncate(10);
+    #[test]
+    fn test_environment_resolves_toggle_overrides() {
+        let mut game = test_game();
+        game.overrides.enable_hdr = ToggleOverride::On;
+        game.overrides.discrete_gpu = ToggleOverride::Off;
+
+        let app_config = AppConfig {
+            enable_hdr: false,
+            discrete_gpu: true,
+            enable_wow64: true,
+            ..Default::default()
+        };
 
-        std::fs::write(&latest_games, games.join("\n") + "\n")?;
+        let env = GameLauncher::build_environment(&game, &app_config, &HashMap::new(), "");
 
-        Ok(())
+        assert_eq!(env.get("ENABLE_HDR").map(String::as_str), Some("1"));
+        assert!(!env.contains_key("__GLX_VENDOR_LIBRARY_NAME"));
+        assert_eq!(env.get("PROTON_USE_WOW64").map(String::as_str), Some("1"));
+    }
+
+    #[test]
+    fn test_environment_skips_invalid_game_keys() {
+        let mut game = test_game();
+        game.env.insert("BAD-KEY".to_string(), "1".to_string());
+
+        let env =
+            GameLauncher::build_environment(&game, &AppConfig::default(), &HashMap::new(), "");
+
+        assert!(!env.contains_key("BAD-KEY"));
+        assert!(
+            !env.contains_key("PROTONPATH"),
+            "empty runner means UMU default"
+        );
+    }
+
+    #[test]
+    fn test_environment_linux_native() {
+        let mut game = test_game();
+        game.runner = runner_resolver::LINUX_NATIVE.to_string();
+        let runner = runner_resolver::resolve_runner(&game.runner).expect("native runner");
+        runner_resolver::validate_runner(&game.runner).expect("native needs no Proton");
+
+        let env =
+            GameLauncher::build_environment(&game, &AppConfig::default(), &HashMap::new(), &runner);
+
+        assert_eq!(env.get("UMU_NO_PROTON").map(String::as_str), Some("1"));
+        assert!(!env.contains_key("WINEPREFIX"));
+        assert!(!env.contains_key("PROTONPATH"));
+        assert_eq!(env.get("GAMEID").map(String::as_str), Some("test-game"));
+    }
+
+    #[test]
+    fn test_command_line_addapp() {
+        let mut game = test_game();
+        game.path = PathBuf::from("/games/game.exe");
+        game.game_arguments = "-windowed".to_string();
+        let umu_run = Path::new("/usr/bin/umu-run");
+
+        assert_eq!(
+            GameLauncher::command_line(&game, &AppConfig::default(), umu_run)
+                .expect("valid arguments"),
+            vec!["/usr/bin/umu-run", "/games/game.exe", "-windowed"]
+        );
+
+        // The batch file replaces the game and carries its arguments
+        game.addapp_checkbox = true;
+        game.addapp = "/tools/trainer.exe".to_string();
+        assert_eq!(
+            GameLauncher::command_line(&game, &AppConfig::default(), umu_run)
+                .expect("valid arguments"),
+            vec![
+                "/usr/bin/umu-run",
+                "/tmp/prefix/drive_c/faugus-addapp-test-game.bat"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_command_line_launch_options() {
+        let mut game = test_game();
+        game.path = PathBuf::from("/games/game.exe");
+        game.launch_arguments = "DXVK_HUD=fps taskset -c 0-3 %command% -dx11".to_string();
+        game.game_arguments = r#"-config "C:\My Games\x.ini""#.to_string();
+        let umu_run = Path::new("/usr/bin/umu-run");
+
+        assert_eq!(
+            GameLauncher::command_line(&game, &AppConfig::default(), umu_run)
+                .expect("valid arguments"),
+            vec![
+                "taskset",
+                "-c",
+                "0-3",
+                "/usr/bin/umu-run",
+                "/games/game.exe",
+                "-dx11",
+                "-config",
+                "C:\\My Games\\x.ini",
+            ]
+        );
+
+        let env =
+            GameLauncher::build_environment(&game, &AppConfig::default(), &HashMap::new(), "");
+        assert_eq!(env.get("DXVK_HUD").map(String::as_str), Some("fps"));
+
+        game.game_arguments = "-config \"unterminated".to_string();
+        let err = GameLauncher::command_line(&game, &AppConfig::default(), umu_run)
+            .expect_err("syntax error should be reported");
+        assert!(format!("{:#}", err).contains("Invalid game arguments"));
+    }
+
+    #[test]
+    fn test_refresh_follows_game_process() {
+        let info = |pid: u32, parent: u32, cmd: &str| ProcessInfo {
+            pid,
+            parent: Some(parent),
+            name: cmd.rsplit('\\').next().unwrap_or_default().to_string(),
+            cmd: vec![cmd.to_string()],
+            wineprefix: Some("/tmp/prefix".to_string()),
+        };
+        let mut process = GameProcess {
+            game_title: "Test Game".to_string(),
+            main_pid: 100,
+            umu_pid: Some(100),
+            gameid: "test-game".to_string(),
+            started_at: 0,
+            addapp_bat: None,
+            log_file: None,
+            game_path: PathBuf::from("/games/game.exe"),
+            prefix: Some(PathBuf::from("/tmp/prefix")),
+            owner_pid: 0,
+            launching: false,
+        };
+
+        // Starting: only umu-run is there
+        assert!(process.refresh(&[info(100, 1, "umu-run")]));
+        assert!(!process.game_found());
+
+        // The game outlives umu-run
+        assert!(process.refresh(&[
+            info(100, 1, "umu-run"),
+            info(120, 100, "Z:\\games\\game.exe")
+        ]));
+        assert_eq!(process.main_pid, 120);
+        assert!(process.refresh(&[info(120, 1, "Z:\\games\\game.exe")]));
+
+        assert!(!process.refresh(&[]));
+    }
+
+    #[test]
+    fn test_adopt_checks_process_identity() {
+        let info = |pid: u32, cmd: &[&str], prefix: Option<&str>| ProcessInfo {
+            pid,
+            parent: Some(1),
+            name: cmd[0]
+                .rsplit(['/', '\\'])
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            cmd: cmd.iter().map(|arg| arg.to_string()).collect(),
+            wineprefix: prefix.map(str::to_string),
+        };
+        let saved = GameProcess {
+            game_title: "Test Game".to_string(),
+            main_pid: 120,
+            umu_pid: Some(100),
+            gameid: "test-game".to_string(),
+            started_at: 0,
+            addapp_bat: None,
+            log_file: None,
+            game_path: PathBuf::from("/games/game.exe"),
+            prefix: Some(PathBuf::from("/tmp/prefix")),
+            owner_pid: 0,
+            launching: false,
+        };
+
+        let mut process = saved.clone();
+        assert!(process.adopt(&[info(120, &["Z:\\games\\game.exe"], Some("/tmp/prefix"))]));
+        assert_eq!(process, saved);
+
+        // The PID now belongs to something else, and the game is gone
+        let mut process = saved.clone();
+        assert!(!process.adopt(&[info(120, &["/usr/bin/bash"], None)]));
+
+        // The game restarted under another PID
+        let mut process = saved.clone();
+        assert!(process.adopt(&[info(130, &["Z:\\games\\game.exe"], Some("/tmp/prefix"))]));
+        assert_eq!(process.main_pid, 130);
+
+        // Still starting: the launcher process runs umu-run
+        let mut process = GameProcess {
+            main_pid: 100,
+            ..saved.clone()
+        };
+        assert!(process.adopt(&[info(100, &["/usr/bin/umu-run", "/games/game.exe"], None)]));
+    }
+
+    #[test]
+    fn test_stop_roots_skip_reused_pids() {
+        let info = |pid: u32, cmd: &[&str], prefix: Option<&str>| ProcessInfo {
+            pid,
+            parent: Some(1),
+            name: cmd[0]
+                .rsplit(['/', '\\'])
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            cmd: cmd.iter().map(|arg| arg.to_string()).collect(),
+            wineprefix: prefix.map(str::to_string),
+        };
+        let process = GameProcess {
+            game_title: "Test Game".to_string(),
+            main_pid: 120,
+            umu_pid: Some(100),
+            gameid: "test-game".to_string(),
+            started_at: 0,
+            addapp_bat: None,
+            log_file: None,
+            game_path: PathBuf::from("/games/game.exe"),
+            prefix: Some(PathBuf::from("/tmp/prefix")),
+            owner_pid: 0,
+            launching: false,
+        };
+
+        let launcher = info(100, &["/usr/bin/umu-run", "/games/game.exe"], None);
+        let game = info(120, &["Z:\\games\\game.exe"], Some("/tmp/prefix"));
+        assert_eq!(
+            process.stop_roots(&[launcher.clone(), game]),
+            vec![120, 100]
+        );
+
+        // Both PIDs were reused by unrelated processes
+        let reused = [
+            info(100, &["/usr/bin/bash"], None),
+            info(120, &["/usr/bin/firefox"], None),
+        ];
+        assert!(process.stop_roots(&reused).is_empty());
+
+        // The game has ended, its launcher is still there
+        assert_eq!(process.stop_roots(&[launcher]), vec![100]);
+    }
+
+    #[test]
+    fn test_launch_conflicts() {
+        let game = test_game();
+        let running = GameProcess {
+            game_title: "Other Game".to_string(),
+            main_pid: 120,
+            umu_pid: Some(100),
+            gameid: "other-game".to_string(),
+            started_at: 0,
+            addapp_bat: None,
+            log_file: None,
+            game_path: PathBuf::from("/games/other.exe"),
+            prefix: Some(PathBuf::from("/tmp/prefix")),
+            owner_pid: 0,
+            launching: false,
+        };
+        let processes = [ProcessInfo {
+            pid: 120,
+            parent: Some(1),
+            name: "other.exe".to_string(),
+            cmd: vec!["Z:\\games\\other.exe".to_string()],
+            wineprefix: Some("/tmp/prefix".to_string()),
+        }];
+        let mut app_config = AppConfig::default();
+
+        // Another game in the same prefix only conflicts when asked to
+        let saved = vec![running.clone()];
+        assert_eq!(
+            find_conflict(&game, &app_config, &saved, &processes),
+            Ok(())
+        );
+        app_config.prevent_shared_prefix = true;
+        assert_eq!(
+            find_conflict(&game, &app_config, &saved, &processes),
+            Err(LaunchConflict::PrefixInUse("Other Game".to_string()))
+        );
+        // Not once it has exited
+        assert_eq!(find_conflict(&game, &app_config, &saved, &[]), Ok(()));
+
+        let same = GameProcess {
+            gameid: game.gameid.clone(),
+            game_title: game.title.clone(),
+            prefix: None,
+            ..running
+        };
+        assert_eq!(
+            find_conflict(&game, &AppConfig::default(), &[same], &processes),
+            Err(LaunchConflict::AlreadyRunning("Test Game".to_string()))
+        );
+
+        // A launch being prepared holds the game while its launcher runs
+        let reserved = GameProcess {
+            owner_pid: 120,
+            ..GameProcess::reservation(&game)
+        };
+        assert_eq!(
+            find_conflict(
+                &game,
+                &AppConfig::default(),
+                std::slice::from_ref(&reserved),
+                &processes
+            ),
+            Err(LaunchConflict::AlreadyRunning("Test Game".to_string()))
+        );
+        assert_eq!(
+            find_conflict(&game, &AppConfig::default(), &[reserved], &[]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_migrate_running_state() {
+        let game = |gameid: &str, title: &str| Game {
+            gameid: gameid.to_string(),
+            title: title.to_string(),
+            ..Default::default()
+        };
+        let games = vec![game("id-a", "Alpha"), game("id-b", "Beta")];
+
+        let latest = ["Beta", "id-a", "id-gone", "Alpha"].map(String::from);
+        assert_eq!(
+            migrate_latest_games(&latest, &games),
+            Some(["id-b", "id-a", "id-gone"].map(String::from).to_vec())
+        );
+        let migrated = ["id-b", "id-a", "id-gone"].map(String::from);
+        assert_eq!(migrate_latest_games(&migrated, &games), None);
+        // A library that didn't load must not empty the list
+        assert_eq!(migrate_latest_games(&migrated, &[]), None);
+
+        let process = |gameid: &str, title: &str| GameProcess {
+            game_title: title.to_string(),
+            main_pid: 1,
+            umu_pid: None,
+            gameid: gameid.to_string(),
+            started_at: 0,
+            addapp_bat: None,
+            log_file: None,
+            game_path: PathBuf::new(),
+            prefix: None,
+            owner_pid: 0,
+            launching: false,
+        };
+        let processes = vec![
+            process("", "Alpha"),
+            process("", "Gone"),
+            process("id-b", "Beta"),
+        ];
+        let migrated = migrate_processes(&processes, &games).expect("needs migration");
+        let ids: Vec<&str> = migrated.iter().map(|p| p.gameid.as_str()).collect();
+        assert_eq!(ids, vec!["id-a", "id-b"]);
+        assert_eq!(migrate_processes(&migrated, &games), None);
+    }
+
+    #[test]
+    fn test_missing_wrappers() {
+        let only_mangohud = |wrapper: &Wrapper| {
+            (wrapper.kind == WrapperKind::Custom && wrapper.command == "mangohud")
+                .then(|| PathBuf::from("/usr/bin/mangohud"))
+        };
+        let custom = |command: &str| Wrapper {
+            command: command.to_string(),
+            ..Wrapper::new(WrapperKind::Custom)
+        };
+
+        // Checkboxes without the tools installed proceed without them
+        let mut game = test_game();
+        game.gamemode = true;
+        game.gamescope.enabled = true;
+        game.wrappers = vec![custom("mangohud")];
+        assert_eq!(
+            installed_wrappers(&game, &AppConfig::default(), only_mangohud),
+            Ok(vec!["mangohud".to_string()])
+        );
+
+        // Listed wrappers must be installed
+        game.wrappers.push(Wrapper::new(WrapperKind::GameMode));
+        assert_eq!(
+            installed_wrappers(&game, &AppConfig::default(), only_mangohud),
+            Err(LaunchError::WrapperMissing {
+                name: WrapperKind::GameMode.label().to_string(),
+                global: false,
+            })
+        );
+
+        let app_config = AppConfig {
+            wrappers: vec![custom("obs-gamecapture")],
+            ..Default::default()
+        };
+        assert_eq!(
+            installed_wrappers(&test_game(), &app_config, only_mangohud),
+            Err(LaunchError::WrapperMissing {
+                name: "obs-gamecapture".to_string(),
+                global: true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_launch_error_remedies() {
+        let error = anyhow::Error::from(RunnerError::NotInstalled {
+            name: "GE-Proton9-20".to_string(),
+        })
+        .context("Failed to resolve environment");
+        let error = LaunchError::from_error(&error);
+        assert_eq!(
+            error,
+            LaunchError::RunnerNotInstalled("GE-Proton9-20".to_string())
+        );
+        assert_eq!(
+            error.remedy(),
+            Some(Remedy::InstallRunner("GE-Proton9-20".to_string()))
+        );
+        assert_eq!(
+            LaunchError::RunnerNotInstalled("Proton-CachyOS".to_string()).remedy(),
+            Some(Remedy::EditGame)
+        );
+
+        let error = anyhow::Error::from(LaunchError::ExecutableMissing(PathBuf::from("/a.exe")));
+        assert_eq!(
+            LaunchError::from_error(&error).remedy(),
+            Some(Remedy::RelinkExecutable)
+        );
+
+        let missing = |global| LaunchError::WrapperMissing {
+            name: "MangoHud".to_string(),
+            global,
+        };
+        assert_eq!(missing(true).remedy(), Some(Remedy::OpenSettings));
+        assert_eq!(missing(false).remedy(), Some(Remedy::EditGame));
+
+        let error = LaunchError::from_error(&anyhow::anyhow!("Invalid launch arguments"));
+        assert_eq!(
+            error,
+            LaunchError::Other("Invalid launch arguments".to_string())
+        );
+        assert_eq!(error.remedy(), None);
+    }
+
+    #[test]
+    fn test_is_writable() {
+        let dir = std::env::temp_dir().join(format!("faugus-writable-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("temp dir should be created");
+        assert!(is_writable(&dir));
+        assert!(!is_writable(&dir.join("missing")));
+        assert_eq!(std::fs::read_dir(&dir).expect("dir is readable").count(), 0);
+        let _ = std::fs::remove_dir_all(&dir);
     }
 }
@@ -0,0 +1,186 @@
+// Game logs
+// Per-game session logs holding the output of umu-run and the game
+
+use anyhow::{Context, Result};
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::config::paths::Paths;
+use crate::config::session_history::DAY;
+
+/// Directory holding the logs of a game
+pub fn game_logs_dir(gameid: &str) -> PathBuf {
+    Paths::logs_dir().join(gameid)
+}
+
+/// Days since 1970-01-01 to a (year, month, day) civil date
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // Howard Hinnant's days_from_civil, inverted
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Session log name for a start time, `YYYY-MM-DD_HH-MM-SS.log` in UTC
+pub fn session_file_name(started_at: u64) -> String {
+    let (year, month, day) = civil_from_days((started_at / DAY) as i64);
+    let secs = started_at % DAY;
+    format!(
+        "{:04}-{:02}-{:02}_{:02}-{:02}-{:02}.log",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// Check if a file is a session log written by the launcher
+///
+/// Other logs in the directory, like Proton's own, are never rotated.
+fn is_session_log(path: &Path) -> bool {
+    if path.extension().is_none_or(|ext| ext != "log") {
+        return false;
+    }
+    let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+        return false;
+    };
+
+    // Same-second sessions get a `-N` suffix
+    let (timestamp, suffix) = stem.split_at(stem.len().min(19));
+    let timestamp_ok = timestamp.len() == 19
+        && timestamp.char_indices().all(|(i, c)| match i {
+            4 | 7 | 13 | 16 => c == '-',
+            10 => c == '_',
+            _ => c.is_ascii_digit(),
+        });
+    let suffix_ok = suffix.is_empty()
+        || suffix
+            .strip_prefix('-')
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    timestamp_ok && suffix_ok
+}
+
+/// Create the log file of a new session, keeping the last `keep` sessions
+pub fn create_session_log(gameid: &str, started_at: u64, keep: usize) -> Result<(PathBuf, File)> {
+    let dir = game_logs_dir(gameid);
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create logs directory: {:?}", dir))?;
+
+    let name = session_file_name(started_at);
+    let mut path = dir.join(&name);
+    let mut attempt = 0;
+    let file = loop {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => break file,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists && attempt < 100 => {
+                attempt += 1;
+                path = dir.join(name.replace(".log", &format!("-{}.log", attempt)));
+            }
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to create log file: {:?}", path))
+            }
+        }
+    };
+
+    prune_sessions(&dir, keep);
+    Ok((path, file))
+}
+
+/// Delete the oldest session logs of a directory beyond the newest `keep`
+pub fn prune_sessions(dir: &Path, keep: usize) {
+    let mut sessions: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| is_session_log(path))
+            .collect(),
+        Err(_) => return,
+    };
+    // Names sort chronologically
+    sessions.sort();
+
+    let excess = sessions.len().saturating_sub(keep.max(1));
+    for path in sessions.drain(..excess) {
+        match fs::remove_file(&path) {
+            Ok(()) => info!("Removed old session log: {:?}", path),
+            Err(e) => warn!("Failed to remove old session log {:?}: {}", path, e),
+        }
+    }
+}
+
+/// Log files of a game, newest first
+pub fn list_logs(gameid: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(game_logs_dir(gameid)) else {
+        return Vec::new();
+    };
+
+    let mut logs: Vec<(PathBuf, std::time::SystemTime)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "log"))
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect();
+    logs.sort_by(|(a_path, a_time), (b_path, b_time)| {
+        b_time.cmp(a_time).then_with(|| b_path.cmp(a_path))
+    });
+    logs.into_iter().map(|(path, _)| path).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_file_name() {
+        assert_eq!(session_file_name(0), "1970-01-01_00-00-00.log");
+        // 2024-02-29 13:45:07 UTC
+        assert_eq!(session_file_name(1_709_214_307), "2024-02-29_13-45-07.log");
+
+        assert!(is_session_log(Path::new("2024-02-29_13-45-07.log")));
+        assert!(is_session_log(Path::new("/logs/2024-02-29_13-45-07-2.log")));
+        assert!(!is_session_log(Path::new("steam-12345.log")));
+        assert!(!is_session_log(Path::new("2024-02-29_13-45-07.txt")));
+        assert!(!is_session_log(Path::new("2024-02-29_13-45-07-.log")));
+    }
+
+    #[test]
+    fn test_prune_keeps_newest_sessions() {
+        let dir = std::env::temp_dir().join(format!("faugus-logs-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("create test dir");
+        for start in [100, 200, 300, 400] {
+            fs::write(dir.join(session_file_name(start)), "").expect("write log");
+        }
+        fs::write(dir.join("steam-0.log"), "").expect("write proton log");
+
+        prune_sessions(&dir, 2);
+
+        let mut left: Vec<String> = fs::read_dir(&dir)
+            .expect("read test dir")
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        left.sort();
+        assert_eq!(
+            left,
+            vec![
+                session_file_name(300),
+                session_file_name(400),
+                "steam-0.log".to_string()
+            ]
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
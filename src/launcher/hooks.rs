@@ -0,0 +1,267 @@
+// Launch hooks
+// Runs the global and per-game pre-launch and post-exit commands
+
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::config::app_config::{AppConfig, DEFAULT_HOOK_TIMEOUT};
+use crate::config::Game;
+
+/// How often a running hook is polled for exit
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// When a hook runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookStage {
+    PreLaunch,
+    PostExit,
+}
+
+impl fmt::Display for HookStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                HookStage::PreLaunch => "pre-launch",
+                HookStage::PostExit => "post-exit",
+            }
+        )
+    }
+}
+
+/// A hook command ready to run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hook {
+    pub command: String,
+    pub timeout: Duration,
+    /// Failure aborts the launch (pre-launch hooks only)
+    pub abort_on_failure: bool,
+}
+
+/// Hooks of a game for a stage, in the order they run
+///
+/// Global pre-launch hooks run before the game's own, and post-exit hooks
+/// run in reverse so the global setup is undone last.
+pub fn hooks_for(stage: HookStage, game: &Game, config: &AppConfig) -> Vec<Hook> {
+    let global_timeout = if config.hook_timeout > 0 {
+        config.hook_timeout
+    } else {
+        DEFAULT_HOOK_TIMEOUT
+    };
+    let game_timeout = if game.hooks.timeout > 0 {
+        game.hooks.timeout
+    } else {
+        global_timeout
+    };
+
+    let (global_command, game_command) = match stage {
+        HookStage::PreLaunch => (&config.pre_launch_hook, &game.hooks.pre_launch),
+        HookStage::PostExit => (&config.post_exit_hook, &game.hooks.post_exit),
+    };
+    let pre_launch = stage == HookStage::PreLaunch;
+
+    let global = Hook {
+        command: global_command.trim().to_string(),
+        timeout: Duration::from_secs(global_timeout),
+        abort_on_failure: pre_launch && config.hook_abort_on_failure,
+    };
+    let own = Hook {
+        command: game_command.trim().to_string(),
+        timeout: Duration::from_secs(game_timeout),
+        abort_on_failure: pre_launch && game.hooks.abort_on_failure,
+    };
+
+    let hooks = if pre_launch {
+        [global, own]
+    } else {
+        [own, global]
+    };
+    hooks
+        .into_iter()
+        .filter(|hook| !hook.command.is_empty())
+        .collect()
+}
+
+/// Run all hooks of a game for a stage
+///
+/// A failing hook is logged and the next one runs, unless it is marked
+/// `abort_on_failure`, in which case the error is returned.
+pub fn run_hooks(
+    stage: HookStage,
+    game: &Game,
+    config: &AppConfig,
+    env: &BTreeMap<String, String>,
+) -> Result<()> {
+    for hook in hooks_for(stage, game, config) {
+        match run_hook(&hook, stage, game, env) {
+            Ok(()) => {}
+            Err(e) if hook.abort_on_failure => {
+                return Err(e.context(format!("Launch of {} aborted", game.title)));
+            }
+            Err(e) => warn!("{:#}", e),
+        }
+    }
+    Ok(())
+}
+
+/// Run one hook through `sh -c`, killing it once the timeout expires
+pub fn run_hook(
+    hook: &Hook,
+    stage: HookStage,
+    game: &Game,
+    env: &BTreeMap<String, String>,
+) -> Result<()> {
+    info!(
+        "Running {} hook for {}: {}",
+        stage, game.title, hook.command
+    );
+
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c")
+        .arg(&hook.command)
+        .envs(env)
+        .env("FAUGUS_HOOK", stage.to_string())
+        .env("FAUGUS_GAME_ID", &game.gameid)
+        .env("FAUGUS_GAME_TITLE", &game.title)
+        .env("FAUGUS_GAME_PATH", &game.path)
+        .stdin(Stdio::null());
+    if let Some(dir) = game.path.parent().filter(|dir| dir.is_dir()) {
+        cmd.current_dir(dir);
+    }
+    // Own process group, so a timeout also stops whatever the hook started
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+
+    let mut child = cmd
+        .spawn()
+        .with_context(|| format!("Failed to start {} hook: {}", stage, hook.command))?;
+
+    let deadline = Instant::now() + hook.timeout;
+    loop {
+        if let Some(status) = child
+            .try_wait()
+            .with_context(|| format!("Failed to wait for {} hook", stage))?
+        {
+            if status.success() {
+                return Ok(());
+            }
+            bail!("{} hook failed ({}): {}", stage, status, hook.command);
+        }
+
+        if Instant::now() >= deadline {
+            #[cfg(unix)]
+            {
+                use nix::sys::signal::{killpg, Signal};
+                use nix::unistd::Pid as NixPid;
+                let _ = killpg(NixPid::from_raw(child.id() as i32), Signal::SIGKILL);
+            }
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!(
+                "{} hook timed out after {}s: {}",
+                stage,
+                hook.timeout.as_secs(),
+                hook.command
+            );
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hooked_game(pre_launch: &str, post_exit: &str) -> Game {
+        Game {
+            gameid: "hook-game".to_string(),
+            title: "Hook Game".to_string(),
+            hooks: crate::config::GameHooks {
+                pre_launch: pre_launch.to_string(),
+                post_exit: post_exit.to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_hook_order_and_settings() {
+        let mut game = hooked_game("mount-share", "umount-share");
+        game.hooks.timeout = 5;
+        game.hooks.abort_on_failure = true;
+        let config = AppConfig {
+            pre_launch_hook: "start-remapper".to_string(),
+            post_exit_hook: "stop-remapper".to_string(),
+            hook_timeout: 30,
+            ..Default::default()
+        };
+
+        let pre = hooks_for(HookStage::PreLaunch, &game, &config);
+        let commands: Vec<&str> = pre.iter().map(|h| h.command.as_str()).collect();
+        assert_eq!(commands, vec!["start-remapper", "mount-share"]);
+        assert_eq!(pre[0].timeout, Duration::from_secs(30));
+        assert!(!pre[0].abort_on_failure);
+        assert_eq!(pre[1].timeout, Duration::from_secs(5));
+        assert!(pre[1].abort_on_failure);
+
+        let post = hooks_for(HookStage::PostExit, &game, &config);
+        let commands: Vec<&str> = post.iter().map(|h| h.command.as_str()).collect();
+        assert_eq!(commands, vec!["umount-share", "stop-remapper"]);
+        assert!(post.iter().all(|h| !h.abort_on_failure));
+
+        // Blank commands are skipped
+        let game = hooked_game("  ", "");
+        assert!(hooks_for(HookStage::PreLaunch, &game, &AppConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn test_run_hook_exports_environment() {
+        let game = hooked_game(
+            r#"test "$WINEPREFIX" = /tmp/prefix && test "$FAUGUS_GAME_ID" = hook-game && test "$FAUGUS_HOOK" = pre-launch"#,
+            "",
+        );
+        let env = BTreeMap::from([("WINEPREFIX".to_string(), "/tmp/prefix".to_string())]);
+
+        run_hooks(HookStage::PreLaunch, &game, &AppConfig::default(), &env)
+            .expect("hook should see the launch environment");
+    }
+
+    #[test]
+    fn test_failing_hook_aborts_only_when_asked() {
+        let mut game = hooked_game("exit 3", "");
+        let env = BTreeMap::new();
+
+        assert!(run_hooks(HookStage::PreLaunch, &game, &AppConfig::default(), &env).is_ok());
+
+        game.hooks.abort_on_failure = true;
+        let err = run_hooks(HookStage::PreLaunch, &game, &AppConfig::default(), &env)
+            .expect_err("failing hook should abort the launch");
+        assert!(format!("{:#}", err).contains("pre-launch hook failed"));
+    }
+
+    #[test]
+    fn test_hook_timeout() {
+        let game = hooked_game("sleep 10", "");
+        let hook = Hook {
+            command: "sleep 10".to_string(),
+            timeout: Duration::from_millis(200),
+            abort_on_failure: true,
+        };
+
+        let started = Instant::now();
+        let err = run_hook(&hook, HookStage::PreLaunch, &game, &BTreeMap::new())
+            .expect_err("hook should time out");
+        assert!(err.to_string().contains("timed out"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
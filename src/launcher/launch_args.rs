@@ -0,0 +1,199 @@
+// Launch arguments
+// POSIX shell-words parsing of the launch and game arguments
+
+use thiserror::Error;
+
+use crate::config::envar;
+
+/// Placeholder standing for the game command, as in Steam launch options
+pub const COMMAND_PLACEHOLDER: &str = "%command%";
+
+/// Syntax errors in an argument string
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ArgsError {
+    #[error("missing closing {0} quote")]
+    UnterminatedQuote(char),
+
+    #[error("nothing to escape after the final backslash")]
+    TrailingBackslash,
+
+    #[error("{COMMAND_PLACEHOLDER} can only be used once")]
+    RepeatedPlaceholder,
+}
+
+/// Split a string into words following POSIX shell quoting
+///
+/// Single quotes keep everything literally, double quotes only treat `\`
+/// before `$`, `` ` ``, `"`, `\` and newlines as an escape, and an unquoted
+/// backslash escapes any character.
+pub fn split(input: &str) -> Result<Vec<String>, ArgsError> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    // A word has started, even if it is empty like `""`
+    let mut in_word = false;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(ArgsError::UnterminatedQuote('\'')),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('\n') => {}
+                            Some(c @ ('$' | '`' | '"' | '\\')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err(ArgsError::UnterminatedQuote('"')),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(ArgsError::UnterminatedQuote('"')),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                // Line continuation
+                Some('\n') => {}
+                Some(c) => {
+                    in_word = true;
+                    word.push(c);
+                }
+                None => return Err(ArgsError::TrailingBackslash),
+            },
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// Launch arguments split around the game command
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LaunchOptions {
+    /// Leading `VAR=value` assignments
+    pub env: Vec<(String, String)>,
+    /// Command the game runs through (before `%command%`)
+    pub prefix: Vec<String>,
+    /// Arguments passed to the game (after `%command%`)
+    pub suffix: Vec<String>,
+}
+
+/// Parse launch arguments
+///
+/// Leading `VAR=value` words go to the environment. The rest wraps the game
+/// command; with a `%command%` placeholder, the words after it are passed
+/// to the game instead.
+pub fn parse_launch_options(input: &str) -> Result<LaunchOptions, ArgsError> {
+    let mut words = split(input)?.into_iter().peekable();
+    let mut options = LaunchOptions::default();
+
+    while let Some((key, value)) = words.peek().and_then(|word| env_assignment(word)) {
+        options.env.push((key, value));
+        words.next();
+    }
+
+    let mut after_command = false;
+    for word in words {
+        if word == COMMAND_PLACEHOLDER {
+            if after_command {
+                return Err(ArgsError::RepeatedPlaceholder);
+            }
+            after_command = true;
+        } else if after_command {
+            options.suffix.push(word);
+        } else {
+            options.prefix.push(word);
+        }
+    }
+
+    Ok(options)
+}
+
+/// Split a `VAR=value` word, if it is one
+fn env_assignment(word: &str) -> Option<(String, String)> {
+    let (key, value) = word.split_once('=')?;
+    envar::is_valid_env_var_key(key).then(|| (key.to_string(), value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(input: &str) -> Vec<String> {
+        split(input).expect("valid shell words")
+    }
+
+    #[test]
+    fn test_split_quoting() {
+        assert_eq!(
+            words(r#"-config "C:\My Games\x.ini" -skip"#),
+            vec!["-config", r"C:\My Games\x.ini", "-skip"]
+        );
+        assert_eq!(
+            words(r#"'it''s' a\ b "say \"hi\"" """#),
+            vec!["its", "a b", r#"say "hi""#, ""]
+        );
+        assert_eq!(words("  one\\\n two  "), vec!["one", "two"]);
+        assert!(words("   ").is_empty());
+    }
+
+    #[test]
+    fn test_split_errors() {
+        assert_eq!(split("-x 'open"), Err(ArgsError::UnterminatedQuote('\'')));
+        assert_eq!(split(r#"-x "open"#), Err(ArgsError::UnterminatedQuote('"')));
+        assert_eq!(split(r"-x \"), Err(ArgsError::TrailingBackslash));
+    }
+
+    #[test]
+    fn test_launch_options() {
+        let options =
+            parse_launch_options(r#"DXVK_HUD=fps PROTON_LOG="1" gamescope -f %command% -dx11"#)
+                .expect("valid launch options");
+        assert_eq!(
+            options,
+            LaunchOptions {
+                env: vec![
+                    ("DXVK_HUD".to_string(), "fps".to_string()),
+                    ("PROTON_LOG".to_string(), "1".to_string()),
+                ],
+                prefix: vec!["gamescope".to_string(), "-f".to_string()],
+                suffix: vec!["-dx11".to_string()],
+            }
+        );
+
+        // Assignments only count before the command
+        let options = parse_launch_options("strace X=1").expect("valid launch options");
+        assert!(options.env.is_empty());
+        assert_eq!(options.prefix, vec!["strace", "X=1"]);
+
+        assert_eq!(
+            parse_launch_options("%command% %command%"),
+            Err(ArgsError::RepeatedPlaceholder)
+        );
+    }
+}
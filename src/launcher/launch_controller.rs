@@ -1,13 +1,31 @@
 // Game launch controller
 // Manages game launching, process monitoring, and UI state
 
+use futures::SinkExt;
 use iced::Task;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use tracing::{error, info};
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
 
 use crate::config::Game;
-use crate::launcher::game_launcher::{GameLauncher, GameProcess};
+use crate::launcher::game_launcher::{GameLauncher, GameProcess, LaunchError, StopOutcome};
+use crate::launcher::process_tree;
+use crate::launcher::running_store::RunningStore;
+use crate::launcher::umu_progress::{self, LogTail, UmuProgress};
+
+/// How often the session log is read for progress
+const PROGRESS_POLL: Duration = Duration::from_millis(500);
+
+/// Progress reads between checks that the game is still running
+const PROGRESS_POLLS_PER_CHECK: u32 = 4;
+
+/// A launch without any setup output within this time is not followed further
+const QUIET_LAUNCH: Duration = Duration::from_secs(30);
+
+/// Setup output may pause this long (large downloads) before following stops
+const PROGRESS_TIMEOUT: Duration = Duration::from_secs(600);
 
 /// Launch status for a game
 #[derive(Debug, Clone, PartialEq)]
@@ -15,14 +33,18 @@ pub enum LaunchStatus {
     NotRunning,
     Launching,
     Running(GameProcess),
+    /// Asked to exit, waiting for its processes to end
+    Stopping(GameProcess),
     Error(String),
 }
 
 /// Game launch controller
 #[derive(Debug, Clone)]
 pub struct GameLaunchController {
-    /// Currently running games
+    /// Currently running games, by game ID
     running_games: Arc<Mutex<HashMap<String, LaunchStatus>>>,
+    /// Set while a background process check is in flight
+    checking: Arc<AtomicBool>,
 }
 
 impl GameLaunchController {
@@ -30,25 +52,78 @@ impl GameLaunchController {
     pub fn new() -> Self {
         Self {
             running_games: Arc::new(Mutex::new(HashMap::new())),
+            checking: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Take over the games saved in running_games.json that are still running
+    ///
+    /// Entries whose process is gone, or was replaced by an unrelated one,
+    /// are dropped from the file. Games followed by another running launcher
+    /// instance (faugus-run) are left to it.
+    pub fn restore_running_games(&self) -> Vec<GameProcess> {
+        if GameLauncher::load_processes().is_empty() {
+            return Vec::new();
+        }
+
+        let own_pid = std::process::id();
+        let restored = RunningStore::open().update(|saved| {
+            let processes = process_tree::snapshot();
+            let mut kept = Vec::new();
+            let mut adopted = Vec::new();
+            for mut process in std::mem::take(saved) {
+                let owner_alive = process.owner_pid != 0
+                    && process.owner_pid != own_pid
+                    && processes.iter().any(|p| p.pid == process.owner_pid);
+                if owner_alive {
+                    kept.push(process);
+                } else if !process.launching && process.adopt(&processes) {
+                    info!(
+                        "Game still running: {} (PID {})",
+                        process.game_title, process.main_pid
+                    );
+                    process.owner_pid = own_pid;
+                    adopted.push(process);
+                } else {
+                    info!("Discarding stale process of {}", process.game_title);
+                }
+            }
+
+            kept.extend(adopted.iter().cloned());
+            *saved = kept;
+            adopted
+        });
+        let adopted = match restored {
+            Ok(adopted) => adopted,
+            Err(e) => {
+                error!("Failed to restore running games: {:#}", e);
+                return Vec::new();
+            }
+        };
+        let mut games = self.running_games.lock().unwrap_or_else(|e| e.into_inner());
+        for process in &adopted {
+            games.insert(
+                process.gameid.clone(),
+                LaunchStatus::Running(process.clone()),
+            );
         }
+        adopted
     }
 
     /// Get the launch status of a game
-    pub fn get_status(&self, title: &str) -> LaunchStatus {
+    pub fn get_status(&self, gameid: &str) -> LaunchStatus {
         let games = self.running_games.lock().unwrap_or_else(|e| e.into_inner());
         games
-            .get(title)
+            .get(gameid)
             .cloned()
             .unwrap_or(LaunchStatus::NotRunning)
     }
 
     /// Check if a game is currently running
-    /// TODO: Use for UI status indicators, double-launch prevention
-    #[allow(dead_code)]
-    pub fn is_running(&self, title: &str) -> bool {
+    pub fn is_running(&self, gameid: &str) -> bool {
         matches!(
-            self.get_status(title),
-            LaunchStatus::Running(_) | LaunchStatus::Launching
+            self.get_status(gameid),
+            LaunchStatus::Running(_) | LaunchStatus::Launching | LaunchStatus::Stopping(_)
         )
     }
 
@@ -56,13 +131,14 @@ impl GameLaunchController {
     pub fn launch_game(&self, game: Game) -> Task<LaunchMessage> {
         info!("Preparing to launch game: {}", game.title);
 
+        let gameid = game.gameid.clone();
         let title = game.title.clone();
         let running_games = self.running_games.clone();
 
         // Set status to launching
         {
             let mut games = running_games.lock().unwrap_or_else(|e| e.into_inner());
-            games.insert(title.clone(), LaunchStatus::Launching);
+            games.insert(gameid.clone(), LaunchStatus::Launching);
         }
 
         // Spawn async task to launch the game
@@ -73,23 +149,18 @@ impl GameLaunchController {
                     Ok(process) => {
                         info!("Game {} launched with PID: {}", title, process.main_pid);
 
-                        // Save process info
-                        if let Err(e) = GameLauncher::save_process(&process) {
-                            error!("Failed to save process info: {}", e);
-                        }
-
                         // Update latest games
-                        if let Err(e) = GameLauncher::update_latest_games(&title) {
+                        if let Err(e) = GameLauncher::update_latest_games(&gameid) {
                             error!("Failed to update latest games: {}", e);
                         }
 
                         // Update status
                         {
                             let mut games = running_games.lock().unwrap_or_else(|e| e.into_inner());
-                            games.insert(title.clone(), LaunchStatus::Running(process.clone()));
+                            games.insert(gameid.clone(), LaunchStatus::Running(process.clone()));
                         }
 
-                        LaunchMessage::Launched(title.clone(), process)
+                        LaunchMessage::Launched(gameid.clone(), process)
                     }
                     Err(e) => {
                         error!("Failed to launch game {}: {}", title, e);
@@ -97,69 +168,169 @@ impl GameLaunchController {
                         // Update status
                         {
                             let mut games = running_games.lock().unwrap_or_else(|e| e.into_inner());
-                            games.insert(title.clone(), LaunchStatus::Error(e.to_string()));
+                            games.insert(gameid.clone(), LaunchStatus::Error(e.to_string()));
                         }
 
-                        LaunchMessage::LaunchFailed(title.clone(), e.to_string())
+                        LaunchMessage::LaunchFailed(gameid.clone(), LaunchError::from_error(&e))
                     }
                 }
             },
             |msg| msg,
         )
+        .then(|msg| match &msg {
+            LaunchMessage::Launched(gameid, process) => {
+                let watch = Self::watch_progress(gameid.clone(), process.clone());
+                Task::done(msg).chain(watch)
+            }
+            _ => Task::done(msg),
+        })
     }
 
-    /// Terminate a running game
-    pub fn terminate_game(&self, title: &str) -> Result<(), String> {
-        let status = self.get_status(title);
+    /// Follow umu-run's output in the session log until the game has started
+    ///
+    /// A first launch may download and unpack gigabytes of runtime and Proton
+    /// before the game shows up; each recognized step is reported as progress.
+    fn watch_progress(gameid: String, process: GameProcess) -> Task<LaunchMessage> {
+        let Some(log_file) = process.log_file.clone() else {
+            return Task::none();
+        };
+
+        Task::stream(iced::stream::channel(16, move |mut output| async move {
+            let mut tail = match LogTail::open(&log_file) {
+                Ok(tail) => tail,
+                Err(e) => {
+                    warn!(
+                        "Not following launch progress of {}: {:#}",
+                        process.game_title, e
+                    );
+                    return;
+                }
+            };
+
+            let started = Instant::now();
+            let mut last_progress: Option<Instant> = None;
+            let mut polls = 0;
+            loop {
+                tokio::time::sleep(PROGRESS_POLL).await;
+
+                for line in tail.read_lines() {
+                    let Some(progress) = umu_progress::parse_line(&line) else {
+                        continue;
+                    };
+                    let done = progress == UmuProgress::Started;
+                    let _ = output
+                        .send(LaunchMessage::Progress(gameid.clone(), progress))
+                        .await;
+                    if done {
+                        return;
+                    }
+                    last_progress = Some(Instant::now());
+                }
+
+                polls += 1;
+                let exited = polls % PROGRESS_POLLS_PER_CHECK == 0
+                    && !GameLauncher::is_process_running(process.main_pid);
+                let idle = match last_progress {
+                    None => started.elapsed() > QUIET_LAUNCH,
+                    Some(at) => at.elapsed() > PROGRESS_TIMEOUT,
+                };
+                if exited || idle {
+                    let _ = output
+                        .send(LaunchMessage::ProgressFinished(gameid.clone()))
+                        .await;
+                    return;
+                }
+            }
+        }))
+    }
 
-        match status {
-            LaunchStatus::Running(process) => {
-                info!("Terminating game: {}", title);
+    /// Stop a running game, giving it `grace` to exit before it is killed
+    pub fn stop_game(&self, gameid: &str, grace: Duration) -> Task<LaunchMessage> {
+        let process = {
+            let mut games = self.running_games.lock().unwrap_or_else(|e| e.into_inner());
+            match games.get(gameid) {
+                Some(LaunchStatus::Running(process)) => {
+                    let process = process.clone();
+                    games.insert(gameid.to_string(), LaunchStatus::Stopping(process.clone()));
+                    process
+                }
+                Some(LaunchStatus::Stopping(process)) => {
+                    info!("{} is already stopping", process.game_title);
+                    return Task::none();
+                }
+                _ => {
+                    warn!("Not stopping {}: not running", gameid);
+                    return Task::none();
+                }
+            }
+        };
+        info!("Stopping game: {}", process.game_title);
 
-                // Terminate the main process
-                GameLauncher::terminate(process.main_pid)
-                    .map_err(|e| format!("Failed to terminate game: {}", e))?;
+        let gameid = gameid.to_string();
+        let running_games = self.running_games.clone();
+        Task::perform(
+            async move {
+                let stopping = process.clone();
+                let outcome =
+                    tokio::task::spawn_blocking(move || GameLauncher::stop(&stopping, grace)).await;
 
-                // Remove from running games
                 {
-                    let mut games = self.running_games.lock().unwrap_or_else(|e| e.into_inner());
-                    games.remove(title);
+                    let mut games = running_games.lock().unwrap_or_else(|e| e.into_inner());
+                    games.remove(&gameid);
                 }
+                let _ = GameLauncher::remove_process(&gameid);
+                Self::finish(&process);
 
-                // Remove process info
-                let _ = GameLauncher::remove_process(title);
-
-                Ok(())
-            }
-            LaunchStatus::Launching => Err("Game is still launching, please wait".to_string()),
-            LaunchStatus::NotRunning => Err("Game is not running".to_string()),
-            LaunchStatus::Error(_) => Err("Game launch failed".to_string()),
-        }
+                match outcome {
+                    Ok(outcome) => LaunchMessage::Stopped(gameid, outcome),
+                    Err(e) => {
+                        error!("Failed to stop {}: {}", process.game_title, e);
+                        LaunchMessage::StopFailed(gameid, e.to_string())
+                    }
+                }
+            },
+            |msg| msg,
+        )
     }
 
-    /// Terminate all running games
-    pub fn terminate_all(&self) {
-        let running = self.get_running_games();
-        for (title, _) in running {
-            let _ = self.terminate_game(&title);
-        }
-
-        // Force kill any remaining wine processes
-        GameLauncher::kill_all_wine_processes();
+    /// Stop all running games
+    pub fn stop_all(&self, grace: Duration) -> Task<LaunchMessage> {
+        Task::batch(
+            self.get_running_games()
+                .into_iter()
+                .map(|(gameid, _)| self.stop_game(&gameid, grace)),
+        )
     }
 
     /// Update game status (call when process exits)
-    pub fn on_process_exited(&self, title: &str) {
-        info!("Game process exited: {}", title);
+    pub fn on_process_exited(&self, gameid: &str) {
+        Self::process_exited(&self.running_games, gameid);
+    }
 
+    /// Forget an exited game and finish its session
+    fn process_exited(running_games: &Mutex<HashMap<String, LaunchStatus>>, gameid: &str) {
         // Remove from running games
-        {
-            let mut games = self.running_games.lock().unwrap_or_else(|e| e.into_inner());
-            games.remove(title);
-        }
+        let status = {
+            let mut games = running_games.lock().unwrap_or_else(|e| e.into_inner());
+            games.remove(gameid)
+        };
 
         // Remove process info
-        let _ = GameLauncher::remove_process(title);
+        let _ = GameLauncher::remove_process(gameid);
+
+        if let Some(LaunchStatus::Running(process)) = status {
+            info!("Game process exited: {}", process.game_title);
+            Self::finish(&process);
+        }
+    }
+
+    /// Account the session of an ended game and run its post-exit hooks
+    fn finish(process: &GameProcess) {
+        GameLauncher::finish_session(process);
+
+        // Hooks can take a while (unmounting, backups), don't block the UI
+        let gameid = process.gameid.clone();
+        std::thread::spawn(move || GameLauncher::run_post_exit_hooks(&gameid));
     }
 
     /// Get all running games
@@ -168,9 +339,9 @@ impl GameLaunchController {
 
         games
             .iter()
-            .filter_map(|(title, status)| {
+            .filter_map(|(gameid, status)| {
                 if let LaunchStatus::Running(process) = status {
-                    Some((title.clone(), process.clone()))
+                    Some((gameid.clone(), process.clone()))
                 } else {
                     None
                 }
@@ -179,27 +350,89 @@ impl GameLaunchController {
     }
 
     /// Check for dead processes and update status
-    pub fn check_processes(&self) -> Vec<String> {
-        let games = self.running_games.lock().unwrap_or_else(|e| e.into_inner());
-        let mut dead_games = Vec::new();
-
-        for (title, status) in games.iter() {
-            if let LaunchStatus::Running(process) = status {
-                if !GameLauncher::is_process_running(process.main_pid) {
-                    dead_games.push(title.clone());
-                }
-            }
+    ///
+    /// The process snapshot is taken in the background, and only while a
+    /// game is running. Games that ended are reported as `ProcessExited`.
+    pub fn check_processes(&self) -> Task<LaunchMessage> {
+        let running: Vec<GameProcess> = {
+            let games = self.running_games.lock().unwrap_or_else(|e| e.into_inner());
+            games
+                .values()
+                .filter_map(|status| match status {
+                    LaunchStatus::Running(process) => Some(process.clone()),
+                    _ => None,
+                })
+                .collect()
+        };
+        // Skip the tick while the previous check is still running
+        if running.is_empty() || self.checking.swap(true, Ordering::AcqRel) {
+            return Task::none();
         }
 
-        // Drop lock before updating
-        drop(games);
+        let running_games = self.running_games.clone();
+        let checking = self.checking.clone();
+        Task::perform(
+            async move {
+                let checked = tokio::task::spawn_blocking(move || {
+                    let processes = process_tree::snapshot();
+                    running
+                        .into_iter()
+                        .map(|mut process| {
+                            let alive = process.refresh(&processes);
+                            (process, alive)
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .await
+                .unwrap_or_else(|e| {
+                    error!("Process check failed: {}", e);
+                    Vec::new()
+                });
+
+                let mut dead_games = Vec::new();
+                let mut moved = Vec::new();
+                {
+                    let mut games = running_games.lock().unwrap_or_else(|e| e.into_inner());
+                    for (process, alive) in checked {
+                        // The game may have been stopped or relaunched meanwhile
+                        let Some(LaunchStatus::Running(current)) = games.get_mut(&process.gameid)
+                        else {
+                            continue;
+                        };
+                        if current.started_at != process.started_at {
+                            continue;
+                        }
+                        if !alive {
+                            dead_games.push((process.gameid.clone(), current.main_pid));
+                        } else if current.main_pid != process.main_pid {
+                            *current = process.clone();
+                            moved.push(process);
+                        }
+                    }
+                }
 
-        // Remove dead games
-        for title in &dead_games {
-            self.on_process_exited(title);
-        }
+                // Keep the game's own PID for the next launcher start
+                for process in &moved {
+                    if let Err(e) = GameLauncher::save_process(process) {
+                        error!("Failed to save process info: {}", e);
+                    }
+                }
+                for (gameid, _) in &dead_games {
+                    Self::process_exited(&running_games, gameid);
+                }
 
-        dead_games
+                checking.store(false, Ordering::Release);
+                dead_games
+            },
+            |dead_games| dead_games,
+        )
+        .then(|dead_games| {
+            Task::batch(
+                dead_games
+                    .into_iter()
+                    .map(|(gameid, pid)| Task::done(LaunchMessage::ProcessExited(gameid, pid))),
+            )
+        })
     }
 }
 
@@ -209,15 +442,21 @@ impl Default for GameLaunchController {
     }
 }
 
-/// Messages from the launch controller
+/// Messages from the launch controller, each naming the game by its ID
 #[derive(Debug, Clone)]
 pub enum LaunchMessage {
     /// Game launched successfully
     Launched(String, GameProcess),
     /// Game launch failed
-    LaunchFailed(String, String),
+    LaunchFailed(String, LaunchError),
     /// Process exited
     ProcessExited(String, u32),
-    /// Launch progress update
-    Progress(String, String),
+    /// Setup step reported while the game starts
+    Progress(String, UmuProgress),
+    /// No more progress will be reported for the launch
+    ProgressFinished(String),
+    /// Game stopped on request
+    Stopped(String, StopOutcome),
+    /// Stopping the game failed
+    StopFailed(String, String),
 }
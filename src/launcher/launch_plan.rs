@@ -0,0 +1,206 @@
+// Launch plan
+// Everything a game launch runs, computed before anything is started
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// What launching a game runs: the command, its environment and files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaunchPlan {
+    /// Game the plan was made for
+    pub title: String,
+    pub gameid: String,
+    /// Program started, the outermost wrapper or umu-run
+    pub program: String,
+    /// Arguments following the program
+    pub args: Vec<String>,
+    /// Variables set on top of the launcher's own environment
+    pub env: BTreeMap<String, String>,
+    /// Directory the game is started from, inherited from the launcher
+    pub working_dir: PathBuf,
+    /// Wrappers the command runs through, outermost first
+    pub wrappers: Vec<String>,
+    /// Batch file written into the prefix for the additional application
+    pub batch_file: Option<(PathBuf, String)>,
+}
+
+impl LaunchPlan {
+    /// Program followed by its arguments
+    pub fn argv(&self) -> Vec<String> {
+        let mut argv = vec![self.program.clone()];
+        argv.extend(self.args.iter().cloned());
+        argv
+    }
+
+    /// Single shell command line: variable assignments, then the command
+    pub fn command_line(&self) -> String {
+        self.env
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, quote(value)))
+            .chain(self.argv().iter().map(|arg| quote(arg)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Shell script reproducing the launch outside the launcher
+    pub fn shell_script(&self) -> String {
+        let mut script = String::from("#!/bin/sh\n");
+        script.push_str(&format!(
+            "# {} ({}), exported from Faugus Launcher\n",
+            self.title.replace('\n', " "),
+            self.gameid
+        ));
+        if !self.wrappers.is_empty() {
+            script.push_str(&format!("# Wrappers: {}\n", self.wrappers.join(", ")));
+        }
+        script.push('\n');
+
+        script.push_str(&format!(
+            "cd {} || exit 1\n",
+            quote(&self.working_dir.to_string_lossy())
+        ));
+        if let Some((path, content)) = &self.batch_file {
+            // printf keeps the CRLF line endings Windows expects
+            let lines: Vec<String> = content.lines().map(quote).collect();
+            script.push_str(&format!(
+                "printf '%s\\r\\n' {} > {}\n",
+                lines.join(" "),
+                quote(&path.to_string_lossy())
+            ));
+        }
+        script.push('\n');
+
+        for (key, value) in &self.env {
+            script.push_str(&format!("export {}={}\n", key, quote(value)));
+        }
+        script.push('\n');
+
+        let argv: Vec<String> = self.argv().iter().map(|arg| quote(arg)).collect();
+        script.push_str(&format!("exec {}\n", argv.join(" \\\n    ")));
+        script
+    }
+
+    /// Write the shell script to `path` and make it executable
+    pub fn export_script(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.shell_script())
+            .with_context(|| format!("Failed to write launch script: {:?}", path))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+                .with_context(|| format!("Failed to make {:?} executable", path))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for LaunchPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Game: {} ({})", self.title, self.gameid)?;
+        writeln!(f, "Working directory: {}", self.working_dir.display())?;
+        if !self.wrappers.is_empty() {
+            writeln!(f, "Wrappers: {}", self.wrappers.join(", "))?;
+        }
+        if let Some((path, _)) = &self.batch_file {
+            writeln!(f, "Batch file: {}", path.display())?;
+        }
+
+        writeln!(f, "Environment:")?;
+        for (key, value) in &self.env {
+            writeln!(f, "  {}={}", key, quote(value))?;
+        }
+
+        writeln!(f, "Command:")?;
+        writeln!(f, "  {}", quote(&self.program))?;
+        for arg in &self.args {
+            writeln!(f, "    {}", quote(arg))?;
+        }
+        Ok(())
+    }
+}
+
+/// Quote a word for POSIX shells, leaving plain words as they are
+pub fn quote(word: &str) -> String {
+    let plain = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c));
+    if plain {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::launcher::launch_args;
+
+    fn plan() -> LaunchPlan {
+        LaunchPlan {
+            title: "Test Game".to_string(),
+            gameid: "test-game".to_string(),
+            program: "/usr/bin/gamemoderun".to_string(),
+            args: vec![
+                "/usr/bin/umu-run".to_string(),
+                "/games/My Game/game.exe".to_string(),
+                "-name=it's".to_string(),
+            ],
+            env: BTreeMap::from([
+                ("GAMEID".to_string(), "test-game".to_string()),
+                (
+                    "WINEPREFIX".to_string(),
+                    "/home/user/Faugus/test game".to_string(),
+                ),
+            ]),
+            working_dir: PathBuf::from("/games/My Game"),
+            wrappers: vec!["GameMode".to_string()],
+            batch_file: None,
+        }
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("/usr/bin/umu-run"), "/usr/bin/umu-run");
+        assert_eq!(quote(""), "''");
+        assert_eq!(quote("a b"), "'a b'");
+        assert_eq!(quote("it's"), r"'it'\''s'");
+        assert_eq!(quote("$HOME"), "'$HOME'");
+    }
+
+    #[test]
+    fn test_command_line_round_trips() {
+        let plan = plan();
+        let words = launch_args::split(&plan.command_line()).expect("valid shell words");
+        let (env, argv) = words.split_at(plan.env.len());
+        assert_eq!(
+            env,
+            ["GAMEID=test-game", "WINEPREFIX=/home/user/Faugus/test game"]
+        );
+        assert_eq!(argv, plan.argv().as_slice());
+    }
+
+    #[test]
+    fn test_shell_script() {
+        let mut plan = plan();
+        plan.batch_file = Some((
+            PathBuf::from("/prefix/drive_c/faugus-addapp-test-game.bat"),
+            "@echo off\r\nstart \"\" \"Z:\\app.exe\"\r\n".to_string(),
+        ));
+        let script = plan.shell_script();
+
+        assert!(script.starts_with("#!/bin/sh\n# Test Game (test-game)"));
+        assert!(script.contains("cd '/games/My Game' || exit 1\n"));
+        assert!(script.contains(
+            "printf '%s\\r\\n' '@echo off' 'start \"\" \"Z:\\app.exe\"' > /prefix/drive_c/faugus-addapp-test-game.bat\n"
+        ));
+        assert!(script.contains("export WINEPREFIX='/home/user/Faugus/test game'\n"));
+        assert!(script.ends_with(
+            "exec /usr/bin/gamemoderun \\\n    /usr/bin/umu-run \\\n    '/games/My Game/game.exe' \\\n    '-name=it'\\''s'\n"
+        ));
+    }
+}
@@ -1,8 +1,17 @@
 // Launcher module
 // Handles game launching and process management
 
+pub mod addapp;
 pub mod game_launcher;
+pub mod game_logs;
+pub mod hooks;
+pub mod launch_args;
 mod launch_controller;
+pub mod launch_plan;
+pub mod process_tree;
+pub mod running_store;
+pub mod umu_progress;
 pub mod wine_tools;
+pub mod wrappers;
 
 pub use launch_controller::{GameLaunchController, LaunchMessage, LaunchStatus};
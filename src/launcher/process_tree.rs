@@ -0,0 +1,262 @@
+// Process tree
+// Finds the real game process among the processes started by umu-run
+
+use std::collections::HashMap;
+use std::path::Path;
+use sysinfo::{ProcessRefreshKind, System, UpdateKind};
+
+/// Linux limits process names to 15 characters
+const COMM_LENGTH: usize = 15;
+
+/// What is needed of a process to recognize a game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub parent: Option<u32>,
+    /// Process name, truncated by the kernel
+    pub name: String,
+    pub cmd: Vec<String>,
+    /// `WINEPREFIX` of the process, if set
+    pub wineprefix: Option<String>,
+}
+
+/// Snapshot of the running processes
+pub fn snapshot() -> Vec<ProcessInfo> {
+    let mut sys = System::new();
+    sys.refresh_processes_specifics(
+        ProcessRefreshKind::new()
+            .with_cmd(UpdateKind::OnlyIfNotSet)
+            .with_environ(UpdateKind::OnlyIfNotSet),
+    );
+
+    sys.processes()
+        .iter()
+        .map(|(pid, process)| ProcessInfo {
+            pid: pid.as_u32(),
+            parent: process.parent().map(|parent| parent.as_u32()),
+            name: process.name().to_string(),
+            cmd: process.cmd().to_vec(),
+            wineprefix: process
+                .environ()
+                .iter()
+                .find_map(|var| var.strip_prefix("WINEPREFIX="))
+                .map(str::to_string),
+        })
+        .collect()
+}
+
+/// Check if a process runs the given executable
+///
+/// Wine processes show the Windows path of the executable as their command
+/// line, so both `/` and `\` separate its components.
+fn runs_executable(process: &ProcessInfo, exe_name: &str) -> bool {
+    let command_name = process
+        .cmd
+        .first()
+        .and_then(|arg0| arg0.rsplit(['/', '\\']).next())
+        .unwrap_or_default();
+    if command_name.eq_ignore_ascii_case(exe_name) {
+        return true;
+    }
+
+    // The kernel name is cut short for long executable names
+    let name = process.name.to_lowercase();
+    let exe_name = exe_name.to_lowercase();
+    if name.chars().count() >= COMM_LENGTH {
+        exe_name.starts_with(&name)
+    } else {
+        name == exe_name
+    }
+}
+
+/// Check if `pid` is `ancestor` or one of its descendants
+pub fn is_descendant(processes: &HashMap<u32, &ProcessInfo>, pid: u32, ancestor: u32) -> bool {
+    let mut current = Some(pid);
+    // Bounded walk, in case parents form a loop in a racy snapshot
+    for _ in 0..processes.len() + 1 {
+        match current {
+            Some(pid) if pid == ancestor => return true,
+            Some(pid) => current = processes.get(&pid).and_then(|p| p.parent),
+            None => return false,
+        }
+    }
+    false
+}
+
+/// Check if a process runs in the given Wine prefix
+pub fn in_prefix(process: &ProcessInfo, prefix: &Path) -> bool {
+    process
+        .wineprefix
+        .as_deref()
+        .is_some_and(|wineprefix| Path::new(wineprefix) == prefix)
+}
+
+/// Processes belonging to a game: the trees under `roots`
+///
+/// Other processes in the game's prefix may belong to another game sharing
+/// it, so they are left to the prefix's wineserver.
+pub fn game_processes(processes: &[ProcessInfo], roots: &[u32]) -> Vec<u32> {
+    let by_pid: HashMap<u32, &ProcessInfo> = processes.iter().map(|p| (p.pid, p)).collect();
+    let own_pid = std::process::id();
+
+    processes
+        .iter()
+        .filter(|process| process.pid != own_pid)
+        .filter(|process| {
+            roots
+                .iter()
+                .any(|&root| is_descendant(&by_pid, process.pid, root))
+        })
+        .map(|process| process.pid)
+        .collect()
+}
+
+/// Check if a process is the game: it runs `exe`, in `prefix` for Wine games
+pub fn is_game_process(process: &ProcessInfo, exe: &Path, prefix: Option<&Path>) -> bool {
+    let Some(exe_name) = exe.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    runs_executable(process, exe_name) && prefix.is_none_or(|prefix| in_prefix(process, prefix))
+}
+
+/// Check if a process is the launcher command of a game
+///
+/// Its command line, wrappers included, runs umu-run or the game itself.
+pub fn is_launcher_of(process: &ProcessInfo, exe: &Path) -> bool {
+    let exe_name = exe.file_name().and_then(|name| name.to_str());
+    process.cmd.iter().any(|arg| {
+        let name = arg.rsplit(['/', '\\']).next().unwrap_or_default();
+        name == "umu-run" || exe_name.is_some_and(|exe_name| name.eq_ignore_ascii_case(exe_name))
+    })
+}
+
+/// Find the process of a game
+///
+/// It runs `exe`, and for Wine games it belongs to `prefix`. Processes
+/// started by the launcher process are preferred over unrelated matches.
+pub fn find_game_pid(
+    processes: &[ProcessInfo],
+    exe: &Path,
+    prefix: Option<&Path>,
+    launcher_pid: Option<u32>,
+) -> Option<u32> {
+    let exe_name = exe.file_name()?.to_str()?;
+    let by_pid: HashMap<u32, &ProcessInfo> = processes.iter().map(|p| (p.pid, p)).collect();
+
+    let mut candidates: Vec<&ProcessInfo> = processes
+        .iter()
+        .filter(|process| Some(process.pid) != launcher_pid)
+        .filter(|process| runs_executable(process, exe_name))
+        .filter(|process| prefix.is_none_or(|prefix| in_prefix(process, prefix)))
+        .collect();
+
+    // Descendants of the launcher first, then the oldest (lowest) PID
+    candidates.sort_by_key(|process| {
+        let related =
+            launcher_pid.is_some_and(|launcher| is_descendant(&by_pid, process.pid, launcher));
+        (!related, process.pid)
+    });
+    candidates.first().map(|process| process.pid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, parent: u32, name: &str, cmd: &str, prefix: Option<&str>) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            parent: Some(parent),
+            name: name.to_string(),
+            cmd: vec![cmd.to_string()],
+            wineprefix: prefix.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_find_game_pid() {
+        let processes = vec![
+            process(100, 1, "umu-run", "/usr/bin/umu-run", None),
+            process(110, 100, "pv-adverb", "pv-adverb", Some("/prefixes/game")),
+            process(
+                120,
+                110,
+                "MyVeryLongGameN",
+                "C:\\Games\\MyVeryLongGameName.exe",
+                Some("/prefixes/game"),
+            ),
+            // Same executable in another prefix
+            process(
+                90,
+                1,
+                "MyVeryLongGameN",
+                "Z:\\other\\MyVeryLongGameName.exe",
+                Some("/prefixes/other"),
+            ),
+        ];
+        let exe = Path::new("/games/MyVeryLongGameName.exe");
+
+        assert_eq!(
+            find_game_pid(
+                &processes,
+                exe,
+                Some(Path::new("/prefixes/game")),
+                Some(100)
+            ),
+            Some(120)
+        );
+        assert_eq!(
+            find_game_pid(
+                &processes,
+                exe,
+                Some(Path::new("/prefixes/none")),
+                Some(100)
+            ),
+            None
+        );
+        // Native games match by executable alone, launcher descendants first
+        assert_eq!(find_game_pid(&processes, exe, None, Some(100)), Some(120));
+        assert_eq!(find_game_pid(&processes, exe, None, None), Some(90));
+    }
+
+    #[test]
+    fn test_game_processes() {
+        let processes = vec![
+            process(100, 1, "umu-run", "/usr/bin/umu-run", None),
+            process(110, 100, "game.exe", "Z:\\game.exe", Some("/prefixes/game")),
+            // Another game sharing the prefix
+            process(50, 1, "other.exe", "Z:\\other.exe", Some("/prefixes/game")),
+            process(70, 1, "bash", "bash", None),
+        ];
+
+        let mut pids = game_processes(&processes, &[100]);
+        pids.sort();
+        assert_eq!(pids, vec![100, 110]);
+        assert_eq!(game_processes(&processes, &[110]), vec![110]);
+    }
+
+    #[test]
+    fn test_launcher_of() {
+        let exe = Path::new("/games/game.exe");
+        let mut launcher = process(100, 1, "gamescope", "/usr/bin/gamescope", None);
+        launcher
+            .cmd
+            .extend(["--", "/usr/bin/umu-run", "/games/game.exe"].map(String::from));
+        assert!(is_launcher_of(&launcher, exe));
+
+        // A reused PID
+        let other = process(100, 1, "bash", "/usr/bin/bash", None);
+        assert!(!is_launcher_of(&other, exe));
+        assert!(!is_game_process(&other, exe, None));
+    }
+
+    #[test]
+    fn test_runs_executable() {
+        let game = process(1, 0, "game.exe", "Z:\\games\\Game.exe", None);
+        assert!(runs_executable(&game, "game.exe"));
+        assert!(!runs_executable(&game, "game2.exe"));
+
+        let short = process(1, 0, "gam", "gam", None);
+        assert!(!runs_executable(&short, "game.exe"));
+    }
+}
@@ -0,0 +1,152 @@
+// Running games store
+// Locked, crash-safe persistence for running_games.json
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+use tracing::warn;
+
+use crate::config::locked_file::LockedFile;
+use crate::config::paths::Paths;
+use crate::launcher::game_launcher::GameProcess;
+
+/// Persistent store for the games followed by launcher instances
+///
+/// The GUI and every `faugus-run` write this file. Each access takes an
+/// advisory lock on a sibling `.lock` file, and writes go through a
+/// temporary file renamed over running_games.json, so concurrent updates
+/// never lose each other's entries.
+#[derive(Debug, Clone)]
+pub struct RunningStore {
+    file: LockedFile,
+}
+
+impl RunningStore {
+    /// Create a store backed by the given running_games.json path
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            file: LockedFile::new(path),
+        }
+    }
+
+    /// Open the store at the default running_games.json location
+    pub fn open() -> Self {
+        Self::new(Paths::running_games_json())
+    }
+
+    /// Load the saved processes under a shared lock
+    ///
+    /// A missing or unreadable file holds no processes.
+    pub fn load(&self) -> Vec<GameProcess> {
+        match self.file.lock(false) {
+            Ok(_lock) => self.read_locked(),
+            Err(e) => {
+                warn!("{:#}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Locked read-modify-write of the saved processes
+    ///
+    /// The closure sees the current processes; whatever it leaves in the
+    /// vector is written back before the lock is released.
+    pub fn update<T>(&self, f: impl FnOnce(&mut Vec<GameProcess>) -> T) -> Result<T> {
+        let _lock = self.file.lock(true)?;
+        let mut processes = self.read_locked();
+        let result = f(&mut processes);
+        self.write_locked(&processes)?;
+        Ok(result)
+    }
+
+    /// Read running_games.json
+    fn read_locked(&self) -> Vec<GameProcess> {
+        let content = match fs::read_to_string(self.file.path()) {
+            Ok(content) => content,
+            Err(_) => return Vec::new(),
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            warn!("Ignoring unreadable {:?}: {}", self.file.path(), e);
+            Vec::new()
+        })
+    }
+
+    /// Write running_games.json through a temp file and atomic rename
+    fn write_locked(&self, processes: &[GameProcess]) -> Result<()> {
+        let content = serde_json::to_string_pretty(processes)
+            .with_context(|| "Failed to serialize running games")?;
+
+        self.file.write_atomic(content.as_bytes())
+    }
+}
+
+impl Default for RunningStore {
+    fn default() -> Self {
+        Self::open()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_support::TempDir;
+
+    fn temp_store() -> (TempDir, RunningStore) {
+        let dir = TempDir::new("faugus-running");
+        let store = RunningStore::new(dir.path().join("running_games.json"));
+        (dir, store)
+    }
+
+    fn process(gameid: &str) -> GameProcess {
+        GameProcess {
+            game_title: gameid.to_string(),
+            main_pid: 1,
+            umu_pid: None,
+            gameid: gameid.to_string(),
+            started_at: 0,
+            addapp_bat: None,
+            log_file: None,
+            game_path: PathBuf::new(),
+            prefix: None,
+            owner_pid: 0,
+            launching: false,
+        }
+    }
+
+    #[test]
+    fn test_concurrent_updates_keep_every_entry() {
+        let (_dir, store) = temp_store();
+        let writers: Vec<_> = (0..8)
+            .map(|n| {
+                let store = store.clone();
+                std::thread::spawn(move || {
+                    store
+                        .update(|processes| processes.push(process(&format!("game-{}", n))))
+                        .expect("Update should succeed");
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().expect("Writer should not panic");
+        }
+
+        assert_eq!(store.load().len(), 8);
+        assert!(
+            !store.file.sibling(".tmp").exists(),
+            "temp file should be renamed"
+        );
+    }
+
+    #[test]
+    fn test_unreadable_file_is_empty() {
+        let (_dir, store) = temp_store();
+        assert!(store.load().is_empty());
+
+        fs::write(store.file.path(), "[{ truncated").expect("Failed to write file");
+        assert!(store.load().is_empty());
+        store
+            .update(|processes| processes.push(process("a")))
+            .expect("Update should succeed");
+        assert_eq!(store.load(), vec![process("a")]);
+    }
+}
@@ -0,0 +1,205 @@
+// UMU progress
+// Recognizes umu-run output that reports setup work before a game starts
+
+use anyhow::{Context, Result};
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Setup step reported by umu-run or Proton
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UmuProgress {
+    /// Downloading the Steam Runtime or a Proton build
+    Downloading { item: String, percent: Option<u8> },
+    /// Unpacking a downloaded archive
+    Extracting(String),
+    /// Updating an anti-cheat runtime (BattlEye, EasyAntiCheat)
+    UpdatingAntiCheat(String),
+    /// Creating or upgrading the Wine prefix
+    PreparingPrefix,
+    /// Wine is up and the game is starting
+    Started,
+}
+
+impl fmt::Display for UmuProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UmuProgress::Downloading {
+                item,
+                percent: Some(percent),
+            } => write!(f, "Downloading {} ({}%)", item, percent),
+            UmuProgress::Downloading {
+                item,
+                percent: None,
+            } => write!(f, "Downloading {}", item),
+            UmuProgress::Extracting(item) => write!(f, "Extracting {}", item),
+            UmuProgress::UpdatingAntiCheat(name) => write!(f, "Updating {}", name),
+            UmuProgress::PreparingPrefix => write!(f, "Preparing prefix"),
+            UmuProgress::Started => write!(f, "Starting game"),
+        }
+    }
+}
+
+/// Anti-cheat runtimes Proton updates before the game starts
+const ANTI_CHEATS: [&str; 2] = ["BattlEye", "EasyAntiCheat"];
+
+/// Map a line of umu-run output to a progress step
+pub fn parse_line(line: &str) -> Option<UmuProgress> {
+    let line = line.trim();
+
+    for name in ANTI_CHEATS {
+        if line.contains(&format!("Updating {}", name)) {
+            return Some(UmuProgress::UpdatingAntiCheat(name.to_string()));
+        }
+    }
+    if let Some(rest) = text_after(line, "Downloading ") {
+        return Some(UmuProgress::Downloading {
+            item: item_name(rest)?,
+            percent: percent(line),
+        });
+    }
+    if let Some(rest) = text_after(line, "Extracting ") {
+        return Some(UmuProgress::Extracting(item_name(rest)?));
+    }
+    if line.contains("Upgrading prefix") || line.contains("Creating prefix") {
+        return Some(UmuProgress::PreparingPrefix);
+    }
+    if [
+        "fsync: up and running",
+        "esync: up and running",
+        "ntsync: up and running",
+    ]
+    .iter()
+    .any(|marker| line.contains(marker))
+    {
+        return Some(UmuProgress::Started);
+    }
+    None
+}
+
+/// Text following the first occurrence of `marker`
+fn text_after<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    line.find(marker).map(|start| &line[start + marker.len()..])
+}
+
+/// First word of `text`, without quotes and trailing punctuation
+fn item_name(text: &str) -> Option<String> {
+    let word = text.split_whitespace().next()?;
+    let name = word
+        .trim_matches(|c| c == '\'' || c == '"')
+        .trim_end_matches(['.', ':', ','])
+        .trim_matches(|c| c == '\'' || c == '"');
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Last `NN%` value of a line
+fn percent(line: &str) -> Option<u8> {
+    line.split_whitespace()
+        .rev()
+        .filter_map(|word| word.strip_suffix('%'))
+        .find_map(|value| value.parse::<f32>().ok())
+        .filter(|value| (0.0..=100.0).contains(value))
+        .map(|value| value as u8)
+}
+
+/// Reads the lines appended to a log file since the last read
+pub struct LogTail {
+    file: File,
+    /// Start of a line that hasn't been terminated yet
+    partial: String,
+}
+
+impl LogTail {
+    /// Follow a log file from its beginning
+    pub fn open(path: &Path) -> Result<Self> {
+        let file =
+            File::open(path).with_context(|| format!("Failed to open log file: {:?}", path))?;
+        Ok(Self {
+            file,
+            partial: String::new(),
+        })
+    }
+
+    /// Complete lines written since the last call
+    ///
+    /// Progress bars redraw with `\r`, so it ends a line as well.
+    pub fn read_lines(&mut self) -> Vec<String> {
+        let mut buffer = Vec::new();
+        if self.file.read_to_end(&mut buffer).is_err() || buffer.is_empty() {
+            return Vec::new();
+        }
+        self.partial.push_str(&String::from_utf8_lossy(&buffer));
+
+        let mut lines: Vec<String> = self
+            .partial
+            .split(['\n', '\r'])
+            .map(str::to_string)
+            .collect();
+        // The last piece is unterminated, keep it for the next read
+        self.partial = lines.pop().unwrap_or_default();
+        lines.retain(|line| !line.trim().is_empty());
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse_line("umu-launcher: INFO: Downloading 'GE-Proton9-20.tar.gz'..."),
+            Some(UmuProgress::Downloading {
+                item: "GE-Proton9-20.tar.gz".to_string(),
+                percent: None,
+            })
+        );
+        assert_eq!(
+            parse_line("Downloading SteamLinuxRuntime_sniper.tar.xz  42.5%"),
+            Some(UmuProgress::Downloading {
+                item: "SteamLinuxRuntime_sniper.tar.xz".to_string(),
+                percent: Some(42),
+            })
+        );
+        assert_eq!(
+            parse_line("[umu.umu_runtime] Extracting steamrt3.tar.xz -> /home/user/.local"),
+            Some(UmuProgress::Extracting("steamrt3.tar.xz".to_string()))
+        );
+        assert_eq!(
+            parse_line("Updating BattlEye..."),
+            Some(UmuProgress::UpdatingAntiCheat("BattlEye".to_string()))
+        );
+        assert_eq!(
+            parse_line("Proton: Upgrading prefix from None to 9.0-200"),
+            Some(UmuProgress::PreparingPrefix)
+        );
+        assert_eq!(
+            parse_line("fsync: up and running."),
+            Some(UmuProgress::Started)
+        );
+        assert_eq!(parse_line("wine: using kernel write watches"), None);
+        assert_eq!(parse_line("Downloading"), None);
+    }
+
+    #[test]
+    fn test_log_tail() {
+        let path =
+            std::env::temp_dir().join(format!("faugus-progress-test-{}.log", std::process::id()));
+        let mut log = File::create(&path).expect("create log");
+        let mut tail = LogTail::open(&path).expect("open log");
+
+        write!(log, "Downloading a.tar 10%\rDownloading a.tar 5").expect("write log");
+        assert_eq!(tail.read_lines(), vec!["Downloading a.tar 10%"]);
+
+        writeln!(log, "0%\n\nExtracting a.tar").expect("write log");
+        assert_eq!(
+            tail.read_lines(),
+            vec!["Downloading a.tar 50%", "Extracting a.tar"]
+        );
+        assert!(tail.read_lines().is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,236 @@
+// Wrapper chain
+// Builds the launch argv from the global and per-game wrappers
+
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+use crate::config::app_config::AppConfig;
+use crate::config::wrappers::{Wrapper, WrapperKind};
+use crate::config::Game;
+use crate::launcher::launch_args::{self, ArgsError};
+
+/// Wrappers of a game in the order they run, outermost first
+///
+/// Global wrappers wrap the game's own. GameMode and gamescope turned on
+/// through their checkboxes are added when not listed: GameMode outermost,
+/// gamescope innermost. A built-in wrapper listed twice only runs once.
+pub fn wrapper_chain(game: &Game, config: &AppConfig) -> Vec<Wrapper> {
+    let mut chain: Vec<Wrapper> = Vec::new();
+    for wrapper in config.wrappers.iter().chain(&game.wrappers) {
+        let duplicate = wrapper.kind != WrapperKind::Custom
+            && chain.iter().any(|listed| listed.kind == wrapper.kind);
+        if !duplicate {
+            chain.push(wrapper.clone());
+        }
+    }
+
+    let listed = |chain: &[Wrapper], kind| chain.iter().any(|wrapper| wrapper.kind == kind);
+    if game.gamemode && !listed(&chain, WrapperKind::GameMode) {
+        chain.insert(0, Wrapper::new(WrapperKind::GameMode));
+    }
+    if game.gamescope.enabled && !listed(&chain, WrapperKind::Gamescope) {
+        chain.push(Wrapper::new(WrapperKind::Gamescope));
+    }
+
+    chain
+}
+
+/// Arguments following a wrapper's program
+///
+/// Extra arguments are split like a shell command line, as launch arguments are.
+fn wrapper_args(wrapper: &Wrapper, game: &Game) -> Result<Vec<String>, ArgsError> {
+    let extra = launch_args::split(&wrapper.args)?;
+
+    Ok(match wrapper.kind {
+        WrapperKind::Gamescope => {
+            let mut args = game.gamescope.args(game.mangohud);
+            args.extend(extra);
+            // gamescope needs `--` before the command it runs
+            args.push("--".to_string());
+            args
+        }
+        _ => extra,
+    })
+}
+
+/// Full argv: the wrapper chain followed by `command`
+///
+/// `find` locates each wrapper's program; wrappers that aren't installed
+/// are skipped so the game still starts. Launches fail before this for
+/// missing wrappers the user listed; only the GameMode and gamescope
+/// checkboxes get here without their tool.
+pub fn build_argv(
+    chain: &[Wrapper],
+    game: &Game,
+    command: Vec<String>,
+    find: impl Fn(&Wrapper) -> Option<PathBuf>,
+) -> Result<Vec<String>, ArgsError> {
+    let mut argv = Vec::new();
+
+    for wrapper in chain {
+        match find(wrapper) {
+            Some(program) => {
+                info!("Wrapping launch with {}", wrapper.kind.label());
+                argv.push(program.to_string_lossy().to_string());
+                argv.extend(wrapper_args(wrapper, game)?);
+            }
+            None => warn!(
+                "{} wrapper not found, proceeding without it",
+                match wrapper.kind {
+                    WrapperKind::Custom => wrapper.command.clone(),
+                    kind => kind.label().to_string(),
+                }
+            ),
+        }
+    }
+
+    argv.extend(command);
+    Ok(argv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{GamescopeSettings, GamescopeWindowMode};
+
+    /// Pretend every wrapper is installed under /usr/bin
+    fn installed(wrapper: &Wrapper) -> Option<PathBuf> {
+        let name = wrapper.kind.binary().unwrap_or(wrapper.command.as_str());
+        Some(PathBuf::from("/usr/bin").join(name))
+    }
+
+    fn command() -> Vec<String> {
+        vec!["umu-run".to_string(), "/games/game.exe".to_string()]
+    }
+
+    #[test]
+    fn test_chain_order_and_toggles() {
+        let game = Game {
+            gamemode: true,
+            mangohud: true,
+            gamescope: GamescopeSettings {
+                enabled: true,
+                window_mode: GamescopeWindowMode::Fullscreen,
+                ..Default::default()
+            },
+            wrappers: vec![
+                Wrapper {
+                    kind: WrapperKind::Strace,
+                    args: "-f -o /tmp/trace".to_string(),
+                    ..Default::default()
+                },
+                // Already listed globally
+                Wrapper::new(WrapperKind::PrimeRun),
+            ],
+            ..Default::default()
+        };
+        let config = AppConfig {
+            wrappers: vec![Wrapper::new(WrapperKind::PrimeRun)],
+            ..Default::default()
+        };
+
+        let chain = wrapper_chain(&game, &config);
+        let kinds: Vec<WrapperKind> = chain.iter().map(|w| w.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                WrapperKind::GameMode,
+                WrapperKind::PrimeRun,
+                WrapperKind::Strace,
+                WrapperKind::Gamescope,
+            ]
+        );
+
+        assert_eq!(
+            build_argv(&chain, &game, command(), installed).expect("valid arguments"),
+            vec![
+                "/usr/bin/gamemoderun",
+                "/usr/bin/prime-run",
+                "/usr/bin/strace",
+                "-f",
+                "-o",
+                "/tmp/trace",
+                "/usr/bin/gamescope",
+                "-f",
+                "--mangoapp",
+                "--",
+                "umu-run",
+                "/games/game.exe",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_listed_position_and_missing_wrappers() {
+        // Listing gamescope places it explicitly, before the custom wrapper
+        let game = Game {
+            gamescope: GamescopeSettings {
+                enabled: true,
+                ..Default::default()
+            },
+            wrappers: vec![
+                Wrapper {
+                    kind: WrapperKind::Gamescope,
+                    args: "--force-grab-cursor".to_string(),
+                    ..Default::default()
+                },
+                Wrapper {
+                    kind: WrapperKind::Custom,
+                    command: "taskset".to_string(),
+                    args: "-c '0-3'".to_string(),
+                },
+            ],
+            ..Default::default()
+        };
+        let chain = wrapper_chain(&game, &AppConfig::default());
+
+        assert_eq!(
+            build_argv(&chain, &game, command(), installed).expect("valid arguments"),
+            vec![
+                "/usr/bin/gamescope",
+                "--force-grab-cursor",
+                "--",
+                "/usr/bin/taskset",
+                "-c",
+                "0-3",
+                "umu-run",
+                "/games/game.exe",
+            ]
+        );
+
+        // Nothing installed: only the game command is left
+        assert_eq!(
+            build_argv(&chain, &game, command(), |_| None).expect("valid arguments"),
+            command()
+        );
+    }
+
+    #[test]
+    fn test_quoted_wrapper_args() {
+        let game = Game {
+            wrappers: vec![Wrapper {
+                kind: WrapperKind::Custom,
+                command: "obs-gamecapture".to_string(),
+                args: r#"--output "/home/user/My Videos""#.to_string(),
+            }],
+            ..Default::default()
+        };
+        let chain = wrapper_chain(&game, &AppConfig::default());
+        assert_eq!(
+            build_argv(&chain, &game, command(), installed).expect("valid arguments")[..3],
+            [
+                "/usr/bin/obs-gamecapture",
+                "--output",
+                "/home/user/My Videos"
+            ]
+        );
+
+        let mut game = game;
+        game.wrappers[0].args = r#"--output "/home/user"#.to_string();
+        let chain = wrapper_chain(&game, &AppConfig::default());
+        assert_eq!(
+            build_argv(&chain, &game, command(), installed),
+            Err(ArgsError::UnterminatedQuote('"'))
+        );
+    }
+}
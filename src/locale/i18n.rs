@@ -130,6 +130,117 @@ impl I18n {
             ("Close on Launch", "Close on Launch"),
             ("Enable Logging", "Enable Logging"),
             ("Show Hidden Games", "Show Hidden Games"),
+            ("Statistics", "Statistics"),
+            ("Show launch command", "Show launch command"),
+            ("Get UMU-Launcher", "Get UMU-Launcher"),
+            ("Install", "Install"),
+            ("Relink executable", "Relink executable"),
+            ("Edit game", "Edit game"),
+            ("Open settings", "Open settings"),
+            ("Launch command", "Launch command"),
+            ("Copy", "Copy"),
+            ("Export as .sh", "Export as .sh"),
+            (
+                "Command copied to the clipboard",
+                "Command copied to the clipboard",
+            ),
+            ("Launch script saved to", "Launch script saved to"),
+            ("Recently played", "Recently played"),
+            ("Tags", "Tags"),
+            ("No tags", "No tags"),
+            ("New tag", "New tag"),
+            ("Edit tags", "Edit tags"),
+            ("Favorites", "Favorites"),
+            ("Add to favorites", "Add to favorites"),
+            ("Remove from favorites", "Remove from favorites"),
+            (
+                "Apply changes to all games shown",
+                "Apply changes to all games shown",
+            ),
+            ("Library", "Library"),
+            ("Playtime", "Playtime"),
+            ("Total", "Total"),
+            ("Last 7 days", "Last 7 days"),
+            ("Last 30 days", "Last 30 days"),
+            ("Sessions", "Sessions"),
+            ("Average session", "Average session"),
+            ("Last played", "Last played"),
+            ("Games played", "Games played"),
+            ("Never", "Never"),
+            ("Today", "Today"),
+            ("Yesterday", "Yesterday"),
+            ("days ago", "days ago"),
+            (
+                "Select a game to see its statistics",
+                "Select a game to see its statistics",
+            ),
+            ("Hooks", "Hooks"),
+            (
+                "Pre-launch command, e.g.: mount /mnt/games",
+                "Pre-launch command, e.g.: mount /mnt/games",
+            ),
+            (
+                "Post-exit command, e.g.: umount /mnt/games",
+                "Post-exit command, e.g.: umount /mnt/games",
+            ),
+            ("Timeout (seconds)", "Timeout (seconds)"),
+            (
+                "Seconds a stopped game gets to exit",
+                "Seconds a stopped game gets to exit",
+            ),
+            (
+                "Don't launch games sharing a prefix with a running game",
+                "Don't launch games sharing a prefix with a running game",
+            ),
+            (
+                "Don't launch if the pre-launch command fails",
+                "Don't launch if the pre-launch command fails",
+            ),
+            (
+                "Commands run for every game, before and after its own hooks",
+                "Commands run for every game, before and after its own hooks",
+            ),
+            ("Run in gamescope", "Run in gamescope"),
+            (
+                "gamescope was not found, the game will start without it",
+                "gamescope was not found, the game will start without it",
+            ),
+            ("Output resolution", "Output resolution"),
+            ("Game resolution", "Game resolution"),
+            ("Refresh rate", "Refresh rate"),
+            ("Upscaling", "Upscaling"),
+            ("Window mode", "Window mode"),
+            ("Wrappers", "Wrappers"),
+            ("Add wrapper", "Add wrapper"),
+            ("Game Logs", "Game Logs"),
+            ("Select a game", "Select a game"),
+            ("Session", "Session"),
+            (
+                "Additional application (trainer, mod loader...)",
+                "Additional application (trainer, mod loader...)",
+            ),
+            ("Program", "Program"),
+            ("Arguments", "Arguments"),
+            ("Gamescope", "Gamescope"),
+            ("prime-run", "prime-run"),
+            ("OBS game capture", "OBS game capture"),
+            ("strace", "strace"),
+            ("Custom", "Custom"),
+            ("Overrides", "Overrides"),
+            ("Effective", "Effective"),
+            ("global", "global"),
+            ("Inherit", "Inherit"),
+            ("On", "On"),
+            ("Off", "Off"),
+            ("Use Wayland driver", "Use Wayland driver"),
+            ("Enable HDR", "Enable HDR"),
+            ("Enable WOW64", "Enable WOW64"),
+            ("Use discrete GPU", "Use discrete GPU"),
+            ("Enable logging", "Enable logging"),
+            (
+                "Programs every game is run through, around the game's own wrappers",
+                "Programs every game is run through, around the game's own wrappers",
+            ),
         ];
 
         match language {
@@ -13,18 +13,25 @@ mod tray;
 
 use iced::widget::{container, mouse_area, stack, Space};
 use iced::{window, Color, Element, Length, Padding, Point, Size, Subscription, Task};
+use std::path::PathBuf;
 use tracing::{error, info, warn};
 
-use config::app_config::AppConfig;
+use config::app_config::{AppConfig, SortMode};
 use config::game_config::Game;
+use config::library_store::LibraryStore;
 use gui::add_game_dialog::{AddGameDialog, AddGameMessage};
 use gui::confirmation_dialog::ConfirmationDialog;
 use gui::context_menu::{ContextMenu, ContextMenuMessage};
+use gui::file_picker;
+use gui::launch_command_dialog::{LaunchCommandDialog, LaunchCommandMessage};
 use gui::log_viewer_dialog::{LogViewerDialog, LogViewerMessage};
 use gui::main_window::MainWindow;
 use gui::proton_manager_dialog::{ProtonManagerDialog, ProtonManagerMessage};
 use gui::settings_dialog::{SettingsDialog, SettingsMessage};
+use gui::stats_dialog::{StatsDialog, StatsMessage};
+use gui::tag_dialog::{TagDialog, TagMessage};
 use icons::IconManager;
+use launcher::game_launcher::{GameLauncher, Remedy, UMU_LAUNCHER_URL};
 use launcher::LaunchMessage;
 use locale::i18n::I18n;
 use shortcuts::DesktopShortcutManager;
@@ -43,17 +50,22 @@ pub enum Message {
     GameSelected(Option<usize>),
     GameClicked(usize),
     GameDoubleClicked(usize),
+    LaunchProfile(usize, String),
     PlayClicked,
     AddClicked,
     EditClicked,
     DeleteClicked,
     DeleteConfirmed(usize, bool),
     HideShowClicked,
+    FavoriteClicked,
     DuplicateClicked,
     KillProcessClicked,
     KillAllProcesses,
     SettingsClicked,
     SearchChanged(String),
+    SortModeChanged(SortMode),
+    ToggleTagFilter(String),
+    ToggleFavoritesFilter,
     Tick,
     ProcessExited(String),
     LaunchMessage(LaunchMessage),
@@ -78,6 +90,17 @@ pub enum Message {
     ProtonManagerDialog(ProtonManagerMessage),
     ShowProtonManagerDialog,
     CloseProtonManagerDialog,
+    // Statistics Dialog messages
+    StatsDialog(StatsMessage),
+    ShowStatsDialog,
+    CloseStatsDialog,
+    // Tag Dialog messages
+    TagDialog(TagMessage),
+    ShowTagDialog(usize),
+    CloseTagDialog,
+    // Launch Command Dialog messages
+    LaunchCommandDialog(LaunchCommandMessage),
+    ShowLaunchCommandDialog(usize),
     // System Tray messages
     TrayEvent(TrayEvent),
     // Confirmation Dialog
@@ -91,6 +114,10 @@ pub enum Message {
     MouseMoved(Point),
     CloseDialog,
     CloseErrorDialog,
+    /// Action picked in the error dialog for a game
+    ApplyRemedy(String, Remedy),
+    /// New executable picked for a game, None if cancelled
+    ExecutableRelinked(String, Option<PathBuf>),
     NoOp,
 }
 
@@ -123,8 +150,27 @@ impl FaugusLauncher {
         // Initialize i18n
         let i18n = I18n::new(config.language.clone());
 
+        // Upgrade games.json to the current schema before anything reads it
+        match LibraryStore::open().migrate(false) {
+            Ok(report) if !report.is_empty() => info!("{}", report),
+            Ok(_) => {}
+            Err(e) => error!("Failed to migrate games library: {:#}", e),
+        }
+
         // Load games
-        let games = Game::load_all().unwrap_or_default();
+        let games = match Game::load_all() {
+            Ok(games) => {
+                // Running state used to be keyed by title
+                if let Err(e) = GameLauncher::migrate_running_state(&games) {
+                    error!("Failed to migrate running games: {:#}", e);
+                }
+                games
+            }
+            Err(e) => {
+                error!("Failed to load games: {:#}", e);
+                Vec::new()
+            }
+        };
 
         info!("Loaded {} games", games.len());
 
@@ -142,7 +188,7 @@ impl FaugusLauncher {
             });
 
             // Initialize the tray
-            if let Err(e) = tray.init(main_window.i18n()) {
+            if let Err(e) = tray.init(main_window.i18n(), main_window.games()) {
                 error!("Failed to initialize system tray: {}", e);
                 None
             } else {
@@ -198,15 +244,77 @@ impl FaugusLauncher {
                 Task::none()
             }
             Message::ShowLogViewerDialog => {
-                let dialog = LogViewerDialog::new();
+                let selected = self
+                    .main_window
+                    .selected_game_index()
+                    .and_then(|index| self.main_window.games().get(index))
+                    .map(|game| game.gameid.as_str());
+                let dialog = LogViewerDialog::new(self.main_window.games(), selected);
                 self.dialog = DialogState::LogViewer(Box::new(dialog));
                 Task::none()
             }
+            Message::ShowStatsDialog => {
+                let selected = self
+                    .main_window
+                    .selected_game_index()
+                    .and_then(|index| self.main_window.games().get(index))
+                    .map(|game| game.gameid.as_str());
+                let dialog = StatsDialog::new(
+                    self.main_window.games(),
+                    self.main_window.play_stats(),
+                    selected,
+                );
+                self.dialog = DialogState::Stats(Box::new(dialog));
+                Task::none()
+            }
+            Message::ShowTagDialog(index) => {
+                if let Some(game) = self.main_window.games().get(index).cloned() {
+                    let dialog = TagDialog::new(
+                        game,
+                        self.main_window.all_tags(),
+                        self.main_window.visible_game_ids(),
+                    );
+                    self.dialog = DialogState::Tags(Box::new(dialog));
+                }
+                Task::none()
+            }
             Message::ShowProtonManagerDialog => {
                 let (dialog, task) = ProtonManagerDialog::new();
                 self.dialog = DialogState::ProtonManager(Box::new(dialog));
                 task.map(Message::ProtonManagerDialog)
             }
+            Message::ApplyRemedy(gameid, remedy) => {
+                let _ = self.main_window.update(Message::CloseErrorDialog);
+                let index = self
+                    .main_window
+                    .games()
+                    .iter()
+                    .position(|game| game.gameid == gameid);
+
+                match remedy {
+                    Remedy::InstallUmu => {
+                        if let Err(e) = open::that(UMU_LAUNCHER_URL) {
+                            error!("Failed to open {}: {}", UMU_LAUNCHER_URL, e);
+                        }
+                        Task::none()
+                    }
+                    Remedy::InstallRunner(runner) => {
+                        let (dialog, task) = ProtonManagerDialog::with_runner(&runner);
+                        self.dialog = DialogState::ProtonManager(Box::new(dialog));
+                        task.map(Message::ProtonManagerDialog)
+                    }
+                    Remedy::RelinkExecutable => {
+                        Task::perform(file_picker::pick_file(), move |path| {
+                            Message::ExecutableRelinked(gameid.clone(), path)
+                        })
+                    }
+                    Remedy::EditGame => match index {
+                        Some(index) => Task::done(Message::ShowEditGameDialog(index)),
+                        None => Task::none(),
+                    },
+                    Remedy::OpenSettings => Task::done(Message::ShowSettingsDialog),
+                }
+            }
             Message::ShowConfirmationDialog(dialog) => {
                 self.dialog = DialogState::Confirmation(dialog);
                 Task::none()
@@ -267,6 +375,8 @@ impl FaugusLauncher {
             | Message::CloseSettingsDialog
             | Message::CloseLogViewerDialog
             | Message::CloseProtonManagerDialog
+            | Message::CloseStatsDialog
+            | Message::CloseTagDialog
             | Message::CloseContextMenu => {
                 self.dialog = DialogState::None;
                 Task::none()
@@ -283,6 +393,8 @@ impl FaugusLauncher {
                         self.mouse_position,
                         game.title.clone(),
                         game.hidden,
+                        game.favorite,
+                        game.profile_names(),
                     );
                     self.dialog = DialogState::ContextMenu(Box::new(menu));
                 }
@@ -297,6 +409,9 @@ impl FaugusLauncher {
                         ContextMenuMessage::Play => {
                             return Task::done(Message::GameDoubleClicked(game_index));
                         }
+                        ContextMenuMessage::PlayProfile(profile) => {
+                            return Task::done(Message::LaunchProfile(game_index, profile));
+                        }
                         ContextMenuMessage::Edit => {
                             return Task::done(Message::ShowEditGameDialog(game_index));
                         }
@@ -321,6 +436,15 @@ impl FaugusLauncher {
                                 .update(Message::GameSelected(Some(game_index)));
                             return Task::done(Message::HideShowClicked);
                         }
+                        ContextMenuMessage::ToggleFavorite => {
+                            let _ = self
+                                .main_window
+                                .update(Message::GameSelected(Some(game_index)));
+                            return Task::done(Message::FavoriteClicked);
+                        }
+                        ContextMenuMessage::EditTags => {
+                            return Task::done(Message::ShowTagDialog(game_index));
+                        }
                         ContextMenuMessage::OpenLocation => {
                             if let Some(game) = self.main_window.games().get(game_index) {
                                 let path = std::path::Path::new(&game.path);
@@ -335,8 +459,20 @@ impl FaugusLauncher {
                             }
                         }
                         ContextMenuMessage::ShowLogs => {
+                            let _ = self
+                                .main_window
+                                .update(Message::GameSelected(Some(game_index)));
                             return Task::done(Message::ShowLogViewerDialog);
                         }
+                        ContextMenuMessage::ShowStats => {
+                            let _ = self
+                                .main_window
+                                .update(Message::GameSelected(Some(game_index)));
+                            return Task::done(Message::ShowStatsDialog);
+                        }
+                        ContextMenuMessage::ShowLaunchCommand => {
+                            return Task::done(Message::ShowLaunchCommandDialog(game_index));
+                        }
                     }
                 }
                 Task::none()
@@ -488,6 +624,9 @@ impl FaugusLauncher {
                     DialogState::LogViewer(_) => false,
                     DialogState::ProtonManager(_) => false,
                     DialogState::ContextMenu(_) => false,
+                    DialogState::Stats(_) => false,
+                    DialogState::Tags(_) => false,
+                    DialogState::LaunchCommand(_) => false,
                 };
 
                 if should_close {
@@ -554,6 +693,9 @@ impl FaugusLauncher {
                     DialogState::LogViewer(_) => false,
                     DialogState::ProtonManager(_) => false,
                     DialogState::ContextMenu(_) => false,
+                    DialogState::Stats(_) => false,
+                    DialogState::Tags(_) => false,
+                    DialogState::LaunchCommand(_) => false,
                 };
 
                 if should_close {
@@ -578,6 +720,9 @@ impl FaugusLauncher {
                     DialogState::Confirmation(_) => false,
                     DialogState::ProtonManager(_) => false,
                     DialogState::ContextMenu(_) => false,
+                    DialogState::Stats(_) => false,
+                    DialogState::Tags(_) => false,
+                    DialogState::LaunchCommand(_) => false,
                 };
 
                 if should_close {
@@ -601,6 +746,53 @@ impl FaugusLauncher {
                     DialogState::Confirmation(_) => false,
                     DialogState::LogViewer(_) => false,
                     DialogState::ContextMenu(_) => false,
+                    DialogState::Stats(_) => false,
+                    DialogState::Tags(_) => false,
+                    DialogState::LaunchCommand(_) => false,
+                };
+
+                if should_close {
+                    self.dialog = DialogState::None;
+                }
+
+                Task::none()
+            }
+            Message::ShowLaunchCommandDialog(index) => {
+                if let Some(game) = self.main_window.games().get(index) {
+                    let dialog = LaunchCommandDialog::new(game.clone());
+                    self.dialog = DialogState::LaunchCommand(Box::new(dialog));
+                }
+                Task::none()
+            }
+            Message::LaunchCommandDialog(msg) => {
+                if let DialogState::LaunchCommand(dialog) = &mut self.dialog {
+                    if let LaunchCommandMessage::Close = msg {
+                        self.dialog = DialogState::None;
+                    } else {
+                        return dialog.update(msg).map(Message::LaunchCommandDialog);
+                    }
+                }
+                Task::none()
+            }
+            Message::StatsDialog(msg) => {
+                // Handle statistics dialog messages
+                let should_close = match &mut self.dialog {
+                    DialogState::Stats(dialog) => match &msg {
+                        StatsMessage::Close => true,
+                        _ => {
+                            dialog.update(msg);
+                            false
+                        }
+                    },
+                    DialogState::None => false,
+                    DialogState::AddGame(_) => false,
+                    DialogState::Settings(_) => false,
+                    DialogState::Confirmation(_) => false,
+                    DialogState::LogViewer(_) => false,
+                    DialogState::ProtonManager(_) => false,
+                    DialogState::ContextMenu(_) => false,
+                    DialogState::Tags(_) => false,
+                    DialogState::LaunchCommand(_) => false,
                 };
 
                 if should_close {
@@ -609,6 +801,63 @@ impl FaugusLauncher {
 
                 Task::none()
             }
+            Message::TagDialog(msg) => {
+                // Handle tag dialog messages
+                let should_close = match &mut self.dialog {
+                    DialogState::Tags(dialog) => match &msg {
+                        TagMessage::Confirm => {
+                            let changes = dialog.changes();
+                            if let Err(e) =
+                                Game::retag(&changes.gameids, &changes.add, &changes.remove)
+                            {
+                                error!("Failed to update tags: {}", e);
+                            } else {
+                                info!(
+                                    "Tags updated for {} game(s): +{:?} -{:?}",
+                                    changes.gameids.len(),
+                                    changes.add,
+                                    changes.remove
+                                );
+                                self.main_window.reload_games();
+                            }
+                            true
+                        }
+                        TagMessage::Cancel => true,
+                        _ => {
+                            dialog.update(msg);
+                            false
+                        }
+                    },
+                    DialogState::None => false,
+                    DialogState::AddGame(_) => false,
+                    DialogState::Settings(_) => false,
+                    DialogState::Confirmation(_) => false,
+                    DialogState::LogViewer(_) => false,
+                    DialogState::ProtonManager(_) => false,
+                    DialogState::ContextMenu(_) => false,
+                    DialogState::Stats(_) => false,
+                    DialogState::LaunchCommand(_) => false,
+                };
+
+                if should_close {
+                    self.dialog = DialogState::None;
+                }
+
+                Task::none()
+            }
+            Message::FavoriteClicked => {
+                // Toggle favorite state for selected game
+                if let Some(index) = self.main_window.selected_game_index() {
+                    if let Some(game) = self.main_window.games().get(index).cloned() {
+                        if let Err(e) = game.update_favorite(!game.favorite) {
+                            error!("Failed to update game favorite state: {}", e);
+                        } else {
+                            self.main_window.reload_games();
+                        }
+                    }
+                }
+                Task::none()
+            }
             Message::HideShowClicked => {
                 // Toggle hidden state for selected game
                 if let Some(index) = self.main_window.selected_game_index() {
@@ -680,10 +929,19 @@ impl FaugusLauncher {
                 }
                 Task::none()
             }
-            Message::KillAllProcesses => {
-                info!("Killing all game processes");
-                self.main_window.launch_controller().terminate_all();
-                Task::none()
+            Message::Tick => {
+                let mut tasks = Vec::new();
+                if let Some(tray) = &mut self.system_tray {
+                    tray.sync_games(self.main_window.i18n(), self.main_window.games());
+                    tray.set_status(self.main_window.launch_progress_summary());
+                    tasks.extend(
+                        tray.poll_events()
+                            .into_iter()
+                            .map(|event| Task::done(Message::TrayEvent(event))),
+                    );
+                }
+                tasks.push(self.main_window.update(Message::Tick));
+                Task::batch(tasks)
             }
             Message::TrayEvent(event) => {
                 // Handle system tray events
@@ -707,6 +965,22 @@ impl FaugusLauncher {
                         info!("Quit requested from tray");
                         std::process::exit(0);
                     }
+                    TrayEvent::LaunchGame { gameid, profile } => {
+                        let index = self
+                            .main_window
+                            .games()
+                            .iter()
+                            .position(|g| g.gameid == gameid);
+                        match (index, profile) {
+                            (Some(index), Some(profile)) => {
+                                return Task::done(Message::LaunchProfile(index, profile));
+                            }
+                            (Some(index), None) => {
+                                return Task::done(Message::GameDoubleClicked(index));
+                            }
+                            (None, _) => warn!("Tray launch for unknown game: {}", gameid),
+                        }
+                    }
                     TrayEvent::TrayIconClicked | TrayEvent::TrayIconDoubleClicked => {
                         // Toggle window visibility
                         // For now, just show the window
@@ -790,6 +1064,37 @@ impl FaugusLauncher {
             .padding(20)
             .into(),
 
+            DialogState::Stats(dialog) => container(
+                dialog
+                    .view(self.main_window.i18n())
+                    .map(Message::StatsDialog),
+            )
+            .width(Length::Fixed(900.0))
+            .max_height(700.0)
+            .padding(20)
+            .style(container::bordered_box)
+            .into(),
+
+            DialogState::Tags(dialog) => {
+                container(dialog.view(self.main_window.i18n()).map(Message::TagDialog))
+                    .width(Length::Fixed(500.0))
+                    .max_height(700.0)
+                    .padding(20)
+                    .style(container::bordered_box)
+                    .into()
+            }
+
+            DialogState::LaunchCommand(dialog) => container(
+                dialog
+                    .view(self.main_window.i18n())
+                    .map(Message::LaunchCommandDialog),
+            )
+            .width(Length::Fixed(900.0))
+            .height(Length::Fixed(600.0))
+            .padding(20)
+            .style(container::bordered_box)
+            .into(),
+
             DialogState::Confirmation(dialog) => dialog.view(self.main_window.i18n()),
         };
 
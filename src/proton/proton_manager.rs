@@ -60,6 +60,14 @@ pub const PROTON_CONFIGS: &[ProtonConfig] = &[
     },
 ];
 
+/// Index of the config in PROTON_CONFIGS a runner is installed from
+pub fn config_for_runner(name: &str) -> Option<usize> {
+    let name = name.to_lowercase();
+    PROTON_CONFIGS
+        .iter()
+        .position(|config| name.starts_with(&config.label.to_lowercase()))
+}
+
 /// Proton manager
 #[derive(Clone, Debug)]
 pub struct ProtonManager {
@@ -174,7 +182,7 @@ impl ProtonManager {
     /// Get all available runners (placeholders + installed)
     pub fn get_available_runners(&self) -> Vec<String> {
         use crate::proton::runner_resolver::{
-            GE_PROTON_LATEST, PROTON_EM_LATEST, UMU_PROTON_LATEST,
+            GE_PROTON_LATEST, LINUX_NATIVE, PROTON_EM_LATEST, UMU_PROTON_LATEST,
         };
 
         let mut runners = vec![
@@ -193,6 +201,7 @@ impl ProtonManager {
 
         let sorted_installed = sort_versions_descending(installed);
         runners.extend(sorted_installed);
+        runners.push(LINUX_NATIVE.to_string());
 
         runners
     }
@@ -469,6 +478,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_config_for_runner() {
+        assert_eq!(config_for_runner("GE-Proton9-20"), Some(0));
+        assert_eq!(config_for_runner("GE-Proton Latest (default)"), Some(0));
+        assert_eq!(config_for_runner("proton-EM-10.0-3"), Some(1));
+        assert_eq!(config_for_runner("Proton-CachyOS"), None);
+        assert_eq!(config_for_runner("UMU-Proton Latest"), None);
+    }
+
     #[test]
     fn test_get_installed_versions() {
         let manager = ProtonManager::new();
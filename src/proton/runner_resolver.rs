@@ -5,6 +5,13 @@ use thiserror::Error;
 pub const UMU_PROTON_LATEST: &str = "UMU-Proton Latest";
 pub const GE_PROTON_LATEST: &str = "GE-Proton Latest (default)";
 pub const PROTON_EM_LATEST: &str = "Proton-EM Latest";
+/// Runs Linux executables (scripts, AppImages, ELF binaries) without Proton
+pub const LINUX_NATIVE: &str = "Linux-Native";
+
+/// Check if a runner runs games natively, without Proton or a prefix
+pub fn is_native(name: &str) -> bool {
+    name == LINUX_NATIVE
+}
 
 #[derive(Error, Debug)]
 pub enum RunnerError {
@@ -19,7 +26,7 @@ pub enum RunnerError {
 /// Resolves a display name to a PROTONPATH value.
 pub fn resolve_runner(name: &str) -> Result<String, RunnerError> {
     match name {
-        UMU_PROTON_LATEST => Ok(String::new()),
+        UMU_PROTON_LATEST | LINUX_NATIVE => Ok(String::new()),
         GE_PROTON_LATEST => Ok("Proton-GE Latest".to_string()),
         PROTON_EM_LATEST => Ok("Proton-EM Latest".to_string()),
         _ => {
@@ -40,7 +47,7 @@ pub fn resolve_runner(name: &str) -> Result<String, RunnerError> {
 pub fn validate_runner(name: &str) -> Result<(), RunnerError> {
     let resolved = resolve_runner(name)?;
     if resolved.is_empty() {
-        return Ok(()); // UMU-Proton Latest is handled by umu-run auto
+        return Ok(()); // UMU-Proton Latest is handled by umu-run auto, native needs no Proton
     }
 
     if resolved.starts_with('/') {
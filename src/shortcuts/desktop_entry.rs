@@ -294,6 +294,18 @@ impl DesktopShortcutManager {
         Ok(())
     }
 
+    /// Check if a game has a shortcut on the desktop
+    pub fn exists_on_desktop(game: &Game) -> bool {
+        let filename = format!("{}.desktop", format_title(&game.title));
+        Paths::desktop_dir().join(filename).exists()
+    }
+
+    /// Check if a game has an applications menu shortcut
+    pub fn exists_in_applications(game: &Game) -> bool {
+        let filename = format!("{}.desktop", format_title(&game.title));
+        Paths::applications_dir().join(filename).exists()
+    }
+
     /// Check if shortcuts exist for a game
     pub fn exists(game: &Game) -> bool {
         let formatted_title = format_title(&game.title);
@@ -2,11 +2,17 @@
 // Manages the right-click context menu for the system tray icon
 
 use anyhow::Result;
-use muda::Menu;
+use muda::{Menu, Submenu};
 use tracing::info;
 
+use crate::config::Game;
 use crate::locale::I18n;
 
+use super::TrayEvent;
+
+/// Prefix of menu item IDs that launch a game ("play:<gameid>[:<profile>]")
+const PLAY_PREFIX: &str = "play:";
+
 /// Tray menu manager
 pub struct TrayMenu {
     menu: Menu,
@@ -16,7 +22,7 @@ pub struct TrayMenu {
 
 impl TrayMenu {
     /// Create a new tray menu
-    pub fn new(i18n: &I18n) -> Result<Self> {
+    pub fn new(i18n: &I18n, games: &[Game]) -> Result<Self> {
         let menu = Menu::new();
 
         let show_item = muda::MenuItem::with_id("show", i18n.t("Show"), true, None);
@@ -26,6 +32,12 @@ impl TrayMenu {
         menu.append(&hide_item)?;
         menu.append(&muda::PredefinedMenuItem::separator())?;
 
+        let visible: Vec<&Game> = games.iter().filter(|g| !g.hidden).collect();
+        if !visible.is_empty() {
+            menu.append(&Self::play_submenu(i18n, &visible)?)?;
+            menu.append(&muda::PredefinedMenuItem::separator())?;
+        }
+
         let _quit_item = muda::MenuItem::with_id("quit", i18n.t("Quit"), true, None);
         menu.append(&_quit_item)?;
 
@@ -38,6 +50,64 @@ impl TrayMenu {
         })
     }
 
+    /// Build the "Play" submenu; games with profiles get one entry per profile
+    fn play_submenu(i18n: &I18n, games: &[&Game]) -> Result<Submenu> {
+        let play = Submenu::new(i18n.t("Play"), true);
+
+        for game in games {
+            let default_id = format!("{}{}", PLAY_PREFIX, game.gameid);
+
+            if game.profiles.is_empty() {
+                play.append(&muda::MenuItem::with_id(
+                    default_id,
+                    &game.title,
+                    true,
+                    None,
+                ))?;
+                continue;
+            }
+
+            let game_menu = Submenu::new(&game.title, true);
+            game_menu.append(&muda::MenuItem::with_id(
+                default_id,
+                i18n.t("Default"),
+                true,
+                None,
+            ))?;
+            for profile in &game.profiles {
+                game_menu.append(&muda::MenuItem::with_id(
+                    format!("{}{}:{}", PLAY_PREFIX, game.gameid, profile.name),
+                    &profile.name,
+                    true,
+                    None,
+                ))?;
+            }
+            play.append(&game_menu)?;
+        }
+
+        Ok(play)
+    }
+
+    /// Map a menu item ID to the tray event it triggers
+    pub fn event_for_id(id: &str) -> Option<TrayEvent> {
+        match id {
+            "show" => Some(TrayEvent::Show),
+            "hide" => Some(TrayEvent::Hide),
+            "quit" => Some(TrayEvent::Quit),
+            _ => {
+                let rest = id.strip_prefix(PLAY_PREFIX)?;
+                let (gameid, profile) = match rest.split_once(':') {
+                    Some((gameid, profile)) => (gameid, Some(profile.to_string())),
+                    None => (rest, None),
+                };
+                Some(TrayEvent::LaunchGame {
+                    gameid: gameid.to_string(),
+                    profile,
+                })
+            }
+        }
+    }
+
     /// Get the menu
     pub fn menu(&self) -> &Menu {
         &self.menu
@@ -49,3 +119,34 @@ impl TrayMenu {
         self.hide_item.set_enabled(visible);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_for_id() {
+        assert!(matches!(
+            TrayMenu::event_for_id("quit"),
+            Some(TrayEvent::Quit)
+        ));
+        assert!(TrayMenu::event_for_id("unknown").is_none());
+
+        match TrayMenu::event_for_id("play:my-game") {
+            Some(TrayEvent::LaunchGame { gameid, profile }) => {
+                assert_eq!(gameid, "my-game");
+                assert_eq!(profile, None);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        // Profile names may contain the separator themselves
+        match TrayMenu::event_for_id("play:my-game:DX11: safe") {
+            Some(TrayEvent::LaunchGame { gameid, profile }) => {
+                assert_eq!(gameid, "my-game");
+                assert_eq!(profile.as_deref(), Some("DX11: safe"));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+}
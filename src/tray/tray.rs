@@ -3,11 +3,13 @@
 
 use anyhow::{Context, Result};
 use std::sync::mpsc;
-use tracing::info;
+use tracing::{info, warn};
 
 use super::icon::TrayIcon;
 use super::menu::TrayMenu;
 use super::TrayConfig;
+use crate::config::Game;
+use crate::locale::I18n;
 
 /// Events from the system tray
 #[derive(Debug, Clone)]
@@ -22,8 +24,16 @@ pub enum TrayEvent {
     TrayIconClicked,
     /// Tray icon double-clicked
     TrayIconDoubleClicked,
+    /// Launch a game, optionally with a named profile
+    LaunchGame {
+        gameid: String,
+        profile: Option<String>,
+    },
 }
 
+/// Games as shown in the tray menu: (gameid, title, profile names)
+type MenuGames = Vec<(String, String, Vec<String>)>;
+
 /// Messages for tray communication
 /// TODO: Implement tray event handling (UI integration)
 #[allow(dead_code)]
@@ -49,6 +59,10 @@ pub struct SystemTray {
     #[allow(dead_code)]
     event_tx: Option<mpsc::Sender<TrayEvent>>,
     window_visible: bool,
+    /// Games the current menu was built from
+    menu_games: MenuGames,
+    /// Launch progress shown in the tooltip
+    status: Option<String>,
 }
 
 impl SystemTray {
@@ -61,11 +75,13 @@ impl SystemTray {
             tray_icon: None,
             event_tx: None,
             window_visible: true,
+            menu_games: Vec::new(),
+            status: None,
         }
     }
 
     /// Initialize the system tray
-    pub fn init(&mut self, i18n: &crate::locale::I18n) -> Result<()> {
+    pub fn init(&mut self, i18n: &I18n, games: &[Game]) -> Result<()> {
         if !self.config.enabled {
             info!("System tray disabled in config");
             return Ok(());
@@ -78,7 +94,8 @@ impl SystemTray {
         )?);
 
         // Create menu
-        self.menu = Some(TrayMenu::new(i18n)?);
+        self.menu = Some(TrayMenu::new(i18n, games)?);
+        self.menu_games = Self::menu_games(games);
 
         // Create tray icon
         let tray_icon = self.create_tray_icon()?;
@@ -107,6 +124,52 @@ impl SystemTray {
         Ok(tray_icon)
     }
 
+    /// Summary of the games that affect the tray menu
+    fn menu_games(games: &[Game]) -> MenuGames {
+        games
+            .iter()
+            .filter(|g| !g.hidden)
+            .map(|g| (g.gameid.clone(), g.title.clone(), g.profile_names()))
+            .collect()
+    }
+
+    /// Rebuild the menu if games, titles or profiles changed
+    pub fn sync_games(&mut self, i18n: &I18n, games: &[Game]) {
+        let Some(tray_icon) = &self.tray_icon else {
+            return;
+        };
+
+        let menu_games = Self::menu_games(games);
+        if menu_games == self.menu_games {
+            return;
+        }
+
+        match TrayMenu::new(i18n, games) {
+            Ok(menu) => {
+                menu.set_window_visible(self.window_visible);
+                tray_icon.set_menu(Some(Box::new(menu.menu().clone())));
+                self.menu = Some(menu);
+                self.menu_games = menu_games;
+            }
+            Err(e) => warn!("Failed to rebuild tray menu: {}", e),
+        }
+    }
+
+    /// Drain pending menu clicks
+    pub fn poll_events(&self) -> Vec<TrayEvent> {
+        if self.tray_icon.is_none() {
+            return Vec::new();
+        }
+
+        let mut events = Vec::new();
+        while let Ok(event) = muda::MenuEvent::receiver().try_recv() {
+            if let Some(event) = TrayMenu::event_for_id(event.id.as_ref()) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
     /// Set the event sender for tray events
     /// TODO: Use for UI-tray communication channel
     #[allow(dead_code)]
@@ -146,10 +209,10 @@ impl SystemTray {
     /// Update the tray icon tooltip based on window state
     fn update_tooltip(&self) -> Option<String> {
         if let Some(tray_icon) = &self.tray_icon {
-            let tooltip = if self.window_visible {
-                "Faugus Launcher - Visible".to_string()
-            } else {
-                "Faugus Launcher - Hidden".to_string()
+            let tooltip = match (&self.status, self.window_visible) {
+                (Some(status), _) => format!("Faugus Launcher - {}", status),
+                (None, true) => "Faugus Launcher - Visible".to_string(),
+                (None, false) => "Faugus Launcher - Hidden".to_string(),
             };
             let _ = tray_icon.set_tooltip(Some(&tooltip));
             Some(tooltip)
@@ -184,6 +247,14 @@ impl SystemTray {
         info!("System tray cleaned up");
     }
 
+    /// Show launch progress in the tooltip, or clear it
+    pub fn set_status(&mut self, status: Option<String>) {
+        if status != self.status {
+            self.status = status;
+            let _ = self.update_tooltip();
+        }
+    }
+
     /// Set window visibility state
     pub fn set_window_visible(&mut self, visible: bool) {
         self.window_visible = visible;